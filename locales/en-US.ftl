@@ -4,6 +4,8 @@ menu-language = Language
 settings-title = Settings
 tab-timers = Timers & Screens
 tab-system = System Settings
+tab-sharing = Sharing
+tab-stats = Statistics
 timers-mode = Mode
 mode-static = Static
 mode-interval = Interval
@@ -15,6 +17,9 @@ screen-edit = Screen editor
 field-title = Title
 field-subtitle = Subtitle
 field-color = Color
+field-sound = Sound
+field-sound-tooltip = WAV/OGG file played when switching to this screen; overrides the transition sounds
+field-notify = Show a desktop notification when switching to this screen
 timers-params = Time parameters
 btn-add-interval = Add interval
 col-screen = Screen
@@ -31,21 +36,160 @@ system-autostart = Autostart with system
 system-sounds = Sound notifications
 system-window-pos = Window position on screen
 system-language-desc = Choose the application interface language
+system-language-dir = More languages: put <tag>.ftl files, for example de.ftl, into { $dir }
+system-language-refresh = Reload languages from the folder
+system-language-fallback = { $requested } is not available, showing { $language }
+system-language-errors = Translation problems: { $count }
 system-autostart-desc = Automatically start the application on system startup
+autostart-method-xdg = Desktop session entry (~/.config/autostart)
+autostart-method-systemd = systemd user service
+autostart-unsupported = Autostart is not supported on this platform yet
+autostart-repaired = The autostart entry pointed to an old program location and was updated
+autostart-foreign = Autostart launches another copy of the program ({ $path }) and was left unchanged. Turn autostart off and on to launch this copy instead
+autostart-error = Could not change autostart: { $error }
 system-sounds-desc = Play sounds when switching screens
+sound-volume = Volume
+sound-chime = Built-in chime
+sound-test = ▶ Test
+sound-files-desc = Custom WAV/OGG files per transition (leave empty for the built-in chime):
+sound-interval-start = Interval start
+sound-interval-end = Interval end
+sound-step = Cycle step
+sound-no-device = No audio device found, sounds are muted
+sound-error = Could not play sound: { $error }
+system-notifications = Desktop notifications
+system-notifications-desc = Show a system notification on every transition, useful when the window is covered or minimized. Can be turned off per screen in the screen editor
+notifications-error = Could not show a notification: { $error }
+system-warnings = Warnings before transitions
+system-warnings-desc = Give a heads-up a few minutes before the screen changes. Intervals can set their own lead time
+warning-lead = Minutes before:
+warning-banner-option = Banner on the main screen
+warning-sound-option = Sound
+warning-notification-option = Desktop notification
+warning-pulse-option = Pulse the background color
+chime-soft = Soft
+chime-bell = Bell
+chime-rising = Rising
 system-window-pos-desc = Remember window position on screen
+system-config-file = Configuration file
+system-config-file-desc = Changes made in a text editor are applied automatically
+config-parse-error = config.json could not be read. The previous settings stay active and the file is not overwritten until it is fixed.
+config-invalid = config.json was applied, but the intervals have problems
+config-reload = Reload now
+window-pos-current = Current position: { $x }, { $y }
+system-pause-mode = Pause behaviour
+system-pause-mode-desc = What happens to the schedule while the timer is paused
+pause-mode-shift = Shift the current and remaining steps, keep the interval end
+pause-mode-extend = Shift the steps and extend the interval by the paused time
+system-extend-minutes = Step extension
+system-extend-minutes-desc = How many minutes the "+ min" button adds to the current cycle step
 btn-save = Save
 btn-cancel = Cancel
 settings-saved = Settings saved
 
+# Config recovery
+recovery-title = Settings could not be loaded
+recovery-desc = config.json contains an error. Fix it in a text editor and press "Try again", or start over with default settings.
+recovery-backup = A copy of the broken file was saved to { $path }
+recovery-no-backup = A copy of the broken file could not be saved
+recovery-retry = Try again
+recovery-defaults = Use default settings
+
+# Sharing schedules
+sharing-export = Export
+sharing-export-desc = Save the selected screens and intervals of the active profile to a file. Screens used by the selected intervals are included automatically. Custom sound files of screens are not saved.
+sharing-screens = Screens
+sharing-intervals = Intervals
+sharing-file = File:
+sharing-format-hint = The format is chosen by the extension: .json or .toml
+sharing-export-button = Export
+sharing-exported = Saved to { $path }
+sharing-import = Import
+sharing-import-desc = Merge screens and intervals from a file into the current settings. Busy ids get new values.
+sharing-preview = Preview
+sharing-preview-title = Changes after import (intervals are added to the profile "{ $profile }"):
+sharing-item-add = { $name } — will be added (id { $id })
+sharing-item-remap = { $name } — will be added with id { $id } instead of { $from }
+sharing-item-existing = { $name } — already present (id { $id })
+sharing-item-missing = { $name } — skipped: screen { $screen } is not in the file
+sharing-new-issues = After import the schedule will have problems:
+sharing-nothing-to-import = Nothing new to import
+sharing-apply = Import
+sharing-cancel = Cancel
+sharing-imported = Import completed
+sharing-ics = Calendar (.ics)
+sharing-ics-desc = Save the schedule of the active profile as an iCalendar file for calendar apps. Repeating intervals become recurring events.
+sharing-ics-from = Starting from:
+sharing-ics-day = One day
+sharing-ics-week = A week
+sharing-ics-steps = Add cycle steps as separate events
+sharing-busy = Busy time from a calendar
+sharing-busy-desc = Turn meetings from an .ics file into intervals of the active profile and fill the gaps between them with a cycle. Importing the same file again replaces the intervals created last time; intervals made by hand are kept.
+sharing-busy-screen = Meeting screen:
+sharing-busy-new-screen = Create a new screen
+sharing-busy-meeting-title = Meeting
+sharing-busy-filler = Fill gaps with:
+sharing-busy-no-filler = Do not fill
+sharing-busy-hours = Working hours:
+sharing-busy-import = Import
+sharing-busy-refresh = Refresh
+sharing-busy-remove = Remove imported
+sharing-busy-imported = Meetings: { $meetings }. Intervals added: { $added }, replaced: { $removed }, events skipped: { $skipped }
+sharing-busy-removed = Intervals removed: { $removed }
+sharing-busy-time-zones = Unknown time zones: { $zones }. Times in them were read as local time, check the imported meetings
+sharing-error = Error: { $error }
+
+# Statistics
+stats-desc = Time spent on each screen, from the session history. Pauses are not counted.
+stats-log = History file: { $path }
+stats-broken-lines = Unreadable lines in the history: { $count }
+stats-today = Today
+stats-day = Day { $date }
+stats-week = Week { $from } – { $to }
+stats-empty = No records
+stats-cycles = Completed cycles: { $count }
+stats-streaks = Streaks
+stats-streaks-desc = A day counts when at least one cycle was completed without skipping its last step.
+stats-streak-current = Current streak: { $days } { $days ->
+    [one] day
+   *[other] days
+}
+stats-streak-longest = Longest streak: { $days } { $days ->
+    [one] day
+   *[other] days
+}
+stats-export = Export for timesheets
+stats-export-desc = Save the history for a period as CSV or JSON (chosen by the file extension). Time is counted without pauses.
+stats-export-from = From:
+stats-export-to = To:
+stats-export-group = Rows:
+stats-group-sessions = Every session
+stats-group-interval = Per interval and day
+stats-group-screen = Per screen and day
+stats-export-round = Round up to, minutes:
+stats-export-round-hint = 0 rounds to the nearest minute
+stats-export-button = Export
+stats-exported = History saved to { $path }
+
 # Main interface
 main-no-screens = No configured screens
 main-no-screens-hint = Open settings to create screens and intervals
 interval-label = Interval: { $name }
 next-transition = Next transition in: { $time }
+notify-body = { $interval } · next transition at { $time }
 timer-pause = ⏸ Pause
 timer-continue = ▶ Continue
 timer-refresh = 🔄 Refresh
+timer-paused = ⏸ Paused — the remaining steps are shifted
+timer-skip = Skip to the next step
+timer-extend = +{ $minutes } min
+timer-extend-tooltip = Extend the current step, the remaining steps move later
+timer-restart = Restart the cycle from the first step
+warning-banner = { $screen } in { $minutes } { $minutes ->
+    [one] minute
+   *[other] minutes
+}
+warning-notify-body = The screen changes at { $time }
 
 # Screen management
 screens-title = Screens
@@ -57,6 +201,20 @@ screen-edit-tooltip = Edit screen
 screen-new-title = New screen
 screen-default-title = New screen
 
+# Profiles
+profiles-title = Profiles
+profiles-description = Each profile has its own intervals and default screen; screens are shared
+profile-active = Profile:
+profile-add = ➕ New
+profile-duplicate = Duplicate
+profile-delete = 🗑 Delete
+profile-name = Name:
+profile-default-screen = Screen outside intervals:
+profile-default-screen-first = First screen
+profile-auto-days = Switch to this profile automatically on:
+profile-new-name = New profile
+profile-switch = Switch profile
+
 # Interval management
 intervals-title = Time intervals
 intervals-description = Each interval has its own working time and screen display mode
@@ -64,9 +222,15 @@ intervals-none = No intervals created
 intervals-create = ➕ Create new interval
 interval-delete-tooltip = Delete interval
 interval-edit-tooltip = Edit interval
+interval-imported = Imported from the calendar { $source }
+interval-imported-edit-hint = This interval was imported from a calendar. After saving it becomes a regular interval and is no longer replaced on refresh.
 interval-new-title = New interval
 interval-static-mode = (static)
-interval-cycle-mode = (cycle of { $steps } steps)
+interval-cycle-mode = (cycle of { $steps } { $steps ->
+    [one] step
+   *[other] steps
+})
+interval-crosses-midnight = (overnight)
 
 # Validation and schedule
 validation-title = Settings validation
@@ -84,6 +248,19 @@ interval-time-work = Working time
 interval-time-from = From
 interval-time-to = to
 interval-duration-format = Duration: {$hours} h {$minutes} min
+interval-crosses-midnight-hint = End is earlier than start: the interval continues past midnight into the next day
+interval-repeat = Repeat
+recurrence-daily = Every day
+recurrence-weekdays = Weekdays (Mon–Fri)
+recurrence-weekends = Weekends (Sat, Sun)
+recurrence-days = Selected days of the week
+recurrence-date-range = Date range
+recurrence-dates = Specific dates
+recurrence-from = From
+recurrence-to = to
+recurrence-add-date = ➕ Add date
+interval-warning-own = Own warning time
+interval-warning-own-tooltip = Overrides the global warning setting for this interval; 0 turns warnings off
 interval-mode-work = Interval working mode
 interval-mode-static-radio = Static
 interval-mode-static-tooltip = Shows one screen for the entire interval
@@ -95,10 +272,20 @@ interval-steps-configure = Configure screen sequence:
 interval-step-number = Step {$number}
 interval-step-screen-label = Screen:
 interval-step-duration-label = Duration:
+interval-step-minutes = min
 interval-step-choose = Select
 interval-step-add = ➕ Add step
 interval-cycle-duration = Total cycle duration: {$minutes} min
 
+# Weekdays
+weekday-mon = Mon
+weekday-tue = Tue
+weekday-wed = Wed
+weekday-thu = Thu
+weekday-fri = Fri
+weekday-sat = Sat
+weekday-sun = Sun
+
 # Default screen
 default-waiting = Waiting
 default-fallback = Using default screen
@@ -109,10 +296,33 @@ static-mode-suffix = (static mode)
 cycle-step-info = Step { $current }/{ $total } (cycle)
 
 # Validation errors
-validation-time-order = Interval '{ $name }': start time ({ $start }) must be earlier than end time ({ $end })
+validation-time-order = Interval '{ $name }': start time ({ $start }) and end time ({ $end }) are the same
 validation-overlap = Intervals '{ $first }' and '{ $second }' overlap in time
 validation-empty-cycle = Interval '{ $name }': cyclic mode must contain at least one step
 validation-zero-duration = Interval '{ $name }': total duration of steps cannot be zero
+validation-calendar-overlap = Interval '{ $name }' from the calendar ({ $source }) overlaps interval '{ $other }'
+validation-date-range = Interval '{ $name }': the repetition start date ({ $from }) is after the end date ({ $to })
+validation-no-weekdays = Interval '{ $name }': no weekday selected
+validation-no-dates = Interval '{ $name }': no date selected
+validation-missing-screen = Interval '{ $name }': screen { $screen } does not exist
+validation-missing-step-screen = Interval '{ $name }': step { $step } uses screen { $screen }, which does not exist
+validation-missing-default-screen = Profile '{ $profile }': default screen { $screen } does not exist
+validation-profile-day = Profiles '{ $first }' and '{ $second }' are both assigned to { $day }
+validation-in-profile = Profile '{ $profile }': { $issue }
+validation-severity-error = Error
+validation-severity-warning = Warning
+validation-fix-time = Set an end time different from the start time
+validation-fix-overlap = Move '{ $name }' or limit the intervals to different days; until then the first one in the list wins
+validation-fix-calendar-overlap = Move '{ $name }' or refresh the calendar import
+validation-fix-date-range = Swap the start and end dates
+validation-fix-weekdays = Select at least one weekday
+validation-fix-dates = Add at least one date
+validation-fix-empty-cycle = Add a step or switch the interval to static mode
+validation-fix-zero-duration = Give at least one step a non-zero duration
+validation-fix-screen = Choose an existing screen
+validation-fix-default-screen = Choose the default screen again
+validation-fix-profile-day = Remove { $day } from one of the profiles
+validation-edit = Edit
 
 # Transitions
 transition-start = Start: { $name }
@@ -132,11 +342,40 @@ btn-settings = Settings
 # Hotkeys
 hotkeys-title = Hotkeys:
 hotkey-settings = F1 or Ctrl+, - open/close settings
+hotkey-pause = Space - pause/continue
+hotkey-cycle = N / E / R - skip step / extend step / restart cycle
+
+# Ad-hoc sessions
+adhoc-title = Start a session
+adhoc-start = Start a one-off session
+adhoc-stop = Stop the session and return to the schedule
+adhoc-single = One screen
+adhoc-cycle = Repeat a cycle
+adhoc-cycle-source = Cycle:
+adhoc-repeats = Repeats:
+adhoc-no-cycles = No cyclic intervals configured
+adhoc-start-btn = ▶ Start now
+adhoc-single-name = { $screen } · { $minutes } min
+adhoc-cycle-name = { $interval } × { $repeats }
+adhoc-active = Session: { $name }
+
+# Command line
+cli-status = { $screen } · { $interval } · { $time } left
+cli-next = { $screen } at { $time } (in { $left })
+cli-no-next = No upcoming transitions
+cli-paused = Paused
+cli-not-running = FlowTimer is not running
+cli-profile-auto = (auto: { $days })
+cli-profile-unknown = Unknown profile: { $name }
+cli-profile-switched = Switched to profile { $name }
+cli-ics-saved = Calendar saved to { $path }
+cli-history-saved = History saved to { $path }
 
 
 
 # Languages
-language-russian = Russian
+# Name of this language in the language itself, for the language list
+language-name = English
 
 # Default screens
 default-screen-work = Work
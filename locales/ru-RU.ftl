@@ -4,6 +4,8 @@ menu-language = Язык
 settings-title = Настройки
 tab-timers = Таймеры и экраны
 tab-system = Системные настройки
+tab-sharing = Обмен
+tab-stats = Статистика
 timers-mode = Режим работы
 mode-static = Статичный
 mode-interval = Интервальный
@@ -15,6 +17,9 @@ screen-edit = Редактор экрана
 field-title = Заголовок
 field-subtitle = Подзаголовок
 field-color = Цвет
+field-sound = Звук
+field-sound-tooltip = Файл WAV/OGG при переходе на этот экран; заменяет звуки переходов
+field-notify = Показывать уведомление рабочего стола при переходе на этот экран
 timers-params = Временные параметры
 btn-add-interval = Добавить интервал
 col-screen = Экран
@@ -31,21 +36,163 @@ system-autostart = Автозапуск с системой
 system-sounds = Звуковые уведомления
 system-window-pos = Положение окна на экране
 system-language-desc = Выберите язык интерфейса приложения
+system-language-dir = Другие языки: положите файлы <тег>.ftl, например de.ftl, в { $dir }
+system-language-refresh = Перечитать языки из папки
+system-language-fallback = Язык { $requested } недоступен, показан { $language }
+system-language-errors = Проблемы перевода: { $count }
 system-autostart-desc = Автоматически запускать приложение при старте системы
+autostart-method-xdg = Запись сеанса рабочего стола (~/.config/autostart)
+autostart-method-systemd = Пользовательская служба systemd
+autostart-unsupported = Автозапуск пока не поддерживается на этой платформе
+autostart-repaired = Запись автозапуска указывала на старое расположение программы и была обновлена
+autostart-foreign = Автозапуск запускает другую копию программы ({ $path }), запись оставлена без изменений. Чтобы запускать эту копию, выключите и снова включите автозапуск
+autostart-error = Не удалось изменить автозапуск: { $error }
 system-sounds-desc = Воспроизводить звуки при смене экранов
+sound-volume = Громкость
+sound-chime = Встроенный сигнал
+sound-test = ▶ Проверить
+sound-files-desc = Свои файлы WAV/OGG для переходов (пусто — встроенный сигнал):
+sound-interval-start = Начало интервала
+sound-interval-end = Конец интервала
+sound-step = Шаг цикла
+sound-no-device = Аудиоустройство не найдено, звук отключён
+sound-error = Не удалось воспроизвести звук: { $error }
+system-notifications = Уведомления рабочего стола
+system-notifications-desc = Показывать системное уведомление при каждом переходе, если окно перекрыто или свёрнуто. Для отдельных экранов отключается в редакторе экрана
+notifications-error = Не удалось показать уведомление: { $error }
+system-warnings = Предупреждения перед переходами
+system-warnings-desc = Предупреждать за несколько минут до смены экрана. Интервалы могут задать своё время
+warning-lead = За сколько минут:
+warning-banner-option = Плашка на главном экране
+warning-sound-option = Звук
+warning-notification-option = Уведомление рабочего стола
+warning-pulse-option = Пульсация цвета фона
+chime-soft = Мягкий
+chime-bell = Колокольчик
+chime-rising = Восходящий
 system-window-pos-desc = Запомнить положение окна на экране
+system-config-file = Файл конфигурации
+system-config-file-desc = Изменения, сделанные в текстовом редакторе, применяются автоматически
+config-parse-error = Не удалось прочитать config.json. Действуют прежние настройки, файл не перезаписывается, пока его не исправят.
+config-invalid = config.json применён, но в интервалах есть проблемы
+config-reload = Перечитать сейчас
+window-pos-current = Текущее положение: { $x }, { $y }
+system-pause-mode = Поведение паузы
+system-pause-mode-desc = Что происходит с расписанием, пока таймер на паузе
+pause-mode-shift = Сдвигать текущий и оставшиеся шаги, конец интервала не менять
+pause-mode-extend = Сдвигать шаги и продлевать интервал на время паузы
+system-extend-minutes = Продление шага
+system-extend-minutes-desc = Сколько минут добавляет к текущему шагу цикла кнопка «+ мин»
 btn-save = Сохранить
 btn-cancel = Отмена
 settings-saved = Настройки сохранены
 
+# Восстановление настроек
+recovery-title = Не удалось загрузить настройки
+recovery-desc = В config.json есть ошибка. Исправьте её в текстовом редакторе и нажмите «Повторить» или начните с настроек по умолчанию.
+recovery-backup = Копия испорченного файла сохранена в { $path }
+recovery-no-backup = Не удалось сохранить копию испорченного файла
+recovery-retry = Повторить
+recovery-defaults = Настройки по умолчанию
+
+# Обмен расписаниями
+sharing-export = Экспорт
+sharing-export-desc = Сохранить выбранные экраны и интервалы активного профиля в файл. Экраны, которые используют выбранные интервалы, добавляются автоматически. Собственные звуки экранов не сохраняются.
+sharing-screens = Экраны
+sharing-intervals = Интервалы
+sharing-file = Файл:
+sharing-format-hint = Формат определяется расширением: .json или .toml
+sharing-export-button = Экспортировать
+sharing-exported = Сохранено в { $path }
+sharing-import = Импорт
+sharing-import-desc = Добавить экраны и интервалы из файла к текущим настройкам. Занятые id получат новые значения.
+sharing-preview = Предпросмотр
+sharing-preview-title = Что изменится после импорта (интервалы добавятся в профиль «{ $profile }»):
+sharing-item-add = { $name } — будет добавлен (id { $id })
+sharing-item-remap = { $name } — будет добавлен с id { $id } вместо { $from }
+sharing-item-existing = { $name } — уже есть (id { $id })
+sharing-item-missing = { $name } — пропущен: экрана { $screen } нет в файле
+sharing-new-issues = После импорта в расписании появятся проблемы:
+sharing-nothing-to-import = Нечего импортировать: всё уже есть
+sharing-apply = Импортировать
+sharing-cancel = Отмена
+sharing-imported = Импорт выполнен
+sharing-ics = Календарь (.ics)
+sharing-ics-desc = Сохранить расписание активного профиля в файл iCalendar для приложений-календарей. Повторяющиеся интервалы становятся повторяющимися событиями.
+sharing-ics-from = Начиная с:
+sharing-ics-day = Один день
+sharing-ics-week = Неделя
+sharing-ics-steps = Добавить шаги цикла отдельными событиями
+sharing-busy = Занятое время из календаря
+sharing-busy-desc = Превратить встречи из файла .ics в интервалы активного профиля и заполнить промежутки между ними циклом. Повторный импорт того же файла заменяет созданные в прошлый раз интервалы; созданные вручную не меняются.
+sharing-busy-screen = Экран встреч:
+sharing-busy-new-screen = Создать новый экран
+sharing-busy-meeting-title = Встреча
+sharing-busy-filler = Заполнить промежутки:
+sharing-busy-no-filler = Не заполнять
+sharing-busy-hours = Рабочее время:
+sharing-busy-import = Импортировать
+sharing-busy-refresh = Обновить
+sharing-busy-remove = Удалить импортированные
+sharing-busy-imported = Встреч: { $meetings }. Интервалов добавлено: { $added }, заменено: { $removed }, событий пропущено: { $skipped }
+sharing-busy-removed = Удалено интервалов: { $removed }
+sharing-busy-time-zones = Неизвестные часовые пояса: { $zones }. Время в них прочитано как местное, проверьте импортированные встречи
+sharing-error = Ошибка: { $error }
+
+# Статистика
+stats-desc = Время на каждом экране по журналу сессий. Паузы не учитываются.
+stats-log = Файл журнала: { $path }
+stats-broken-lines = Нечитаемых строк в журнале: { $count }
+stats-today = Сегодня
+stats-day = День { $date }
+stats-week = Неделя { $from } – { $to }
+stats-empty = Нет записей
+stats-cycles = Завершено циклов: { $count }
+stats-streaks = Серии
+stats-streaks-desc = День засчитывается, если в нём завершён хотя бы один цикл без пропуска последнего шага.
+stats-streak-current = Текущая серия: { $days } { $days ->
+    [one] день
+    [few] дня
+   *[many] дней
+}
+stats-streak-longest = Лучшая серия: { $days } { $days ->
+    [one] день
+    [few] дня
+   *[many] дней
+}
+stats-export = Выгрузка для табеля
+stats-export-desc = Сохранить журнал за период в CSV или JSON (по расширению файла). Время считается без пауз.
+stats-export-from = С:
+stats-export-to = По:
+stats-export-group = Строки:
+stats-group-sessions = Каждый отрезок
+stats-group-interval = По интервалам за день
+stats-group-screen = По экранам за день
+stats-export-round = Округлять вверх до, минут:
+stats-export-round-hint = 0 - до ближайшей минуты
+stats-export-button = Выгрузить
+stats-exported = Журнал сохранён в { $path }
+
 # Основной интерфейс
 main-no-screens = Нет настроенных экранов
 main-no-screens-hint = Откройте настройки для создания экранов и интервалов
 interval-label = Интервал: { $name }
 next-transition = Следующий переход через: { $time }
+notify-body = { $interval } · следующий переход в { $time }
 timer-pause = ⏸ Пауза
 timer-continue = ▶ Продолжить
 timer-refresh = 🔄 Обновить
+timer-paused = ⏸ Пауза — оставшиеся шаги сдвигаются
+timer-skip = Перейти к следующему шагу
+timer-extend = +{ $minutes } мин
+timer-extend-tooltip = Продлить текущий шаг, оставшиеся шаги сдвинутся позже
+timer-restart = Начать цикл заново с первого шага
+warning-banner = { $screen } через { $minutes } { $minutes ->
+    [one] минуту
+    [few] минуты
+   *[many] минут
+}
+warning-notify-body = Смена экрана в { $time }
 
 # Управление экранами
 screens-title = Экраны
@@ -57,6 +204,20 @@ screen-edit-tooltip = Редактировать экран
 screen-new-title = Новый экран
 screen-default-title = Новый экран
 
+# Профили
+profiles-title = Профили
+profiles-description = У каждого профиля свои интервалы и экран по умолчанию; экраны общие
+profile-active = Профиль:
+profile-add = ➕ Новый
+profile-duplicate = Копировать
+profile-delete = 🗑 Удалить
+profile-name = Название:
+profile-default-screen = Экран вне интервалов:
+profile-default-screen-first = Первый экран
+profile-auto-days = Включать этот профиль автоматически по дням:
+profile-new-name = Новый профиль
+profile-switch = Переключить профиль
+
 # Управление интервалами
 intervals-title = Временные интервалы
 intervals-description = Каждый интервал имеет свое время работы и режим отображения экранов
@@ -64,9 +225,15 @@ intervals-none = Нет созданных интервалов
 intervals-create = ➕ Создать новый интервал
 interval-delete-tooltip = Удалить интервал
 interval-edit-tooltip = Редактировать интервал
+interval-imported = Импортирован из календаря { $source }
+interval-imported-edit-hint = Этот интервал импортирован из календаря. После сохранения он станет обычным и не будет заменяться при обновлении.
 interval-new-title = Новый интервал
 interval-static-mode = (статичный)
-interval-cycle-mode = (цикл из { $steps } шагов)
+interval-cycle-mode = (цикл из { $steps } { $steps ->
+    [one] шага
+   *[other] шагов
+})
+interval-crosses-midnight = (через полночь)
 
 # Валидация и расписание
 validation-title = Валидация настроек
@@ -84,6 +251,19 @@ interval-time-work = Время работы
 interval-time-from = С
 interval-time-to = до
 interval-duration-format = Длительность: {$hours} ч {$minutes} мин
+interval-crosses-midnight-hint = Конец раньше начала: интервал продолжается после полуночи в следующих сутках
+interval-repeat = Повторение
+recurrence-daily = Каждый день
+recurrence-weekdays = По будням (пн–пт)
+recurrence-weekends = По выходным (сб, вс)
+recurrence-days = Выбранные дни недели
+recurrence-date-range = Диапазон дат
+recurrence-dates = Отдельные даты
+recurrence-from = С
+recurrence-to = по
+recurrence-add-date = ➕ Добавить дату
+interval-warning-own = Своё время предупреждения
+interval-warning-own-tooltip = Заменяет глобальную настройку предупреждений для этого интервала; 0 отключает их
 interval-mode-work = Режим работы интервала
 interval-mode-static-radio = Статичный
 interval-mode-static-tooltip = Показывает один экран весь интервал
@@ -95,10 +275,20 @@ interval-steps-configure = Настройте последовательност
 interval-step-number = Шаг {$number}
 interval-step-screen-label = Экран:
 interval-step-duration-label = Длительность:
+interval-step-minutes = мин
 interval-step-choose = Выберите
 interval-step-add = ➕ Добавить шаг
 interval-cycle-duration = Общая длительность цикла: {$minutes} мин
 
+# Дни недели
+weekday-mon = Пн
+weekday-tue = Вт
+weekday-wed = Ср
+weekday-thu = Чт
+weekday-fri = Пт
+weekday-sat = Сб
+weekday-sun = Вс
+
 # Экран по умолчанию
 default-waiting = Ожидание
 default-fallback = Используется экран по умолчанию
@@ -109,10 +299,33 @@ static-mode-suffix = (статичный режим)
 cycle-step-info = Шаг { $current }/{ $total } (цикл)
 
 # Валидация ошибок
-validation-time-order = Интервал '{ $name }': время начала ({ $start }) должно быть раньше времени окончания ({ $end })
+validation-time-order = Интервал '{ $name }': время начала ({ $start }) и окончания ({ $end }) совпадают
 validation-overlap = Интервалы '{ $first }' и '{ $second }' пересекаются по времени
 validation-empty-cycle = Интервал '{ $name }': циклический режим должен содержать хотя бы один шаг
 validation-zero-duration = Интервал '{ $name }': общая длительность шагов не может быть нулевой
+validation-calendar-overlap = Интервал '{ $name }' из календаря ({ $source }) пересекается с интервалом '{ $other }'
+validation-date-range = Интервал '{ $name }': дата начала повторения ({ $from }) позже даты окончания ({ $to })
+validation-no-weekdays = Интервал '{ $name }': не выбран ни один день недели
+validation-no-dates = Интервал '{ $name }': не выбрана ни одна дата
+validation-missing-screen = Интервал '{ $name }': экрана { $screen } не существует
+validation-missing-step-screen = Интервал '{ $name }': шаг { $step } использует несуществующий экран { $screen }
+validation-missing-default-screen = Профиль '{ $profile }': экрана по умолчанию { $screen } не существует
+validation-profile-day = Профили '{ $first }' и '{ $second }' назначены на один день недели ({ $day })
+validation-in-profile = Профиль '{ $profile }': { $issue }
+validation-severity-error = Ошибка
+validation-severity-warning = Предупреждение
+validation-fix-time = Задайте время окончания, отличное от времени начала
+validation-fix-overlap = Сдвиньте '{ $name }' или разведите интервалы по разным дням; пока действует первый в списке
+validation-fix-calendar-overlap = Сдвиньте '{ $name }' или обновите импорт календаря
+validation-fix-date-range = Поменяйте местами даты начала и окончания
+validation-fix-weekdays = Выберите хотя бы один день недели
+validation-fix-dates = Добавьте хотя бы одну дату
+validation-fix-empty-cycle = Добавьте шаг или переключите интервал в статичный режим
+validation-fix-zero-duration = Задайте хотя бы одному шагу ненулевую длительность
+validation-fix-screen = Выберите существующий экран
+validation-fix-default-screen = Выберите экран по умолчанию заново
+validation-fix-profile-day = Уберите { $day } из одного из профилей
+validation-edit = Изменить
 
 # Переходы
 transition-start = Начало: { $name }
@@ -132,11 +345,40 @@ btn-settings = Настройки
 # Горячие клавиши
 hotkeys-title = Горячие клавиши:
 hotkey-settings = F1 или Ctrl+, - открыть/закрыть настройки
+hotkey-pause = Space - пауза/продолжить
+hotkey-cycle = N / E / R - пропустить шаг / продлить шаг / начать цикл заново
+
+# Разовые сессии
+adhoc-title = Запуск сессии
+adhoc-start = Запустить разовую сессию
+adhoc-stop = Остановить сессию и вернуться к расписанию
+adhoc-single = Один экран
+adhoc-cycle = Повторить цикл
+adhoc-cycle-source = Цикл:
+adhoc-repeats = Повторов:
+adhoc-no-cycles = Нет настроенных циклических интервалов
+adhoc-start-btn = ▶ Начать сейчас
+adhoc-single-name = { $screen } · { $minutes } мин
+adhoc-cycle-name = { $interval } × { $repeats }
+adhoc-active = Сессия: { $name }
+
+# Командная строка
+cli-status = { $screen } · { $interval } · осталось { $time }
+cli-next = { $screen } в { $time } (через { $left })
+cli-no-next = Нет предстоящих переходов
+cli-paused = На паузе
+cli-not-running = FlowTimer не запущен
+cli-profile-auto = (авто: { $days })
+cli-profile-unknown = Неизвестный профиль: { $name }
+cli-profile-switched = Включён профиль { $name }
+cli-ics-saved = Календарь сохранён в { $path }
+cli-history-saved = Журнал сохранён в { $path }
 
 
 
 # Языки
-language-russian = Русский
+# Название этого языка на нём самом, для списка языков
+language-name = Русский
 
 # Экраны по умолчанию
 default-screen-work = Работа
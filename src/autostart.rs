@@ -0,0 +1,442 @@
+//! Автозапуск с системой.
+//!
+//! На Linux поддерживаются два способа: XDG-запись `~/.config/autostart/flowtimer.desktop`
+//! (работает в любом окружении рабочего стола) и пользовательский unit systemd
+//! `~/.config/systemd/user/flowtimer.service`, привязанный к `graphical-session.target`.
+//! На остальных платформах автозапуск пока не реализован.
+
+use std::{fmt, io, path::PathBuf};
+
+use crate::config::{AutostartMethod, SystemSettings};
+
+#[derive(Debug)]
+pub enum AutostartError {
+    /// Платформа не поддерживается
+    Unsupported,
+    /// Ошибка записи или удаления файла
+    Io(io::Error),
+    /// `systemctl --user` завершился с ошибкой
+    Systemctl(String),
+}
+
+impl fmt::Display for AutostartError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AutostartError::Unsupported => {
+                write!(f, "автозапуск не поддерживается на этой платформе")
+            }
+            AutostartError::Io(err) => write!(f, "{}", err),
+            AutostartError::Systemctl(msg) => write!(f, "systemctl: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for AutostartError {}
+
+impl From<io::Error> for AutostartError {
+    fn from(err: io::Error) -> Self {
+        AutostartError::Io(err)
+    }
+}
+
+/// Состояние записи автозапуска
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AutostartStatus {
+    /// Записи нет
+    Disabled,
+    /// Запись есть и указывает на текущий исполняемый файл
+    Enabled,
+    /// Запись есть, но программы по её пути больше нет (старый путь)
+    Stale { exec: PathBuf },
+    /// Запись запускает другую существующую копию программы
+    Foreign { exec: PathBuf },
+}
+
+/// Поддерживается ли автозапуск на текущей платформе
+pub fn is_supported() -> bool {
+    cfg!(target_os = "linux")
+}
+
+/// Включает или выключает автозапуск выбранным способом.
+/// Запись другого способа удаляется, чтобы приложение не запускалось дважды.
+pub fn apply(enabled: bool, method: AutostartMethod) -> Result<(), AutostartError> {
+    imp::apply(enabled, method)
+}
+
+/// Проверяет запись автозапуска выбранного способа
+pub fn status(method: AutostartMethod) -> Result<AutostartStatus, AutostartError> {
+    imp::status(method)
+}
+
+/// Приводит записи автозапуска в соответствие с настройками при старте приложения.
+/// Запись другой существующей копии программы не переписывается.
+/// Возвращает запись, о которой стоит сообщить: исправленную устаревшую или чужую.
+pub fn sync(settings: &SystemSettings) -> Result<Option<AutostartStatus>, AutostartError> {
+    if !is_supported() || !settings.autostart {
+        return Ok(None);
+    }
+    match status(settings.autostart_method)? {
+        AutostartStatus::Enabled => Ok(None),
+        AutostartStatus::Disabled => {
+            apply(true, settings.autostart_method)?;
+            Ok(None)
+        }
+        status @ AutostartStatus::Stale { .. } => {
+            apply(true, settings.autostart_method)?;
+            Ok(Some(status))
+        }
+        status @ AutostartStatus::Foreign { .. } => Ok(Some(status)),
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use std::{
+        fs, io,
+        path::{Path, PathBuf},
+        process::Command,
+    };
+
+    use directories::BaseDirs;
+
+    use super::{AutostartError, AutostartStatus};
+    use crate::config::AutostartMethod;
+
+    const DESKTOP_FILE: &str = "flowtimer.desktop";
+    const SERVICE_NAME: &str = "flowtimer.service";
+
+    /// Каталог конфигурации пользователя (учитывает `XDG_CONFIG_HOME`)
+    fn config_home() -> io::Result<PathBuf> {
+        BaseDirs::new()
+            .map(|dirs| dirs.config_dir().to_path_buf())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no valid home directory"))
+    }
+
+    fn desktop_path() -> io::Result<PathBuf> {
+        Ok(config_home()?.join("autostart").join(DESKTOP_FILE))
+    }
+
+    fn service_path() -> io::Result<PathBuf> {
+        Ok(config_home()?
+            .join("systemd")
+            .join("user")
+            .join(SERVICE_NAME))
+    }
+
+    fn entry_path(method: AutostartMethod) -> io::Result<PathBuf> {
+        match method {
+            AutostartMethod::Xdg => desktop_path(),
+            AutostartMethod::Systemd => service_path(),
+        }
+    }
+
+    /// Экранирует путь для `Exec=` (.desktop). В двойных кавычках `"`, `` ` ``, `$` и `\`
+    /// экранируются обратной косой чертой, `%` удваивается; затем значение, как любая
+    /// строка .desktop, экранируется ещё раз, и каждая `\` удваивается
+    fn quote_desktop_exec(path: &Path) -> String {
+        let mut quoted = String::from("\"");
+        for ch in path.to_string_lossy().chars() {
+            match ch {
+                '"' | '`' | '$' => {
+                    quoted.push_str("\\\\");
+                    quoted.push(ch);
+                }
+                '\\' => quoted.push_str("\\\\\\\\"),
+                '%' => quoted.push_str("%%"),
+                _ => quoted.push(ch),
+            }
+        }
+        quoted.push('"');
+        quoted
+    }
+
+    /// Экранирует путь для `ExecStart=` (systemd). В двойных кавычках экранируются
+    /// `"` и `\`, а `$` (подстановка переменных) и `%` (спецификаторы) удваиваются
+    fn quote_unit_exec(path: &Path) -> String {
+        let mut quoted = String::from("\"");
+        for ch in path.to_string_lossy().chars() {
+            match ch {
+                '"' | '\\' => {
+                    quoted.push('\\');
+                    quoted.push(ch);
+                }
+                '$' => quoted.push_str("$$"),
+                '%' => quoted.push_str("%%"),
+                _ => quoted.push(ch),
+            }
+        }
+        quoted.push('"');
+        quoted
+    }
+
+    /// Извлекает путь к программе из `Exec=` (.desktop)
+    fn unquote_desktop_exec(value: &str) -> PathBuf {
+        // Сначала снимается экранирование строки .desktop
+        let mut unescaped = String::new();
+        let mut chars = value.trim().chars();
+        while let Some(ch) = chars.next() {
+            if ch != '\\' {
+                unescaped.push(ch);
+                continue;
+            }
+            match chars.next() {
+                Some('\\') => unescaped.push('\\'),
+                Some('s') => unescaped.push(' '),
+                Some('n') => unescaped.push('\n'),
+                Some('t') => unescaped.push('\t'),
+                Some('r') => unescaped.push('\r'),
+                // Прочие последовательности относятся к кавычкам `Exec=`
+                Some(other) => {
+                    unescaped.push('\\');
+                    unescaped.push(other);
+                }
+                None => {}
+            }
+        }
+        first_argument(&unescaped, &['%'])
+    }
+
+    /// Извлекает путь к программе из `ExecStart=` (systemd)
+    fn unquote_unit_exec(value: &str) -> PathBuf {
+        first_argument(value.trim(), &['%', '$'])
+    }
+
+    /// Первый аргумент командной строки: в кавычках `\` экранирует следующий символ,
+    /// а удвоенные символы из `doubled` означают один
+    fn first_argument(value: &str, doubled: &[char]) -> PathBuf {
+        let (quoted, rest) = match value.strip_prefix('"') {
+            Some(rest) => (true, rest),
+            None => (false, value.split_whitespace().next().unwrap_or_default()),
+        };
+
+        let mut path = String::new();
+        let mut chars = rest.chars();
+        while let Some(ch) = chars.next() {
+            match ch {
+                '"' if quoted => break,
+                '\\' if quoted => path.extend(chars.next()),
+                ch if doubled.contains(&ch) => {
+                    path.push(ch);
+                    chars.next();
+                }
+                _ => path.push(ch),
+            }
+        }
+        PathBuf::from(path)
+    }
+
+    fn desktop_entry(exe: &Path) -> String {
+        format!(
+            "[Desktop Entry]\n\
+             Type=Application\n\
+             Name=FlowTimer\n\
+             Comment=Visual time interval timer\n\
+             Exec={}\n\
+             Terminal=false\n\
+             Hidden=false\n\
+             X-GNOME-Autostart-enabled=true\n",
+            quote_desktop_exec(exe)
+        )
+    }
+
+    fn service_unit(exe: &Path) -> String {
+        format!(
+            "[Unit]\n\
+             Description=FlowTimer\n\
+             PartOf=graphical-session.target\n\
+             After=graphical-session.target\n\
+             \n\
+             [Service]\n\
+             ExecStart={}\n\
+             Restart=on-failure\n\
+             \n\
+             [Install]\n\
+             WantedBy=graphical-session.target\n",
+            quote_unit_exec(exe)
+        )
+    }
+
+    fn systemctl(args: &[&str]) -> Result<(), AutostartError> {
+        let output = Command::new("systemctl")
+            .arg("--user")
+            .args(args)
+            .output()
+            .map_err(|e| AutostartError::Systemctl(e.to_string()))?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(AutostartError::Systemctl(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ))
+        }
+    }
+
+    fn remove_if_exists(path: &Path) -> io::Result<bool> {
+        match fs::remove_file(path) {
+            Ok(()) => Ok(true),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn write_entry(path: &Path, contents: &str) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, contents)
+    }
+
+    fn enable(method: AutostartMethod, exe: &Path) -> Result<(), AutostartError> {
+        match method {
+            AutostartMethod::Xdg => write_entry(&desktop_path()?, &desktop_entry(exe))?,
+            AutostartMethod::Systemd => {
+                write_entry(&service_path()?, &service_unit(exe))?;
+                systemctl(&["daemon-reload"])?;
+                systemctl(&["enable", SERVICE_NAME])?;
+            }
+        }
+        Ok(())
+    }
+
+    fn disable(method: AutostartMethod) -> Result<(), AutostartError> {
+        match method {
+            AutostartMethod::Xdg => {
+                remove_if_exists(&desktop_path()?)?;
+            }
+            AutostartMethod::Systemd => {
+                let path = service_path()?;
+                if path.exists() {
+                    // Unit может быть уже выключен; важнее удалить файл
+                    let _ = systemctl(&["disable", SERVICE_NAME]);
+                    remove_if_exists(&path)?;
+                    let _ = systemctl(&["daemon-reload"]);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn apply(enabled: bool, method: AutostartMethod) -> Result<(), AutostartError> {
+        let other = match method {
+            AutostartMethod::Xdg => AutostartMethod::Systemd,
+            AutostartMethod::Systemd => AutostartMethod::Xdg,
+        };
+        disable(other)?;
+
+        if enabled {
+            enable(method, &std::env::current_exe()?)
+        } else {
+            disable(method)
+        }
+    }
+
+    pub fn status(method: AutostartMethod) -> Result<AutostartStatus, AutostartError> {
+        let contents = match fs::read_to_string(entry_path(method)?) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                return Ok(AutostartStatus::Disabled);
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        let (key, unquote): (_, fn(&str) -> PathBuf) = match method {
+            AutostartMethod::Xdg => ("Exec=", unquote_desktop_exec),
+            AutostartMethod::Systemd => ("ExecStart=", unquote_unit_exec),
+        };
+        let exec = contents
+            .lines()
+            .find_map(|line| line.trim().strip_prefix(key))
+            .map(unquote)
+            .unwrap_or_default();
+
+        Ok(classify(exec, &std::env::current_exe()?))
+    }
+
+    /// Сравнивает путь из записи с текущим исполняемым файлом
+    fn classify(exec: PathBuf, current: &Path) -> AutostartStatus {
+        if exec == current {
+            AutostartStatus::Enabled
+        } else if !exec.exists() {
+            AutostartStatus::Stale { exec }
+        } else if fs::canonicalize(&exec).ok() == fs::canonicalize(current).ok() {
+            AutostartStatus::Enabled
+        } else {
+            AutostartStatus::Foreign { exec }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn exec_paths_are_classified() {
+            let dir =
+                std::env::temp_dir().join(format!("flowtimer-autostart-{}", std::process::id()));
+            fs::create_dir_all(&dir).unwrap();
+            let current = dir.join("flow_timer");
+            let other = dir.join("flow_timer-old");
+            fs::write(&current, "").unwrap();
+            fs::write(&other, "").unwrap();
+
+            assert_eq!(
+                classify(current.clone(), &current),
+                AutostartStatus::Enabled
+            );
+            // Тот же файл по другому пути
+            assert_eq!(
+                classify(dir.join(".").join("flow_timer"), &current),
+                AutostartStatus::Enabled
+            );
+            assert_eq!(
+                classify(other.clone(), &current),
+                AutostartStatus::Foreign { exec: other }
+            );
+            let gone = dir.join("missing");
+            assert_eq!(
+                classify(gone.clone(), &current),
+                AutostartStatus::Stale { exec: gone }
+            );
+
+            fs::remove_dir_all(&dir).unwrap();
+        }
+
+        #[test]
+        fn exec_quoting_round_trips() {
+            let path = Path::new("/opt/Flow Timer/100% \"new\"/$HOME\\`x`/flow_timer");
+            assert_eq!(unquote_desktop_exec(&quote_desktop_exec(path)), path);
+            assert_eq!(unquote_unit_exec(&quote_unit_exec(path)), path);
+            assert_eq!(
+                unquote_desktop_exec("/usr/bin/flow_timer --minimized"),
+                PathBuf::from("/usr/bin/flow_timer")
+            );
+            assert_eq!(
+                unquote_unit_exec("/opt/$$x/flow_timer --minimized"),
+                PathBuf::from("/opt/$x/flow_timer")
+            );
+        }
+
+        #[test]
+        fn exec_quoting_follows_each_format() {
+            let path = Path::new("/opt/$HOME\\bin/100%");
+            // .desktop: `\$` внутри кавычек, и ещё раз экранированная `\` строки
+            assert_eq!(quote_desktop_exec(path), r#""/opt/\\$HOME\\\\bin/100%%""#);
+            // systemd: `$$` вместо подстановки переменной
+            assert_eq!(quote_unit_exec(path), r#""/opt/$$HOME\\bin/100%%""#);
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod imp {
+    use super::{AutostartError, AutostartStatus};
+    use crate::config::AutostartMethod;
+
+    pub fn apply(_enabled: bool, _method: AutostartMethod) -> Result<(), AutostartError> {
+        Err(AutostartError::Unsupported)
+    }
+
+    pub fn status(_method: AutostartMethod) -> Result<AutostartStatus, AutostartError> {
+        Err(AutostartError::Unsupported)
+    }
+}
@@ -0,0 +1,1424 @@
+//! Обмен расписанием с календарями в формате iCalendar (RFC 5545).
+//!
+//! Экспорт: каждый интервал активного профиля становится одним событием. Повторы
+//! внутри выбранного периода передаются правилом RRULE (или списком RDATE для
+//! отдельных дат), а не разворачиваются в отдельные события. По желанию
+//! добавляются события для шагов цикла.
+//!
+//! Импорт: занятое время из файла .ics (встречи) превращается в интервалы с
+//! экраном встречи, а промежутки между ними заполняются выбранным циклом.
+//! Такие интервалы помечаются источником и заменяются при повторном импорте.
+//!
+//! Время записывается как «плавающее» локальное (без часового пояса): расписание
+//! привязано к часам пользователя, а не к конкретной зоне. При чтении время в UTC
+//! переводится в местное. Базы часовых поясов в программе нет: время с TZID
+//! системного пояса читается как местное, а с любым другим TZID - тоже как местное,
+//! но такие пояса перечисляются в [`BusyCalendar::unknown_time_zones`].
+
+use std::{collections::BTreeMap, fmt};
+
+use chrono::{
+    DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc,
+    Weekday,
+};
+
+use crate::{
+    config::{
+        AppConfig, CycleStep, IntervalMode, MINUTES_PER_DAY, Recurrence, TimeInterval, TimeOfDay,
+    },
+    timer::cycle_step_blocks,
+};
+
+const DATE_TIME: &str = "%Y%m%dT%H%M%S";
+
+/// Собирает календарь на `days` дней, начиная с `from`.
+/// `stamp` - момент создания файла (DTSTAMP).
+pub fn export_ics(
+    cfg: &AppConfig,
+    from: NaiveDate,
+    days: u32,
+    include_steps: bool,
+    stamp: DateTime<Utc>,
+) -> String {
+    let mut out = String::new();
+    let stamp = stamp.format("%Y%m%dT%H%M%SZ").to_string();
+
+    push_line(&mut out, "BEGIN:VCALENDAR");
+    push_line(&mut out, "VERSION:2.0");
+    push_line(
+        &mut out,
+        &format!(
+            "PRODID:-//FlowTimer//FlowTimer {}//EN",
+            env!("CARGO_PKG_VERSION")
+        ),
+    );
+    push_line(&mut out, "CALSCALE:GREGORIAN");
+    push_line(
+        &mut out,
+        &format!(
+            "X-WR-CALNAME:{}",
+            escape_text(&format!("FlowTimer: {}", cfg.active_profile().name))
+        ),
+    );
+
+    for interval in cfg.intervals() {
+        let occurrences: Vec<NaiveDate> = (0..days)
+            .map(|offset| from + Duration::days(offset as i64))
+            .filter(|date| interval.recurrence.occurs_on(*date))
+            .collect();
+        if occurrences.is_empty() || interval.duration_minutes() == 0 {
+            continue;
+        }
+        let repeat = repeat_lines(
+            &interval.recurrence,
+            &occurrences,
+            interval.start.to_minutes(),
+        );
+
+        let description = match &interval.mode {
+            IntervalMode::Static { screen_id } => screen_title(cfg, *screen_id),
+            IntervalMode::Cycle { steps } => steps
+                .iter()
+                .map(|step| screen_title(cfg, step.screen_id))
+                .collect::<Vec<_>>()
+                .join(" → "),
+        };
+        let event = Event {
+            uid: format!("interval-{}-{}@flowtimer", interval.id, occurrences[0]),
+            summary: interval.name.clone(),
+            description,
+            offset: 0,
+            duration: interval.duration_minutes(),
+        };
+        event.write(&mut out, interval, occurrences[0], &repeat, &stamp);
+
+        if !include_steps {
+            continue;
+        }
+        let IntervalMode::Cycle { steps } = &interval.mode else {
+            continue;
+        };
+        for (number, (offset, duration, step_idx)) in
+            cycle_step_blocks(interval).into_iter().enumerate()
+        {
+            let title = screen_title(cfg, steps[step_idx].screen_id);
+            let event = Event {
+                uid: format!(
+                    "interval-{}-{}-step-{}@flowtimer",
+                    interval.id, occurrences[0], number
+                ),
+                summary: format!("{}: {}", interval.name, title),
+                description: title,
+                offset,
+                duration,
+            };
+            // У шага те же дни, что и у интервала: правило сдвигается вместе с началом
+            let repeat = repeat_lines(
+                &interval.recurrence,
+                &occurrences,
+                interval.start.to_minutes() + offset,
+            );
+            event.write(&mut out, interval, occurrences[0], &repeat, &stamp);
+        }
+    }
+
+    push_line(&mut out, "END:VCALENDAR");
+    out
+}
+
+struct Event {
+    uid: String,
+    summary: String,
+    description: String,
+    /// Смещение начала события от начала интервала, минут
+    offset: u32,
+    duration: u32,
+}
+
+impl Event {
+    fn write(
+        &self,
+        out: &mut String,
+        interval: &TimeInterval,
+        first: NaiveDate,
+        repeat: &[String],
+        stamp: &str,
+    ) {
+        let start = at_minutes(first, interval.start.to_minutes() + self.offset);
+        let end = start + Duration::minutes(self.duration as i64);
+
+        push_line(out, "BEGIN:VEVENT");
+        push_line(out, &format!("UID:{}", self.uid));
+        push_line(out, &format!("DTSTAMP:{}", stamp));
+        push_line(out, &format!("DTSTART:{}", start.format(DATE_TIME)));
+        push_line(out, &format!("DTEND:{}", end.format(DATE_TIME)));
+        for line in repeat {
+            push_line(out, line);
+        }
+        push_line(out, &format!("SUMMARY:{}", escape_text(&self.summary)));
+        if !self.description.is_empty() {
+            push_line(
+                out,
+                &format!("DESCRIPTION:{}", escape_text(&self.description)),
+            );
+        }
+        push_line(out, "END:VEVENT");
+    }
+}
+
+/// RRULE или RDATE для вхождений события в периоде. `start_min` - начало
+/// события в минутах от полуночи дня вхождения (может превышать сутки).
+fn repeat_lines(recurrence: &Recurrence, occurrences: &[NaiveDate], start_min: u32) -> Vec<String> {
+    let [_, .., last] = occurrences else {
+        return Vec::new();
+    };
+    let rule = match recurrence {
+        Recurrence::Daily | Recurrence::DateRange { .. } => "FREQ=DAILY".to_string(),
+        Recurrence::Weekdays => by_day(&[
+            Weekday::Mon,
+            Weekday::Tue,
+            Weekday::Wed,
+            Weekday::Thu,
+            Weekday::Fri,
+        ]),
+        Recurrence::Weekends => by_day(&[Weekday::Sat, Weekday::Sun]),
+        Recurrence::Days { days } => by_day(days),
+        // Отдельные даты не образуют правила: перечисляем их, кроме первой (она в DTSTART)
+        Recurrence::Dates { .. } => {
+            let dates: Vec<String> = occurrences[1..]
+                .iter()
+                .map(|date| at_minutes(*date, start_min).format(DATE_TIME).to_string())
+                .collect();
+            return vec![format!("RDATE:{}", dates.join(","))];
+        }
+    };
+    // Правило ограничено выбранным периодом; UNTIL в том же виде, что и DTSTART
+    vec![format!(
+        "RRULE:{};UNTIL={}",
+        rule,
+        at_minutes(*last, start_min).format(DATE_TIME)
+    )]
+}
+
+fn by_day(days: &[Weekday]) -> String {
+    let days: Vec<&str> = days
+        .iter()
+        .map(|day| match day {
+            Weekday::Mon => "MO",
+            Weekday::Tue => "TU",
+            Weekday::Wed => "WE",
+            Weekday::Thu => "TH",
+            Weekday::Fri => "FR",
+            Weekday::Sat => "SA",
+            Weekday::Sun => "SU",
+        })
+        .collect();
+    format!("FREQ=WEEKLY;BYDAY={}", days.join(","))
+}
+
+fn at_minutes(date: NaiveDate, minutes: u32) -> NaiveDateTime {
+    date.and_time(NaiveTime::MIN) + Duration::minutes(minutes as i64)
+}
+
+fn screen_title(cfg: &AppConfig, screen_id: u32) -> String {
+    cfg.screens
+        .iter()
+        .find(|s| s.id == screen_id)
+        .map(|s| s.title.clone())
+        .unwrap_or_else(|| format!("#{}", screen_id))
+}
+
+/// Экранирование значения типа TEXT (RFC 5545, 3.3.11)
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// Добавляет строку с CRLF, перенося её по 75 октетов (RFC 5545, 3.1)
+/// без разрыва символов UTF-8
+fn push_line(out: &mut String, line: &str) {
+    let mut octets = 0;
+    for ch in line.chars() {
+        if octets + ch.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            octets = 1;
+        }
+        out.push(ch);
+        octets += ch.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+#[derive(Debug)]
+pub enum IcsError {
+    Io(std::io::Error),
+    /// В файле нет BEGIN:VCALENDAR
+    NotCalendar,
+}
+
+impl fmt::Display for IcsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IcsError::Io(err) => write!(f, "ошибка ввода-вывода: {}", err),
+            IcsError::NotCalendar => write!(f, "файл не является календарём iCalendar"),
+        }
+    }
+}
+
+impl std::error::Error for IcsError {}
+
+impl From<std::io::Error> for IcsError {
+    fn from(err: std::io::Error) -> Self {
+        IcsError::Io(err)
+    }
+}
+
+/// Занятый промежуток внутри одного дня
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BusyBlock {
+    pub date: NaiveDate,
+    /// Минуты от полуночи, `start < end <= MINUTES_PER_DAY`
+    pub start: u32,
+    pub end: u32,
+    pub summary: String,
+}
+
+/// Занятое время, прочитанное из календаря
+#[derive(Debug, Clone, Default)]
+pub struct BusyCalendar {
+    pub blocks: Vec<BusyBlock>,
+    /// Пропущенные события: некорректные даты или неподдерживаемое правило повторения
+    pub skipped: usize,
+    /// TZID, которые не удалось учесть: время событий в них прочитано как местное
+    pub unknown_time_zones: Vec<String>,
+}
+
+/// Читает занятое время на `days` дней, начиная с `from`. События на весь день,
+/// отменённые и помеченные как «свободен» (TRANSP:TRANSPARENT) не учитываются.
+pub fn read_busy_blocks(text: &str, from: NaiveDate, days: u32) -> Result<BusyCalendar, IcsError> {
+    let lines = unfold(text);
+    if !lines
+        .iter()
+        .any(|line| line.eq_ignore_ascii_case("BEGIN:VCALENDAR"))
+    {
+        return Err(IcsError::NotCalendar);
+    }
+    let events = parse_events(&lines, system_time_zone().as_deref());
+    let last_day = from + Duration::days(days.max(1) as i64 - 1);
+
+    // Изменённые вхождения повторяющихся событий заменяют исходные
+    let overridden: Vec<(&str, NaiveDateTime)> = events
+        .iter()
+        .filter_map(|event| Some((event.uid.as_deref()?, event.recurrence_id?)))
+        .collect();
+
+    let mut calendar = BusyCalendar::default();
+    for event in &events {
+        if event.cancelled || event.transparent {
+            continue;
+        }
+        let Some(start) = event.start else {
+            calendar.skipped += 1;
+            continue;
+        };
+        let EventTime::DateTime(start) = start else {
+            // Событие на весь день не занимает рабочее время
+            continue;
+        };
+        for zone in &event.unknown_time_zones {
+            if !calendar.unknown_time_zones.contains(zone) {
+                calendar.unknown_time_zones.push(zone.clone());
+            }
+        }
+        let end = match (event.end, event.duration) {
+            (Some(EventTime::DateTime(end)), _) => end,
+            (None, Some(duration)) => start + duration,
+            (None, None) => start,
+            _ => {
+                calendar.skipped += 1;
+                continue;
+            }
+        };
+        if end <= start {
+            continue;
+        }
+
+        let starts = match (&event.rrule, event.recurrence_id) {
+            (Some(rule), None) => match parse_rule(rule) {
+                Some(rule) => rule.occurrences(start, last_day),
+                None => {
+                    calendar.skipped += 1;
+                    continue;
+                }
+            },
+            _ => vec![start],
+        };
+        for occurrence in starts {
+            let excluded = event.exdates.iter().any(|exdate| match exdate {
+                EventTime::Date(date) => *date == occurrence.date(),
+                EventTime::DateTime(at) => *at == occurrence,
+            });
+            let replaced = event.recurrence_id.is_none()
+                && event.uid.as_deref().is_some_and(|uid| {
+                    overridden
+                        .iter()
+                        .any(|(other, at)| *other == uid && *at == occurrence)
+                });
+            if excluded || replaced {
+                continue;
+            }
+            split_by_days(
+                &mut calendar.blocks,
+                occurrence,
+                occurrence + (end - start),
+                from,
+                last_day,
+                &event.summary,
+            );
+        }
+    }
+    calendar
+        .blocks
+        .sort_by_key(|block| (block.date, block.start, block.end));
+    Ok(calendar)
+}
+
+/// Как превращать занятое время в интервалы
+pub struct BusyImport<'a> {
+    /// Метка источника, см. `TimeInterval::source`
+    pub source: &'a str,
+    pub meeting_screen_id: u32,
+    /// Название встречи без темы
+    pub untitled: &'a str,
+    /// Название и шаги цикла, которым заполняются промежутки между встречами
+    pub filler: Option<(&'a str, &'a [CycleStep])>,
+    /// Рабочее время, в пределах которого заполняются промежутки
+    pub day_start: TimeOfDay,
+    pub day_end: TimeOfDay,
+}
+
+impl BusyImport<'_> {
+    /// Интервалы для встреч и промежутков между ними. Пересекающиеся встречи
+    /// сливаются; одинаковые интервалы разных дней объединяются в один с
+    /// правилом повторения по датам. Новые id выдаются начиная с `next_id`.
+    pub fn intervals(&self, blocks: &[BusyBlock], next_id: &mut u32) -> Vec<TimeInterval> {
+        let mut by_date: BTreeMap<NaiveDate, Vec<BusyBlock>> = BTreeMap::new();
+        for block in blocks {
+            by_date.entry(block.date).or_default().push(block.clone());
+        }
+
+        // Одинаковые промежутки разных дней -> даты
+        let mut slots: Vec<(Slot, Vec<NaiveDate>)> = Vec::new();
+        let mut add_slot =
+            |key: Slot, date: NaiveDate| match slots.iter_mut().find(|(slot, _)| *slot == key) {
+                Some((_, dates)) => dates.push(date),
+                None => slots.push((key, vec![date])),
+            };
+
+        let window = (self.day_start.to_minutes(), self.day_end.to_minutes());
+        for (date, blocks) in by_date {
+            let meetings = merge_blocks(blocks);
+
+            if let Some((name, steps)) = self.filler {
+                // Промежуток короче первого шага цикла не заполняем
+                let min_gap = steps.first().map_or(1, |s| s.duration_minutes.max(1));
+                let mut cursor = window.0;
+                let bounds = meetings
+                    .iter()
+                    .map(|m| (m.start, m.end))
+                    .chain([(window.1, window.1)]);
+                for (start, end) in bounds {
+                    let gap_end = start.min(window.1);
+                    if gap_end >= cursor + min_gap {
+                        let slot = Slot {
+                            name: name.to_string(),
+                            start: cursor,
+                            end: gap_end,
+                            meeting: false,
+                        };
+                        add_slot(slot, date);
+                    }
+                    cursor = cursor.max(end);
+                }
+            }
+
+            for meeting in meetings {
+                let name = if meeting.summary.is_empty() {
+                    self.untitled.to_string()
+                } else {
+                    meeting.summary
+                };
+                let slot = Slot {
+                    name,
+                    start: meeting.start,
+                    end: meeting.end,
+                    meeting: true,
+                };
+                add_slot(slot, date);
+            }
+        }
+
+        slots.sort_by_key(|(slot, dates)| (dates[0], slot.start));
+        slots
+            .into_iter()
+            .map(|(slot, dates)| {
+                let Slot {
+                    name,
+                    start,
+                    end,
+                    meeting,
+                } = slot;
+                let mode = if meeting {
+                    IntervalMode::Static {
+                        screen_id: self.meeting_screen_id,
+                    }
+                } else {
+                    IntervalMode::Cycle {
+                        steps: self
+                            .filler
+                            .map(|(_, steps)| steps.to_vec())
+                            .unwrap_or_default(),
+                    }
+                };
+                // Конец в полночь записывается как 00:00; занятость на весь день - до 23:59
+                let end = if start == 0 && end == MINUTES_PER_DAY {
+                    MINUTES_PER_DAY - 1
+                } else {
+                    end
+                };
+                let interval = TimeInterval {
+                    id: *next_id,
+                    name,
+                    start: TimeOfDay::from_minutes(start),
+                    end: TimeOfDay::from_minutes(end),
+                    mode,
+                    recurrence: Recurrence::Dates { dates },
+                    warning_minutes: None,
+                    source: Some(self.source.to_string()),
+                };
+                *next_id += 1;
+                interval
+            })
+            .collect()
+    }
+}
+
+/// Будущий интервал внутри дня, минуты от полуночи
+#[derive(Debug, PartialEq, Eq)]
+struct Slot {
+    name: String,
+    start: u32,
+    end: u32,
+    /// Встреча или промежуток, заполненный циклом
+    meeting: bool,
+}
+
+/// Заменяет интервалы, импортированные из `source`, новыми.
+/// Возвращает число удалённых интервалов.
+pub fn replace_imported(
+    intervals: &mut Vec<TimeInterval>,
+    source: &str,
+    imported: Vec<TimeInterval>,
+) -> usize {
+    let before = intervals.len();
+    intervals.retain(|i| i.source.as_deref() != Some(source));
+    let removed = before - intervals.len();
+    intervals.extend(imported);
+    removed
+}
+
+/// Сливает пересекающиеся и смежные встречи одного дня
+fn merge_blocks(mut blocks: Vec<BusyBlock>) -> Vec<BusyBlock> {
+    blocks.sort_by_key(|block| (block.start, block.end));
+    let mut merged: Vec<BusyBlock> = Vec::new();
+    for block in blocks {
+        match merged.last_mut() {
+            Some(last) if block.start <= last.end => {
+                last.end = last.end.max(block.end);
+                if !block.summary.is_empty()
+                    && !last.summary.split(" / ").any(|s| s == block.summary)
+                {
+                    if !last.summary.is_empty() {
+                        last.summary.push_str(" / ");
+                    }
+                    last.summary.push_str(&block.summary);
+                }
+            }
+            _ => merged.push(block),
+        }
+    }
+    merged
+}
+
+/// Разбивает вхождение события по дням периода
+fn split_by_days(
+    blocks: &mut Vec<BusyBlock>,
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+    from: NaiveDate,
+    last_day: NaiveDate,
+    summary: &str,
+) {
+    let mut date = start.date().max(from);
+    while date <= last_day {
+        let day_start = date.and_time(NaiveTime::MIN);
+        if end <= day_start {
+            break;
+        }
+        let block_start = (start.max(day_start) - day_start).num_seconds();
+        let block_end = (end.min(day_start + Duration::days(1)) - day_start).num_seconds();
+        if block_start < block_end {
+            blocks.push(BusyBlock {
+                date,
+                start: (block_start / 60) as u32,
+                // Неполная минута в конце считается занятой
+                end: ((block_end + 59) / 60) as u32,
+                summary: summary.to_string(),
+            });
+        }
+        let Some(next) = date.succ_opt() else {
+            break;
+        };
+        date = next;
+    }
+}
+
+/// Дата или дата со временем из свойства календаря
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EventTime {
+    Date(NaiveDate),
+    DateTime(NaiveDateTime),
+}
+
+#[derive(Debug, Default)]
+struct RawEvent {
+    uid: Option<String>,
+    summary: String,
+    start: Option<EventTime>,
+    end: Option<EventTime>,
+    duration: Option<Duration>,
+    rrule: Option<String>,
+    exdates: Vec<EventTime>,
+    recurrence_id: Option<NaiveDateTime>,
+    cancelled: bool,
+    transparent: bool,
+    /// TZID времён события, кроме UTC и системного пояса
+    unknown_time_zones: Vec<String>,
+}
+
+/// Склеивает перенесённые строки (RFC 5545, 3.1)
+fn unfold(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in text.split('\n') {
+        let raw = raw.strip_suffix('\r').unwrap_or(raw);
+        if let Some(rest) = raw.strip_prefix([' ', '\t'])
+            && let Some(last) = lines.last_mut()
+        {
+            last.push_str(rest);
+        } else if !raw.is_empty() {
+            lines.push(raw.to_string());
+        }
+    }
+    lines
+}
+
+/// События VEVENT; вложенные компоненты (VALARM) пропускаются.
+/// `system_zone` - часовой пояс системы, время в котором совпадает с местным.
+fn parse_events(lines: &[String], system_zone: Option<&str>) -> Vec<RawEvent> {
+    let mut events = Vec::new();
+    let mut components: Vec<String> = Vec::new();
+    let mut event = RawEvent::default();
+
+    for line in lines {
+        let Some((head, value)) = split_property(line) else {
+            continue;
+        };
+        let (name, params) = match head.split_once(';') {
+            Some((name, params)) => (name.to_ascii_uppercase(), params),
+            None => (head.to_ascii_uppercase(), ""),
+        };
+        match name.as_str() {
+            "BEGIN" => {
+                if value.eq_ignore_ascii_case("VEVENT") {
+                    event = RawEvent::default();
+                }
+                components.push(value.to_ascii_uppercase());
+                continue;
+            }
+            "END" => {
+                if components.pop().as_deref() == Some("VEVENT") {
+                    events.push(std::mem::take(&mut event));
+                }
+                continue;
+            }
+            _ => {}
+        }
+        if components.last().map(String::as_str) != Some("VEVENT") {
+            continue;
+        }
+        if let Some(zone) = tzid(params)
+            && !is_utc_zone(zone)
+            && system_zone != Some(zone)
+            && !event.unknown_time_zones.iter().any(|known| known == zone)
+        {
+            event.unknown_time_zones.push(zone.to_string());
+        }
+        match name.as_str() {
+            "UID" => event.uid = Some(value.to_string()),
+            "SUMMARY" => event.summary = unescape_text(value),
+            "DTSTART" => event.start = parse_time(params, value),
+            "DTEND" => event.end = parse_time(params, value),
+            "DURATION" => event.duration = parse_duration(value),
+            "RRULE" => event.rrule = Some(value.to_string()),
+            "EXDATE" => event.exdates.extend(
+                value
+                    .split(',')
+                    .filter_map(|value| parse_time(params, value)),
+            ),
+            "RECURRENCE-ID" => {
+                event.recurrence_id = match parse_time(params, value) {
+                    Some(EventTime::DateTime(at)) => Some(at),
+                    Some(EventTime::Date(date)) => Some(date.and_time(NaiveTime::MIN)),
+                    None => None,
+                }
+            }
+            "STATUS" => event.cancelled = value.eq_ignore_ascii_case("CANCELLED"),
+            "TRANSP" => event.transparent = value.eq_ignore_ascii_case("TRANSPARENT"),
+            _ => {}
+        }
+    }
+    events
+}
+
+/// Делит строку на имя с параметрами и значение: первое двоеточие вне кавычек
+fn split_property(line: &str) -> Option<(&str, &str)> {
+    let mut in_quotes = false;
+    let colon = line.char_indices().find_map(|(idx, ch)| {
+        if ch == '"' {
+            in_quotes = !in_quotes;
+        }
+        (ch == ':' && !in_quotes).then_some(idx)
+    })?;
+    Some((&line[..colon], &line[colon + 1..]))
+}
+
+/// Значение параметра TZID
+fn tzid(params: &str) -> Option<&str> {
+    params.split(';').find_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        key.eq_ignore_ascii_case("TZID")
+            .then(|| value.trim_matches('"'))
+    })
+}
+
+/// Пояса, время в которых совпадает с UTC
+fn is_utc_zone(zone: &str) -> bool {
+    ["UTC", "GMT", "Etc/UTC", "Etc/GMT", "Z"]
+        .iter()
+        .any(|utc| zone.eq_ignore_ascii_case(utc))
+}
+
+/// Часовой пояс системы (`TZ` или ссылка `/etc/localtime`), например `Europe/Berlin`
+fn system_time_zone() -> Option<String> {
+    if let Ok(zone) = std::env::var("TZ")
+        && !zone.is_empty()
+    {
+        return Some(zone.trim_start_matches(':').to_string());
+    }
+    let target = std::fs::read_link("/etc/localtime").ok()?;
+    let (_, zone) = target.to_str()?.split_once("zoneinfo/")?;
+    Some(zone.to_string())
+}
+
+/// Время в UTC (`...Z` или TZID=UTC) переводится в местное, остальное читается как есть
+fn parse_time(params: &str, value: &str) -> Option<EventTime> {
+    let value = value.trim();
+    let is_date = params
+        .split(';')
+        .any(|param| param.eq_ignore_ascii_case("VALUE=DATE"))
+        || value.len() == 8;
+    if is_date {
+        return NaiveDate::parse_from_str(value, "%Y%m%d")
+            .ok()
+            .map(EventTime::Date);
+    }
+    let utc = value
+        .strip_suffix('Z')
+        .or_else(|| tzid(params).filter(|zone| is_utc_zone(zone)).map(|_| value));
+    if let Some(utc) = utc {
+        let at = NaiveDateTime::parse_from_str(utc, DATE_TIME).ok()?;
+        let local = Utc.from_utc_datetime(&at).with_timezone(&Local);
+        return Some(EventTime::DateTime(local.naive_local()));
+    }
+    NaiveDateTime::parse_from_str(value, DATE_TIME)
+        .ok()
+        .map(EventTime::DateTime)
+}
+
+/// Длительность вида `PT1H30M`, `P1D`, `-PT15M` (RFC 5545, 3.3.6)
+fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let (negative, rest) = match value.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let rest = rest.strip_prefix('P')?;
+
+    let mut total = Duration::zero();
+    let mut number = String::new();
+    let mut in_time = false;
+    for ch in rest.chars() {
+        match ch {
+            '0'..='9' => number.push(ch),
+            'T' => in_time = true,
+            _ => {
+                let n: i64 = number.parse().ok()?;
+                number.clear();
+                total += match (ch, in_time) {
+                    ('W', false) => Duration::weeks(n),
+                    ('D', false) => Duration::days(n),
+                    ('H', true) => Duration::hours(n),
+                    ('M', true) => Duration::minutes(n),
+                    ('S', true) => Duration::seconds(n),
+                    _ => return None,
+                };
+            }
+        }
+    }
+    number
+        .is_empty()
+        .then_some(if negative { -total } else { total })
+}
+
+fn unescape_text(value: &str) -> String {
+    let mut text = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            text.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => text.push(' '),
+            Some(other) => text.push(other),
+            None => {}
+        }
+    }
+    text.trim().to_string()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// Правило RRULE в поддерживаемом объёме: частота, INTERVAL, COUNT, UNTIL и
+/// BYDAY для еженедельных событий
+#[derive(Debug, Clone)]
+struct Rule {
+    frequency: Frequency,
+    interval: u32,
+    count: Option<u32>,
+    until: Option<NaiveDateTime>,
+    by_day: Vec<Weekday>,
+}
+
+/// `None` - правило не поддерживается
+fn parse_rule(value: &str) -> Option<Rule> {
+    let mut rule = Rule {
+        frequency: Frequency::Daily,
+        interval: 1,
+        count: None,
+        until: None,
+        by_day: Vec::new(),
+    };
+    let mut frequency = None;
+    for part in value.split(';') {
+        let (key, value) = part.split_once('=')?;
+        match key.to_ascii_uppercase().as_str() {
+            "FREQ" => {
+                frequency = Some(match value.to_ascii_uppercase().as_str() {
+                    "DAILY" => Frequency::Daily,
+                    "WEEKLY" => Frequency::Weekly,
+                    "MONTHLY" => Frequency::Monthly,
+                    "YEARLY" => Frequency::Yearly,
+                    _ => return None,
+                })
+            }
+            "INTERVAL" => rule.interval = value.parse().ok().filter(|n| *n > 0)?,
+            "COUNT" => rule.count = Some(value.parse().ok()?),
+            "UNTIL" => {
+                rule.until = Some(match parse_time("", value)? {
+                    EventTime::DateTime(at) => at,
+                    EventTime::Date(date) => date.and_time(NaiveTime::MIN) + Duration::days(1),
+                })
+            }
+            "BYDAY" => {
+                rule.by_day = value
+                    .split(',')
+                    .map(|day| match day.to_ascii_uppercase().as_str() {
+                        "MO" => Some(Weekday::Mon),
+                        "TU" => Some(Weekday::Tue),
+                        "WE" => Some(Weekday::Wed),
+                        "TH" => Some(Weekday::Thu),
+                        "FR" => Some(Weekday::Fri),
+                        "SA" => Some(Weekday::Sat),
+                        "SU" => Some(Weekday::Sun),
+                        // «второй вторник» и подобное не поддерживаются
+                        _ => None,
+                    })
+                    .collect::<Option<_>>()?
+            }
+            "WKST" => {}
+            _ => return None,
+        }
+    }
+    rule.frequency = frequency?;
+    if !rule.by_day.is_empty() && rule.frequency != Frequency::Weekly {
+        return None;
+    }
+    Some(rule)
+}
+
+impl Rule {
+    /// Начала вхождений, которые приходятся не позже `last_day`
+    fn occurrences(&self, start: NaiveDateTime, last_day: NaiveDate) -> Vec<NaiveDateTime> {
+        let first = start.date();
+        let mut result = Vec::new();
+        let mut count = 0;
+        let mut date = first;
+        while date <= last_day {
+            if self.matches(first, date) {
+                let at = date.and_time(start.time());
+                if self.until.is_some_and(|until| at > until)
+                    || self.count.is_some_and(|max| count >= max)
+                {
+                    break;
+                }
+                count += 1;
+                result.push(at);
+            }
+            let Some(next) = date.succ_opt() else {
+                break;
+            };
+            date = next;
+        }
+        result
+    }
+
+    fn matches(&self, first: NaiveDate, date: NaiveDate) -> bool {
+        let interval = self.interval as i64;
+        match self.frequency {
+            Frequency::Daily => (date - first).num_days() % interval == 0,
+            Frequency::Weekly => {
+                let week_start =
+                    |d: NaiveDate| d - Duration::days(d.weekday().num_days_from_monday() as i64);
+                let weeks = (week_start(date) - week_start(first)).num_days() / 7;
+                let day_matches = if self.by_day.is_empty() {
+                    date.weekday() == first.weekday()
+                } else {
+                    self.by_day.contains(&date.weekday())
+                };
+                weeks % interval == 0 && day_matches
+            }
+            Frequency::Monthly => {
+                let months = (date.year() - first.year()) as i64 * 12 + date.month() as i64
+                    - first.month() as i64;
+                date.day() == first.day() && months % interval == 0
+            }
+            Frequency::Yearly => {
+                date.month() == first.month()
+                    && date.day() == first.day()
+                    && (date.year() - first.year()) as i64 % interval == 0
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Timelike;
+
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    fn calendar(events: &[(&str, &str)]) -> String {
+        let mut text = String::from("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n");
+        for (start, end) in events {
+            text.push_str(&format!(
+                "BEGIN:VEVENT\r\nSUMMARY:Встреча\r\n{}\r\n{}\r\nEND:VEVENT\r\n",
+                start, end
+            ));
+        }
+        text.push_str("END:VCALENDAR\r\n");
+        text
+    }
+
+    fn interval(
+        start: (u8, u8),
+        end: (u8, u8),
+        recurrence: Recurrence,
+        mode: IntervalMode,
+    ) -> TimeInterval {
+        TimeInterval {
+            id: 1,
+            name: "Интервал".into(),
+            start: TimeOfDay {
+                hour: start.0,
+                minute: start.1,
+            },
+            end: TimeOfDay {
+                hour: end.0,
+                minute: end.1,
+            },
+            mode,
+            recurrence,
+            warning_minutes: None,
+            source: None,
+        }
+    }
+
+    /// Склеенные строки календаря на `days` дней с 2026-06-08 (понедельник)
+    fn export(interval: TimeInterval, days: u32, include_steps: bool) -> Vec<String> {
+        let mut cfg = AppConfig::default();
+        cfg.screens[0].title = "Работа".into();
+        cfg.screens[1].title = "Перерыв".into();
+        cfg.profiles[0].intervals = vec![interval];
+        let stamp = Utc.with_ymd_and_hms(2026, 6, 1, 12, 0, 0).unwrap();
+        unfold(&export_ics(
+            &cfg,
+            date("2026-06-08"),
+            days,
+            include_steps,
+            stamp,
+        ))
+    }
+
+    /// Свойства событий календаря без служебных UID и DTSTAMP
+    fn events(lines: &[String]) -> Vec<Vec<&str>> {
+        let mut events = Vec::new();
+        let mut current: Option<Vec<&str>> = None;
+        for line in lines {
+            match line.as_str() {
+                "BEGIN:VEVENT" => current = Some(Vec::new()),
+                "END:VEVENT" => events.extend(current.take()),
+                line if line.starts_with("UID:") || line.starts_with("DTSTAMP:") => {}
+                line => {
+                    if let Some(event) = current.as_mut() {
+                        event.push(line);
+                    }
+                }
+            }
+        }
+        events
+    }
+
+    fn static_screen() -> IntervalMode {
+        IntervalMode::Static { screen_id: 1 }
+    }
+
+    /// Минуты от полуночи местного времени для момента в UTC
+    fn local_minutes(utc: &str) -> (NaiveDate, u32) {
+        let at = NaiveDateTime::parse_from_str(utc, DATE_TIME).unwrap();
+        let local = Utc
+            .from_utc_datetime(&at)
+            .with_timezone(&Local)
+            .naive_local();
+        (local.date(), local.hour() * 60 + local.minute())
+    }
+
+    #[test]
+    fn utc_times_are_converted_to_local() {
+        let text = calendar(&[
+            ("DTSTART:20260610T070000Z", "DTEND:20260610T080000Z"),
+            (
+                "DTSTART;TZID=Etc/UTC:20260610T120000",
+                "DTEND;TZID=Etc/UTC:20260610T123000",
+            ),
+        ]);
+        let busy = read_busy_blocks(&text, date("2026-06-09"), 3).unwrap();
+        let starts: Vec<_> = busy
+            .blocks
+            .iter()
+            .map(|block| (block.date, block.start))
+            .collect();
+        assert_eq!(
+            starts,
+            vec![
+                local_minutes("20260610T070000"),
+                local_minutes("20260610T120000")
+            ]
+        );
+        assert!(busy.unknown_time_zones.is_empty());
+    }
+
+    #[test]
+    fn unknown_time_zones_are_reported() {
+        let text = calendar(&[
+            (
+                "DTSTART;TZID=Mars/Olympus:20260610T100000",
+                "DTEND;TZID=Mars/Olympus:20260610T110000",
+            ),
+            (
+                "DTSTART;TZID=\"Mars/Olympus\":20260611T100000",
+                "DTEND;TZID=Mars/Olympus:20260611T110000",
+            ),
+            ("DTSTART:20260612T100000", "DTEND:20260612T110000"),
+        ]);
+        let busy = read_busy_blocks(&text, date("2026-06-10"), 3).unwrap();
+        // Время прочитано как местное, но пояс не потерян
+        assert_eq!(
+            busy.blocks
+                .iter()
+                .map(|block| (block.date, block.start, block.end))
+                .collect::<Vec<_>>(),
+            vec![
+                (date("2026-06-10"), 600, 660),
+                (date("2026-06-11"), 600, 660),
+                (date("2026-06-12"), 600, 660)
+            ]
+        );
+        assert_eq!(busy.unknown_time_zones, vec!["Mars/Olympus".to_string()]);
+    }
+
+    #[test]
+    fn system_time_zone_is_local() {
+        let lines = unfold(&calendar(&[(
+            "DTSTART;TZID=Europe/Berlin:20260610T100000",
+            "DTEND;TZID=Europe/Berlin:20260610T110000",
+        )]));
+        let known = parse_events(&lines, Some("Europe/Berlin"));
+        assert!(known[0].unknown_time_zones.is_empty());
+        assert_eq!(
+            known[0].start,
+            Some(EventTime::DateTime(
+                date("2026-06-10").and_hms_opt(10, 0, 0).unwrap()
+            ))
+        );
+
+        let unknown = parse_events(&lines, Some("Europe/Moscow"));
+        assert_eq!(unknown[0].unknown_time_zones, vec!["Europe/Berlin"]);
+    }
+
+    #[test]
+    fn weekday_recurrence_becomes_weekly_rule() {
+        let lines = export(
+            interval((9, 0), (10, 0), Recurrence::Weekdays, static_screen()),
+            14,
+            false,
+        );
+        assert_eq!(
+            events(&lines),
+            vec![vec![
+                "DTSTART:20260608T090000",
+                "DTEND:20260608T100000",
+                "RRULE:FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR;UNTIL=20260619T090000",
+                "SUMMARY:Интервал",
+                "DESCRIPTION:Работа",
+            ]]
+        );
+
+        // Первое вхождение - первый подходящий день периода
+        let days = Recurrence::Days {
+            days: vec![Weekday::Wed, Weekday::Fri],
+        };
+        let lines = export(interval((9, 0), (10, 0), days, static_screen()), 10, false);
+        let event = &events(&lines)[0];
+        assert_eq!(event[0], "DTSTART:20260610T090000");
+        assert_eq!(
+            event[2],
+            "RRULE:FREQ=WEEKLY;BYDAY=WE,FR;UNTIL=20260617T090000"
+        );
+    }
+
+    #[test]
+    fn date_list_becomes_rdate() {
+        let dates = Recurrence::Dates {
+            dates: vec![date("2026-06-10"), date("2026-06-12"), date("2026-06-20")],
+        };
+        // 2026-06-20 вне периода
+        let lines = export(interval((9, 30), (10, 0), dates, static_screen()), 7, false);
+        let event = &events(&lines)[0];
+        assert_eq!(event[0], "DTSTART:20260610T093000");
+        assert_eq!(event[2], "RDATE:20260612T093000");
+        assert!(!event.iter().any(|line| line.starts_with("RRULE")));
+    }
+
+    #[test]
+    fn interval_after_midnight_ends_next_day() {
+        let dates = Recurrence::Dates {
+            dates: vec![date("2026-06-10")],
+        };
+        let lines = export(interval((22, 0), (2, 0), dates, static_screen()), 7, false);
+        // Единственное вхождение не требует ни RRULE, ни RDATE
+        assert_eq!(
+            &events(&lines)[0][..3],
+            [
+                "DTSTART:20260610T220000",
+                "DTEND:20260611T020000",
+                "SUMMARY:Интервал"
+            ]
+        );
+    }
+
+    #[test]
+    fn cycle_steps_get_their_own_events() {
+        let pomodoro = IntervalMode::Cycle {
+            steps: vec![
+                CycleStep {
+                    screen_id: 1,
+                    duration_minutes: 25,
+                },
+                CycleStep {
+                    screen_id: 2,
+                    duration_minutes: 5,
+                },
+            ],
+        };
+        let cycle = interval((9, 0), (10, 0), Recurrence::Daily, pomodoro);
+
+        assert_eq!(events(&export(cycle.clone(), 3, false)).len(), 1);
+
+        let lines = export(cycle, 3, true);
+        let events = events(&lines);
+        assert_eq!(events.len(), 5);
+        assert_eq!(events[0][3], "SUMMARY:Интервал");
+        assert_eq!(events[0][4], "DESCRIPTION:Работа → Перерыв");
+        // Второй шаг: перерыв 09:25-09:30 каждый день периода
+        assert_eq!(
+            events[2],
+            vec![
+                "DTSTART:20260608T092500",
+                "DTEND:20260608T093000",
+                "RRULE:FREQ=DAILY;UNTIL=20260610T092500",
+                "SUMMARY:Интервал: Перерыв",
+                "DESCRIPTION:Перерыв",
+            ]
+        );
+        assert_eq!(events[4][0], "DTSTART:20260608T095500");
+
+        let uids: Vec<&String> = lines
+            .iter()
+            .filter(|line| line.starts_with("UID:"))
+            .collect();
+        assert_eq!(uids[0], "UID:interval-1-2026-06-08@flowtimer");
+        assert_eq!(uids[4], "UID:interval-1-2026-06-08-step-3@flowtimer");
+    }
+
+    #[test]
+    fn long_lines_are_folded_between_characters() {
+        let line = format!("SUMMARY:{}", "Ж".repeat(60));
+        let mut out = String::new();
+        push_line(&mut out, &line);
+
+        let physical: Vec<&str> = out.strip_suffix("\r\n").unwrap().split("\r\n").collect();
+        assert!(physical.iter().all(|part| part.len() <= 75));
+        // 8 октетов заголовка и 33 двухоктетных символа: 34-й не помещается целиком
+        assert_eq!(physical[0].len(), 74);
+        assert!(physical[1..].iter().all(|part| part.starts_with(' ')));
+        assert_eq!(unfold(&out), vec![line]);
+    }
+
+    fn block(date_value: &str, start: u32, end: u32, summary: &str) -> BusyBlock {
+        BusyBlock {
+            date: date(date_value),
+            start,
+            end,
+            summary: summary.into(),
+        }
+    }
+
+    /// Название, начало и конец (минуты) и признак встречи
+    fn slots(intervals: &[TimeInterval]) -> Vec<(String, u32, u32, bool)> {
+        intervals
+            .iter()
+            .map(|interval| {
+                (
+                    interval.name.clone(),
+                    interval.start.to_minutes(),
+                    interval.end.to_minutes(),
+                    matches!(interval.mode, IntervalMode::Static { screen_id: 3 }),
+                )
+            })
+            .collect()
+    }
+
+    const FOCUS_STEPS: [CycleStep; 2] = [
+        CycleStep {
+            screen_id: 1,
+            duration_minutes: 25,
+        },
+        CycleStep {
+            screen_id: 2,
+            duration_minutes: 5,
+        },
+    ];
+
+    fn busy_import(filler: bool) -> BusyImport<'static> {
+        BusyImport {
+            source: "work.ics",
+            meeting_screen_id: 3,
+            untitled: "Встреча",
+            filler: filler.then_some(("Фокус", &FOCUS_STEPS[..])),
+            day_start: TimeOfDay { hour: 9, minute: 0 },
+            day_end: TimeOfDay {
+                hour: 18,
+                minute: 0,
+            },
+        }
+    }
+
+    #[test]
+    fn recurring_meeting_skips_exdate_and_moved_occurrence() {
+        let text = "BEGIN:VCALENDAR\r\n\
+            BEGIN:VEVENT\r\nUID:standup\r\nSUMMARY:Стендап\r\n\
+            DTSTART:20260608T100000\r\nDTEND:20260608T101500\r\n\
+            RRULE:FREQ=DAILY;COUNT=5\r\nEXDATE:20260609T100000\r\nEND:VEVENT\r\n\
+            BEGIN:VEVENT\r\nUID:standup\r\nSUMMARY:Стендап\r\n\
+            RECURRENCE-ID:20260610T100000\r\n\
+            DTSTART:20260610T140000\r\nDTEND:20260610T143000\r\nEND:VEVENT\r\n\
+            END:VCALENDAR\r\n";
+        let busy = read_busy_blocks(text, date("2026-06-08"), 7).unwrap();
+        assert_eq!(
+            busy.blocks,
+            vec![
+                block("2026-06-08", 600, 615, "Стендап"),
+                block("2026-06-10", 840, 870, "Стендап"),
+                block("2026-06-11", 600, 615, "Стендап"),
+                block("2026-06-12", 600, 615, "Стендап"),
+            ]
+        );
+        assert_eq!(busy.skipped, 0);
+    }
+
+    #[test]
+    fn overlapping_and_adjacent_meetings_are_merged() {
+        let merged = merge_blocks(vec![
+            block("2026-06-10", 700, 720, "Ревью"),
+            block("2026-06-10", 600, 660, "Ревью"),
+            block("2026-06-10", 630, 700, "Планёрка"),
+            block("2026-06-10", 800, 830, ""),
+        ]);
+        assert_eq!(
+            merged,
+            vec![
+                block("2026-06-10", 600, 720, "Ревью / Планёрка"),
+                block("2026-06-10", 800, 830, ""),
+            ]
+        );
+    }
+
+    #[test]
+    fn gaps_are_filled_within_working_hours() {
+        let blocks = [
+            // Начинается до рабочего дня
+            block("2026-06-10", 8 * 60, 9 * 60 + 30, "Ранняя"),
+            block("2026-06-10", 10 * 60, 11 * 60, "Ревью"),
+            // Промежуток 10 минут короче первого шага цикла
+            block("2026-06-10", 11 * 60 + 10, 12 * 60, "Обед"),
+            // Заканчивается после рабочего дня
+            block("2026-06-10", 17 * 60 + 45, 19 * 60, "Созвон"),
+            block("2026-06-11", 13 * 60, 14 * 60, ""),
+        ];
+        let mut next_id = 10;
+        let intervals = busy_import(true).intervals(&blocks, &mut next_id);
+
+        let fill = |start, end| ("Фокус".to_string(), start, end, false);
+        let meeting = |name: &str, start, end| (name.to_string(), start, end, true);
+        assert_eq!(
+            slots(&intervals),
+            vec![
+                meeting("Ранняя", 480, 570),
+                fill(570, 600),
+                meeting("Ревью", 600, 660),
+                meeting("Обед", 670, 720),
+                fill(720, 1065),
+                meeting("Созвон", 1065, 1140),
+                // Без встреч до 13:00 цикл начинается с рабочего дня и заканчивается с ним
+                fill(540, 780),
+                meeting("Встреча", 780, 840),
+                fill(840, 1080),
+            ]
+        );
+        assert_eq!(
+            intervals.iter().map(|i| i.id).collect::<Vec<_>>(),
+            (10..19).collect::<Vec<_>>()
+        );
+        assert_eq!(next_id, 19);
+        assert!(
+            intervals
+                .iter()
+                .all(|i| i.source.as_deref() == Some("work.ics"))
+        );
+        assert_eq!(
+            intervals[1].mode,
+            IntervalMode::Cycle {
+                steps: FOCUS_STEPS.to_vec()
+            }
+        );
+    }
+
+    #[test]
+    fn same_slot_on_several_days_becomes_one_interval() {
+        let blocks = [
+            block("2026-06-10", 600, 660, "Планёрка"),
+            block("2026-06-11", 900, 960, "Планёрка"),
+            block("2026-06-12", 600, 660, "Планёрка"),
+        ];
+        let intervals = busy_import(false).intervals(&blocks, &mut 1);
+        assert_eq!(intervals.len(), 2);
+        assert_eq!(
+            intervals[0].recurrence,
+            Recurrence::Dates {
+                dates: vec![date("2026-06-10"), date("2026-06-12")]
+            }
+        );
+        assert_eq!(
+            intervals[1].recurrence,
+            Recurrence::Dates {
+                dates: vec![date("2026-06-11")]
+            }
+        );
+    }
+
+    #[test]
+    fn reimport_replaces_only_intervals_from_the_same_source() {
+        let tagged = |id, source: Option<&str>| TimeInterval {
+            id,
+            source: source.map(String::from),
+            ..interval((9, 0), (10, 0), Recurrence::Daily, static_screen())
+        };
+        let mut intervals = vec![
+            tagged(1, None),
+            tagged(2, Some("work.ics")),
+            tagged(3, Some("home.ics")),
+            tagged(4, Some("work.ics")),
+        ];
+        let removed = replace_imported(
+            &mut intervals,
+            "work.ics",
+            vec![tagged(5, Some("work.ics"))],
+        );
+        assert_eq!(removed, 2);
+        assert_eq!(
+            intervals.iter().map(|i| i.id).collect::<Vec<_>>(),
+            vec![1, 3, 5]
+        );
+    }
+}
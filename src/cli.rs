@@ -0,0 +1,802 @@
+//! Консольный режим без окна.
+//!
+//! `flow_timer status | next | schedule [--date ГГГГ-ММ-ДД] | validate [--json]`
+//! печатает состояние расписания и завершается, `flow_timer ics` выгружает его
+//! в iCalendar, `flow_timer history` - журнал сессий в CSV или JSON.
+//! Без подкоманды запускается окно.
+//!
+//! Команды управления (`pause`, `skip`, `start` и т.д.) передаются через
+//! управляющий сокет в уже запущенное окно; `status` при запущенном окне
+//! показывает его состояние с учётом паузы и разовой сессии.
+
+use std::{fmt, path::PathBuf};
+
+use chrono::{DateTime, Local, NaiveDate, Utc, Weekday};
+use fluent_bundle::{FluentArgs, FluentBundle, FluentResource};
+use serde::Serialize;
+
+use crate::{
+    calendar::export_ics,
+    config::{AppConfig, config_path, load_config, save_config},
+    history::{
+        ExportFormat, ExportGrouping, ExportOptions, History, export, history_path, week_start,
+    },
+    ipc::{self, IpcError, IpcRequest, StateSnapshot},
+    locale::{available_locales, make_bundle},
+    timer::{
+        ActiveScreenInfo, ScheduleShift, TransitionKind, calculate_next_transition,
+        determine_active_screen, format_duration_hhmmss, get_daily_transitions,
+        upcoming_transition,
+    },
+    utils::{tr, tr_with_args},
+    validation::{Severity, validate_profiles},
+};
+
+/// Подкоманда консольного режима
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Текущий экран и оставшееся время
+    Status,
+    /// Ближайший переход
+    Next,
+    /// Переходы за день (по умолчанию - сегодня)
+    Schedule { date: Option<NaiveDate> },
+    /// Проверка интервалов
+    Validate,
+    /// Расписание на день или неделю в формате iCalendar
+    Ics {
+        date: Option<NaiveDate>,
+        week: bool,
+        steps: bool,
+        output: Option<PathBuf>,
+    },
+    /// Журнал сессий за период (по умолчанию - с начала недели по сегодня)
+    History {
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        grouping: ExportGrouping,
+        rounding_minutes: u32,
+        output: Option<PathBuf>,
+    },
+    /// Список профилей или переключение на профиль (по id или имени)
+    Profile { name: Option<String> },
+    /// Команда запущенному окну через управляющий сокет
+    Control(IpcRequest),
+    /// Справка
+    Help,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CliArgs {
+    pub command: Command,
+    /// Машиночитаемый вывод
+    pub json: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CliError {
+    UnknownCommand(String),
+    UnknownOption(String),
+    MissingValue(&'static str),
+    InvalidDate(String),
+    InvalidNumber(String),
+    InvalidGrouping(String),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::UnknownCommand(cmd) => write!(f, "неизвестная команда: {}", cmd),
+            CliError::UnknownOption(opt) => write!(f, "неизвестный параметр: {}", opt),
+            CliError::MissingValue(opt) => write!(f, "не указано значение для {}", opt),
+            CliError::InvalidDate(value) => {
+                write!(f, "некорректная дата '{}', ожидается ГГГГ-ММ-ДД", value)
+            }
+            CliError::InvalidNumber(value) => {
+                write!(f, "некорректное число '{}'", value)
+            }
+            CliError::InvalidGrouping(value) => write!(
+                f,
+                "некорректная группировка '{}', ожидается sessions, interval или screen",
+                value
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+const USAGE: &str = "\
+FlowTimer
+
+Usage:
+  flow_timer                         open the timer window
+  flow_timer status [--json]         current screen and remaining time
+  flow_timer next [--json]           the next transition
+  flow_timer schedule [--date YYYY-MM-DD] [--json]
+                                     transitions for a day (today by default)
+  flow_timer validate [--json]       check intervals; exit code 1 on problems
+  flow_timer ics [--date YYYY-MM-DD] [--week] [--steps] [--output FILE]
+                                     export a day (or 7 days) as iCalendar;
+                                     --steps adds cycle steps as events
+  flow_timer history [--from YYYY-MM-DD] [--to YYYY-MM-DD]
+                     [--group sessions|interval|screen] [--round MIN]
+                     [--output FILE] [--json]
+                                     export the session history as CSV
+                                     (JSON with --json or a .json file);
+                                     this week by default, --round rounds
+                                     each row up to MIN minutes
+  flow_timer profile [NAME|ID] [--json]
+                                     list profiles or switch to one
+                                     (in the running window, if any)
+  flow_timer help                    show this help
+
+Commands for the running window (exit code 1 if it is not running):
+  flow_timer show                    raise the timer window
+  flow_timer pause | resume | toggle pause or resume the timer
+  flow_timer skip                    skip the current cycle step
+  flow_timer extend [--minutes N]    extend the current cycle step
+  flow_timer restart                 restart the cycle
+  flow_timer start [--screen ID] [--minutes N] [--interval ID] [--repeats N]
+                                     start a one-off session
+  flow_timer stop                    stop the one-off session
+  flow_timer reload                  re-read config.json
+";
+
+/// Разбирает аргументы командной строки (без имени программы).
+/// `Ok(None)` означает, что подкоманды нет и нужно запустить окно.
+pub fn parse_args<I>(args: I) -> Result<Option<CliArgs>, CliError>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut args = args.into_iter();
+    let Some(name) = args.next() else {
+        return Ok(None);
+    };
+
+    let mut command = match name.as_str() {
+        "status" => Command::Status,
+        "next" => Command::Next,
+        "schedule" => Command::Schedule { date: None },
+        "validate" => Command::Validate,
+        "ics" => Command::Ics {
+            date: None,
+            week: false,
+            steps: false,
+            output: None,
+        },
+        "history" => Command::History {
+            from: None,
+            to: None,
+            grouping: ExportGrouping::Sessions,
+            rounding_minutes: 0,
+            output: None,
+        },
+        "profile" => Command::Profile { name: None },
+        "show" => Command::Control(IpcRequest::Focus),
+        "pause" => Command::Control(IpcRequest::Pause),
+        "resume" => Command::Control(IpcRequest::Resume),
+        "toggle" => Command::Control(IpcRequest::TogglePause),
+        "skip" => Command::Control(IpcRequest::Skip),
+        "extend" => Command::Control(IpcRequest::Extend { minutes: None }),
+        "restart" => Command::Control(IpcRequest::Restart),
+        "start" => Command::Control(IpcRequest::StartAdhoc {
+            screen_id: None,
+            minutes: None,
+            interval_id: None,
+            repeats: None,
+        }),
+        "stop" => Command::Control(IpcRequest::StopAdhoc),
+        "reload" => Command::Control(IpcRequest::Reload),
+        "help" | "--help" | "-h" => Command::Help,
+        _ => return Err(CliError::UnknownCommand(name)),
+    };
+
+    let mut json = false;
+    while let Some(arg) = args.next() {
+        match (arg.as_str(), &mut command) {
+            ("--json", _) => json = true,
+            ("--date", Command::Schedule { date }) | ("--date", Command::Ics { date, .. }) => {
+                let value = args.next().ok_or(CliError::MissingValue("--date"))?;
+                *date = Some(parse_date(&value)?);
+            }
+            ("--week", Command::Ics { week, .. }) => *week = true,
+            ("--steps", Command::Ics { steps, .. }) => *steps = true,
+            ("--from", Command::History { from, .. }) => {
+                let value = args.next().ok_or(CliError::MissingValue("--from"))?;
+                *from = Some(parse_date(&value)?);
+            }
+            ("--to", Command::History { to, .. }) => {
+                let value = args.next().ok_or(CliError::MissingValue("--to"))?;
+                *to = Some(parse_date(&value)?);
+            }
+            ("--group", Command::History { grouping, .. }) => {
+                let value = args.next().ok_or(CliError::MissingValue("--group"))?;
+                *grouping =
+                    ExportGrouping::from_name(&value).ok_or(CliError::InvalidGrouping(value))?;
+            }
+            (
+                "--round",
+                Command::History {
+                    rounding_minutes, ..
+                },
+            ) => {
+                *rounding_minutes = parse_number(args.next(), "--round")?;
+            }
+            ("--output", Command::Ics { output, .. })
+            | ("--output", Command::History { output, .. }) => {
+                let value = args.next().ok_or(CliError::MissingValue("--output"))?;
+                *output = Some(PathBuf::from(value));
+            }
+            ("--minutes", Command::Control(IpcRequest::Extend { minutes }))
+            | ("--minutes", Command::Control(IpcRequest::StartAdhoc { minutes, .. })) => {
+                *minutes = Some(parse_number(args.next(), "--minutes")?);
+            }
+            ("--screen", Command::Control(IpcRequest::StartAdhoc { screen_id, .. })) => {
+                *screen_id = Some(parse_number(args.next(), "--screen")?);
+            }
+            ("--interval", Command::Control(IpcRequest::StartAdhoc { interval_id, .. })) => {
+                *interval_id = Some(parse_number(args.next(), "--interval")?);
+            }
+            ("--repeats", Command::Control(IpcRequest::StartAdhoc { repeats, .. })) => {
+                *repeats = Some(parse_number(args.next(), "--repeats")?);
+            }
+            (value, Command::Profile { name }) if name.is_none() && !value.starts_with("--") => {
+                *name = Some(arg);
+            }
+            _ => return Err(CliError::UnknownOption(arg)),
+        }
+    }
+
+    Ok(Some(CliArgs { command, json }))
+}
+
+fn parse_date(value: &str) -> Result<NaiveDate, CliError> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| CliError::InvalidDate(value.into()))
+}
+
+fn parse_number(value: Option<String>, option: &'static str) -> Result<u32, CliError> {
+    let value = value.ok_or(CliError::MissingValue(option))?;
+    value.parse().map_err(|_| CliError::InvalidNumber(value))
+}
+
+/// Выполняет подкоманду и возвращает код завершения процесса
+pub fn run(args: &CliArgs) -> i32 {
+    if args.command == Command::Help {
+        print!("{}", USAGE);
+        return 0;
+    }
+
+    // Консольные команды только читают config.json и не создают его
+    let mut config = match load_config(&config_path()) {
+        Ok(config) => config,
+        Err(err) if err.is_not_found() => AppConfig::default(),
+        Err(err) => {
+            eprintln!("flow_timer: {}", err);
+            return 2;
+        }
+    };
+    let mut bundle = make_bundle(&config.language, &available_locales());
+    // Символы изоляции Fluent в терминале только мешают
+    bundle.set_use_isolating(false);
+    let now = Local::now();
+    // Профиль по дню недели, как его выбрало бы окно (без записи в файл)
+    config.apply_auto_profile(now.date_naive());
+
+    let (output, code) = match &args.command {
+        // Запущенное окно знает о паузе и разовой сессии
+        Command::Status => match ipc::send_request(&IpcRequest::State)
+            .ok()
+            .and_then(|response| response.state)
+        {
+            Some(state) => (running_status(&bundle, &state, args.json), 0),
+            None => (status(&config, &bundle, now, args.json), 0),
+        },
+        Command::Next => (next(&config, &bundle, now, args.json), 0),
+        Command::Schedule { date } => {
+            let date = date.unwrap_or_else(|| now.date_naive());
+            (schedule(&config, &bundle, date, args.json), 0)
+        }
+        Command::Validate => validate(&config, &bundle, args.json),
+        Command::Ics {
+            date,
+            week,
+            steps,
+            output,
+        } => {
+            let from = date.unwrap_or_else(|| now.date_naive());
+            let days = if *week { 7 } else { 1 };
+            let ics = export_ics(&config, from, days, *steps, Utc::now());
+            file_output(&bundle, ics, output.as_deref(), "cli-ics-saved")
+        }
+        Command::History {
+            from,
+            to,
+            grouping,
+            rounding_minutes,
+            output,
+        } => {
+            let to = to.unwrap_or_else(|| now.date_naive());
+            let options = ExportOptions {
+                from: from.unwrap_or_else(|| week_start(to)),
+                to,
+                grouping: *grouping,
+                rounding_minutes: *rounding_minutes,
+            };
+            let format = match output {
+                _ if args.json => ExportFormat::Json,
+                Some(path) => ExportFormat::from_path(path),
+                None => ExportFormat::Csv,
+            };
+            history_output(&bundle, &options, format, output.as_deref())
+        }
+        Command::Profile { name: None } => (profiles(&config, &bundle, args.json), 0),
+        Command::Profile { name: Some(name) } => switch_profile(config, &bundle, name, args.json),
+        Command::Control(request) => control(&bundle, request, args.json),
+        Command::Help => unreachable!(),
+    };
+
+    if !output.is_empty() {
+        println!("{}", output);
+    }
+    code
+}
+
+/// Выгружает журнал сессий
+fn history_output(
+    bundle: &FluentBundle<FluentResource>,
+    options: &ExportOptions,
+    format: ExportFormat,
+    output: Option<&std::path::Path>,
+) -> (String, i32) {
+    let path = history_path(&config_path());
+    let history = match History::load(&path) {
+        Ok(history) => history,
+        Err(err) => {
+            eprintln!("flow_timer: {}: {}", path.display(), err);
+            return (String::new(), 2);
+        }
+    };
+    if history.broken_lines > 0 {
+        let mut args = FluentArgs::new();
+        args.set("count", history.broken_lines);
+        eprintln!(
+            "{}",
+            tr_with_args(bundle, "stats-broken-lines", Some(&args))
+        );
+    }
+    let mut text = export(&history, options, format);
+    if !text.ends_with('\n') {
+        text.push('\n');
+    }
+    file_output(bundle, text, output, "cli-history-saved")
+}
+
+/// Печатает выгрузку или записывает её в файл; `saved_key` - сообщение об успешной записи
+fn file_output(
+    bundle: &FluentBundle<FluentResource>,
+    text: String,
+    output: Option<&std::path::Path>,
+    saved_key: &str,
+) -> (String, i32) {
+    let Some(path) = output else {
+        // Выгрузка уже заканчивается переводом строки
+        print!("{}", text);
+        return (String::new(), 0);
+    };
+    match std::fs::write(path, text) {
+        Ok(()) => {
+            let mut args = FluentArgs::new();
+            args.set("path", path.display().to_string());
+            (tr_with_args(bundle, saved_key, Some(&args)), 0)
+        }
+        Err(err) => {
+            eprintln!("flow_timer: {}: {}", path.display(), err);
+            (String::new(), 1)
+        }
+    }
+}
+
+fn to_json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string_pretty(value).expect("serialize cli output")
+}
+
+fn kind_name(kind: TransitionKind) -> &'static str {
+    match kind {
+        TransitionKind::IntervalStart => "start",
+        TransitionKind::IntervalEnd => "end",
+        TransitionKind::Step => "step",
+    }
+}
+
+#[derive(Serialize)]
+struct ScreenJson {
+    id: u32,
+    title: String,
+    subtitle: String,
+    color: String,
+}
+
+#[derive(Serialize)]
+struct StatusJson {
+    screen: Option<ScreenJson>,
+    interval: Option<String>,
+    is_default_screen: bool,
+    remaining_seconds: u64,
+    next_transition: Option<DateTime<Local>>,
+    paused: bool,
+    /// Название разовой сессии
+    adhoc: Option<String>,
+}
+
+impl StatusJson {
+    fn new(
+        bundle: &FluentBundle<FluentResource>,
+        active: Option<&ActiveScreenInfo>,
+        next_transition: Option<DateTime<Local>>,
+    ) -> Self {
+        match active {
+            Some(info) => StatusJson {
+                screen: Some(ScreenJson {
+                    id: info.screen_id,
+                    title: info.display_title(bundle),
+                    subtitle: info.display_subtitle(bundle),
+                    color: info.color.to_hex(),
+                }),
+                interval: info.interval_name.clone(),
+                is_default_screen: info.is_default_screen(),
+                remaining_seconds: info.remaining_seconds,
+                next_transition,
+                paused: false,
+                adhoc: None,
+            },
+            None => StatusJson {
+                screen: None,
+                interval: None,
+                is_default_screen: false,
+                remaining_seconds: 0,
+                next_transition,
+                paused: false,
+                adhoc: None,
+            },
+        }
+    }
+}
+
+fn status_line(bundle: &FluentBundle<FluentResource>, active: Option<&ActiveScreenInfo>) -> String {
+    let Some(info) = active else {
+        return tr(bundle, "main-no-screens");
+    };
+    let mut args = FluentArgs::new();
+    args.set("screen", info.display_title(bundle));
+    args.set("interval", info.interval_label(bundle));
+    args.set("time", format_duration_hhmmss(info.remaining_seconds));
+    tr_with_args(bundle, "cli-status", Some(&args))
+}
+
+/// Состояние по расписанию, когда окно не запущено: паузы и разовой сессии нет
+fn status(
+    config: &AppConfig,
+    bundle: &FluentBundle<FluentResource>,
+    now: DateTime<Local>,
+    json: bool,
+) -> String {
+    let shift = ScheduleShift::default();
+    let active = determine_active_screen(config, now, &shift, None);
+    let next_transition = calculate_next_transition(config, now, &shift, None);
+
+    if json {
+        return to_json(&StatusJson::new(bundle, active.as_ref(), next_transition));
+    }
+    status_line(bundle, active.as_ref())
+}
+
+/// Состояние запущенного окна
+fn running_status(
+    bundle: &FluentBundle<FluentResource>,
+    state: &StateSnapshot,
+    json: bool,
+) -> String {
+    let active = state.current_screen.as_ref();
+
+    if json {
+        let mut status = StatusJson::new(bundle, active, state.next_transition);
+        status.paused = state.paused_at.is_some();
+        status.adhoc = state.adhoc.as_ref().map(|session| session.name.clone());
+        return to_json(&status);
+    }
+
+    let line = status_line(bundle, active);
+    if state.paused_at.is_some() {
+        format!("{}\n{}", line, tr(bundle, "cli-paused"))
+    } else {
+        line
+    }
+}
+
+/// Передаёт команду запущенному окну
+fn control(
+    bundle: &FluentBundle<FluentResource>,
+    request: &IpcRequest,
+    json: bool,
+) -> (String, i32) {
+    let response = match ipc::send_request(request) {
+        Ok(response) => response,
+        Err(IpcError::Io(_)) => return (tr(bundle, "cli-not-running"), 1),
+        Err(err) => return (err.to_string(), 1),
+    };
+    let code = if response.ok { 0 } else { 1 };
+
+    if json {
+        return (to_json(&response), code);
+    }
+    match (&response.state, &response.error) {
+        (Some(state), _) => (running_status(bundle, state, false), code),
+        (None, Some(error)) => (error.clone(), code),
+        (None, None) => (String::new(), code),
+    }
+}
+
+#[derive(Serialize)]
+struct NextJson {
+    at: DateTime<Local>,
+    in_seconds: i64,
+    kind: &'static str,
+    screen_id: u32,
+    title: String,
+}
+
+fn next(
+    config: &AppConfig,
+    bundle: &FluentBundle<FluentResource>,
+    now: DateTime<Local>,
+    json: bool,
+) -> String {
+    let shift = ScheduleShift::default();
+    let upcoming = calculate_next_transition(config, now, &shift, None)
+        .and_then(|at| upcoming_transition(config, now, at, &shift, None));
+
+    let Some(upcoming) = upcoming else {
+        return if json {
+            "null".to_string()
+        } else {
+            tr(bundle, "cli-no-next")
+        };
+    };
+    let in_seconds = (upcoming.at - now).num_seconds().max(0);
+
+    if json {
+        return to_json(&NextJson {
+            at: upcoming.at,
+            in_seconds,
+            kind: kind_name(upcoming.kind),
+            screen_id: upcoming.next_screen_id,
+            title: upcoming.next_title,
+        });
+    }
+
+    let mut args = FluentArgs::new();
+    args.set("screen", upcoming.next_title.as_str());
+    args.set("time", upcoming.at.format("%H:%M").to_string());
+    args.set("left", format_duration_hhmmss(in_seconds as u64));
+    tr_with_args(bundle, "cli-next", Some(&args))
+}
+
+#[derive(Serialize)]
+struct TransitionJson {
+    time: String,
+    minute: u32,
+    kind: &'static str,
+    description: String,
+}
+
+#[derive(Serialize)]
+struct ScheduleJson {
+    date: NaiveDate,
+    transitions: Vec<TransitionJson>,
+}
+
+fn schedule(
+    config: &AppConfig,
+    bundle: &FluentBundle<FluentResource>,
+    date: NaiveDate,
+    json: bool,
+) -> String {
+    let transitions = get_daily_transitions(config, date);
+
+    if json {
+        return to_json(&ScheduleJson {
+            date,
+            transitions: transitions
+                .into_iter()
+                .map(|transition| TransitionJson {
+                    time: format!(
+                        "{:02}:{:02}",
+                        transition.minute / 60,
+                        transition.minute % 60
+                    ),
+                    minute: transition.minute,
+                    kind: kind_name(transition.kind),
+                    description: transition.description(bundle),
+                })
+                .collect(),
+        });
+    }
+
+    if transitions.is_empty() {
+        return tr(bundle, "schedule-none");
+    }
+    transitions
+        .iter()
+        .map(|transition| {
+            format!(
+                "{:02}:{:02}  {}",
+                transition.minute / 60,
+                transition.minute % 60,
+                transition.description(bundle)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[derive(Serialize)]
+struct ProfileJson {
+    id: u32,
+    name: String,
+    active: bool,
+    intervals: usize,
+    auto_days: Vec<Weekday>,
+}
+
+fn profiles(config: &AppConfig, bundle: &FluentBundle<FluentResource>, json: bool) -> String {
+    if json {
+        let profiles: Vec<ProfileJson> = config
+            .profiles
+            .iter()
+            .map(|profile| ProfileJson {
+                id: profile.id,
+                name: profile.name.clone(),
+                active: profile.id == config.active_profile_id,
+                intervals: profile.intervals.len(),
+                auto_days: profile.auto_days.clone(),
+            })
+            .collect();
+        return to_json(&profiles);
+    }
+
+    config
+        .profiles
+        .iter()
+        .map(|profile| {
+            let marker = if profile.id == config.active_profile_id {
+                '*'
+            } else {
+                ' '
+            };
+            let mut line = format!("{} {:>3}  {}", marker, profile.id, profile.name);
+            if !profile.auto_days.is_empty() {
+                let days: Vec<String> = profile.auto_days.iter().map(|d| d.to_string()).collect();
+                let mut args = FluentArgs::new();
+                args.set("days", days.join(", "));
+                line.push_str("  ");
+                line.push_str(&tr_with_args(bundle, "cli-profile-auto", Some(&args)));
+            }
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Переключает профиль: в запущенном окне через сокет, иначе прямо в config.json
+fn switch_profile(
+    mut config: AppConfig,
+    bundle: &FluentBundle<FluentResource>,
+    name: &str,
+    json: bool,
+) -> (String, i32) {
+    let Some(profile) = config.find_profile(name) else {
+        let mut args = FluentArgs::new();
+        args.set("name", name);
+        return (tr_with_args(bundle, "cli-profile-unknown", Some(&args)), 1);
+    };
+    let (id, profile_name) = (profile.id, profile.name.clone());
+
+    let request = IpcRequest::SwitchProfile {
+        profile: id.to_string(),
+    };
+    match ipc::send_request(&request) {
+        Ok(response) => {
+            let code = if response.ok { 0 } else { 1 };
+            if json {
+                return (to_json(&response), code);
+            }
+            if let Some(error) = response.error {
+                return (error, code);
+            }
+        }
+        // Окно не запущено - меняем файл сами
+        Err(IpcError::Io(_) | IpcError::Unsupported) => {
+            config.set_active_profile(id);
+            if let Err(err) = save_config(&config_path(), &config) {
+                return (err.to_string(), 1);
+            }
+            if json {
+                return (
+                    to_json(&ProfileJson {
+                        id,
+                        name: profile_name,
+                        active: true,
+                        intervals: config.intervals().len(),
+                        auto_days: config.active_profile().auto_days.clone(),
+                    }),
+                    0,
+                );
+            }
+        }
+        Err(err) => return (err.to_string(), 1),
+    }
+
+    let mut args = FluentArgs::new();
+    args.set("name", profile_name);
+    (tr_with_args(bundle, "cli-profile-switched", Some(&args)), 0)
+}
+
+#[derive(Serialize)]
+struct ValidateJson {
+    valid: bool,
+    /// Тексты проблем (как в обычном выводе)
+    errors: Vec<String>,
+    issues: Vec<IssueJson>,
+}
+
+#[derive(Serialize)]
+struct IssueJson {
+    code: &'static str,
+    severity: Severity,
+    message: String,
+    fix: String,
+    intervals: Vec<u32>,
+}
+
+fn validate(
+    config: &AppConfig,
+    bundle: &FluentBundle<FluentResource>,
+    json: bool,
+) -> (String, i32) {
+    let issues = validate_profiles(config);
+    let code = if issues.is_empty() { 0 } else { 1 };
+
+    if json {
+        let issues: Vec<IssueJson> = issues
+            .iter()
+            .map(|issue| IssueJson {
+                code: issue.code(),
+                severity: issue.severity(),
+                message: issue.message(bundle),
+                fix: issue.fix(bundle),
+                intervals: issue.interval_ids(),
+            })
+            .collect();
+        let result = ValidateJson {
+            valid: issues.is_empty(),
+            errors: issues.iter().map(|issue| issue.message.clone()).collect(),
+            issues,
+        };
+        return (to_json(&result), code);
+    }
+
+    if issues.is_empty() {
+        return (tr(bundle, "validation-ok"), code);
+    }
+    let mut lines = vec![tr(bundle, "validation-problems-found")];
+    for issue in &issues {
+        let mark = match issue.severity() {
+            Severity::Error => "✖",
+            Severity::Warning => "⚠",
+        };
+        lines.push(format!("  {} {}", mark, issue.message(bundle)));
+        lines.push(format!("    → {}", issue.fix(bundle)));
+    }
+    (lines.join("\n"), code)
+}
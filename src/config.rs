@@ -1,13 +1,19 @@
-use std::{fs, path::PathBuf};
+use std::{
+    fmt, fs,
+    io::Write,
+    path::{Path, PathBuf},
+    time::{Duration as StdDuration, Instant, SystemTime},
+};
 
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
 use directories::ProjectDirs;
 use eframe::egui;
 use serde::{Deserialize, Serialize};
 use unic_langid::LanguageIdentifier;
 
+use crate::migrations::{self, CURRENT_SCHEMA_VERSION, MigrationError};
 
-
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Rgba8 {
     pub r: u8,
     pub g: u8,
@@ -20,42 +26,67 @@ impl Rgba8 {
         egui::Color32::from_rgba_premultiplied(self.r, self.g, self.b, self.a)
     }
 
+    /// Цвет в виде `#rrggbb`
+    pub fn to_hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ScreenConfig {
     pub id: u32,
     pub title: String,
     pub subtitle: String,
     pub color: Rgba8,
+    /// Собственный звук (WAV/OGG), который играет при переходе на этот экран
+    #[serde(default)]
+    pub sound: Option<PathBuf>,
+    /// Показывать уведомление рабочего стола при переходе на этот экран
+    #[serde(default = "default_true")]
+    pub notify: bool,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+fn default_true() -> bool {
+    true
+}
+
+/// Количество минут в сутках
+pub const MINUTES_PER_DAY: u32 = 24 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TimeOfDay {
     pub hour: u8,  // 0..=23
     pub minute: u8, // 0..=59
 }
 
 impl TimeOfDay {
-
     pub fn to_minutes(self) -> u32 {
         self.hour as u32 * 60 + self.minute as u32
     }
+
+    /// Время по числу минут от полуночи (по модулю суток)
+    pub fn from_minutes(minutes: u32) -> Self {
+        let minutes = minutes % MINUTES_PER_DAY;
+        Self {
+            hour: (minutes / 60) as u8,
+            minute: (minutes % 60) as u8,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct TimeRange {
     pub start: TimeOfDay,
-    pub end: TimeOfDay, // предполагаем start < end в рамках одного дня
+    pub end: TimeOfDay, // если end < start, диапазон переходит через полночь
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CycleStep {
     pub screen_id: u32,
     pub duration_minutes: u32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "mode", rename_all = "snake_case")]
 pub enum IntervalMode {
     Static {
@@ -66,55 +97,474 @@ pub enum IntervalMode {
     },
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Правило повторения интервала по дням
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(tag = "repeat", rename_all = "snake_case")]
+pub enum Recurrence {
+    /// Каждый день
+    #[default]
+    Daily,
+    /// По будням (пн–пт)
+    Weekdays,
+    /// По выходным (сб, вс)
+    Weekends,
+    /// В выбранные дни недели
+    Days { days: Vec<Weekday> },
+    /// Каждый день в диапазоне дат (включительно)
+    DateRange { from: NaiveDate, to: NaiveDate },
+    /// В отдельные даты
+    Dates { dates: Vec<NaiveDate> },
+}
+
+impl Recurrence {
+    /// Срабатывает ли правило в указанную дату
+    pub fn occurs_on(&self, date: NaiveDate) -> bool {
+        match self {
+            Recurrence::Daily => true,
+            Recurrence::Weekdays => date.weekday().num_days_from_monday() < 5,
+            Recurrence::Weekends => date.weekday().num_days_from_monday() >= 5,
+            Recurrence::Days { days } => days.contains(&date.weekday()),
+            Recurrence::DateRange { from, to } => *from <= date && date <= *to,
+            Recurrence::Dates { dates } => dates.contains(&date),
+        }
+    }
+
+    /// Ближайшая дата не раньше `date`, в которую правило срабатывает.
+    /// Правила по дням недели повторяются каждую неделю, поэтому хватает семи дней.
+    pub fn next_occurrence(&self, date: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            Recurrence::DateRange { from, to } => Some(date.max(*from)).filter(|d| d <= to),
+            Recurrence::Dates { dates } => dates.iter().filter(|d| **d >= date).min().copied(),
+            _ => date.iter_days().take(7).find(|d| self.occurs_on(*d)),
+        }
+    }
+
+    /// Границы дат, вне которых правило никогда не срабатывает
+    fn date_bounds(&self) -> (Option<NaiveDate>, Option<NaiveDate>) {
+        match self {
+            Recurrence::DateRange { from, to } => (Some(*from), Some(*to)),
+            Recurrence::Dates { dates } => {
+                (dates.iter().min().copied(), dates.iter().max().copied())
+            }
+            _ => (None, None),
+        }
+    }
+
+    /// Есть ли дата X, в которую срабатывает `self`, а `other` срабатывает в X + `shift_days`
+    pub fn shares_day_with(&self, other: &Recurrence, shift_days: i64) -> bool {
+        let shift = Duration::days(shift_days);
+
+        // Отдельные даты проверяем напрямую
+        if let Recurrence::Dates { dates } = self {
+            return dates.iter().any(|d| other.occurs_on(*d + shift));
+        }
+        if let Recurrence::Dates { dates } = other {
+            return dates.iter().any(|d| self.occurs_on(*d - shift));
+        }
+
+        // Остальные правила периодичны по неделе: достаточно проверить до 7 дней
+        // в пересечении диапазонов дат
+        let (self_from, self_to) = self.date_bounds();
+        let (other_from, other_to) = other.date_bounds();
+        let other_from = other_from.map(|d| d - shift);
+        let other_to = other_to.map(|d| d - shift);
+
+        let from = self_from.into_iter().chain(other_from).max();
+        let to = self_to.into_iter().chain(other_to).min();
+        if let (Some(from), Some(to)) = (from, to)
+            && from > to
+        {
+            return false;
+        }
+
+        let first = from
+            .or_else(|| to.map(|d| d - Duration::days(6)))
+            .unwrap_or_default();
+        (0..7)
+            .map(|offset| first + Duration::days(offset))
+            .take_while(|date| to.is_none_or(|to| *date <= to))
+            .any(|date| self.occurs_on(date) && other.occurs_on(date + shift))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TimeInterval {
     pub id: u32,
     pub name: String,
     pub start: TimeOfDay,
     pub end: TimeOfDay,
     pub mode: IntervalMode,
+    #[serde(default)]
+    pub recurrence: Recurrence,
+    /// Собственное время предупреждения перед переходами, минут (0 - без предупреждений).
+    /// Если не задано, используется глобальная настройка.
+    #[serde(default)]
+    pub warning_minutes: Option<u32>,
+    /// Откуда импортирован интервал (файл .ics). При повторном импорте из того же
+    /// источника такие интервалы заменяются; созданные вручную не трогаются.
+    #[serde(default)]
+    pub source: Option<String>,
+}
+
+impl TimeInterval {
+    /// Интервал переходит через полночь (например, 22:00–02:00)
+    pub fn crosses_midnight(&self) -> bool {
+        self.end.to_minutes() < self.start.to_minutes()
+    }
+
+    /// Длительность интервала в минутах с учетом перехода через полночь
+    pub fn duration_minutes(&self) -> u32 {
+        let start_min = self.start.to_minutes();
+        let end_min = self.end.to_minutes();
+        if end_min >= start_min {
+            end_min - start_min
+        } else {
+            MINUTES_PER_DAY - start_min + end_min
+        }
+    }
+
+    /// Сколько минут прошло с начала интервала, если момент `now_min` попадает в него
+    pub fn minutes_into(&self, now_min: u32) -> Option<u32> {
+        let into = (now_min + MINUTES_PER_DAY - self.start.to_minutes()) % MINUTES_PER_DAY;
+        (into < self.duration_minutes()).then_some(into)
+    }
+
+    /// Вхождение интервала, активное в момент `now_min` даты `date`: дата начала вхождения
+    /// и сколько минут прошло с его начала. Интервал через полночь после 00:00 относится
+    /// к вхождению, начавшемуся накануне.
+    pub fn active_occurrence(&self, date: NaiveDate, now_min: u32) -> Option<(NaiveDate, u32)> {
+        let into = self.minutes_into(now_min)?;
+        let occurrence_date = if into > now_min {
+            date.pred_opt()?
+        } else {
+            date
+        };
+        self.recurrence
+            .occurs_on(occurrence_date)
+            .then_some((occurrence_date, into))
+    }
+
+    /// Пересекаются ли интервалы по времени в какой-либо общий день
+    pub fn overlaps(&self, other: &TimeInterval) -> bool {
+        if self.duration_minutes() == 0 || other.duration_minutes() == 0 {
+            return false;
+        }
+
+        let self_start = self.start.to_minutes() as i64;
+        let self_end = self_start + self.duration_minutes() as i64;
+
+        // Интервалы короче суток, поэтому достаточно сравнить вхождения в тот же,
+        // предыдущий и следующий день
+        (-1..=1).any(|shift_days: i64| {
+            let other_start = shift_days * MINUTES_PER_DAY as i64 + other.start.to_minutes() as i64;
+            let other_end = other_start + other.duration_minutes() as i64;
+            self_start < other_end
+                && other_start < self_end
+                && self
+                    .recurrence
+                    .shares_day_with(&other.recurrence, shift_days)
+        })
+    }
+}
+
+/// Как пауза влияет на расписание текущего интервала
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PauseMode {
+    /// Время паузы сдвигает текущий и оставшиеся шаги цикла, конец интервала не меняется
+    #[default]
+    ShiftSteps,
+    /// Время паузы сдвигает шаги и продлевает интервал на ту же величину
+    ExtendInterval,
+}
+
+/// Встроенный сигнал, синтезируемый без звуковых файлов
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Chime {
+    /// Короткий мягкий тон
+    #[default]
+    Soft,
+    /// Колокольчик из двух нот
+    Bell,
+    /// Три восходящие ноты
+    Rising,
+}
+
+/// Настройки звуковых уведомлений
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SoundSettings {
+    /// Громкость 0.0..=1.0
+    pub volume: f32,
+    pub chime: Chime,
+    /// Собственные звуки для типов переходов; если не заданы, играет встроенный сигнал
+    pub interval_start: Option<PathBuf>,
+    pub interval_end: Option<PathBuf>,
+    pub step: Option<PathBuf>,
+}
+
+impl Default for SoundSettings {
+    fn default() -> Self {
+        Self {
+            volume: 0.7,
+            chime: Chime::default(),
+            interval_start: None,
+            interval_end: None,
+            step: None,
+        }
+    }
+}
+
+/// Способ автозапуска на Linux
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AutostartMethod {
+    /// Запись в `~/.config/autostart`
+    #[default]
+    Xdg,
+    /// Пользовательский unit systemd
+    Systemd,
+}
+
+/// Предупреждения за несколько минут до смены экрана
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WarningSettings {
+    pub enabled: bool,
+    /// За сколько минут до перехода предупреждать
+    pub lead_minutes: u32,
+    /// Плашка на главном экране
+    pub banner: bool,
+    /// Звуковой сигнал
+    pub sound: bool,
+    /// Уведомление рабочего стола
+    pub notification: bool,
+    /// Пульсация цвета фона
+    pub pulse: bool,
+}
+
+impl Default for WarningSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            lead_minutes: 2,
+            banner: true,
+            sound: false,
+            notification: false,
+            pulse: true,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemSettings {
     pub autostart: bool,
+    #[serde(default)]
+    pub autostart_method: AutostartMethod,
     pub sound_notifications: bool,
+    /// Уведомления рабочего стола о переходах
+    #[serde(default)]
+    pub desktop_notifications: bool,
     pub window_position: Option<WindowPosition>,
+    #[serde(default)]
+    pub pause_mode: PauseMode,
+    /// На сколько минут продлевается шаг цикла кнопкой «продлить»
+    #[serde(default = "default_extend_step_minutes")]
+    pub extend_step_minutes: u32,
+    #[serde(default)]
+    pub sound: SoundSettings,
+    #[serde(default)]
+    pub warnings: WarningSettings,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+fn default_extend_step_minutes() -> u32 {
+    5
+}
+
+/// Положение окна (левый верхний угол внешней рамки) в логических точках
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WindowPosition {
     pub x: f32,
     pub y: f32,
+    /// Внутренний размер окна
+    #[serde(default)]
+    pub size: Option<[f32; 2]>,
+    /// Размер монитора, на котором окно находилось при сохранении
+    #[serde(default)]
+    pub monitor_size: Option<[f32; 2]>,
+}
+
+impl WindowPosition {
+    /// Сдвигает окно с внешним размером `outer_size` так, чтобы оно целиком
+    /// помещалось на мониторе размера `monitor`. Если монитор тот же, что при
+    /// сохранении, положение не меняется: на конфигурации из нескольких мониторов
+    /// координаты могут законно выходить за пределы одного из них.
+    pub fn clamped_to(&self, outer_size: [f32; 2], monitor: [f32; 2]) -> Option<(f32, f32)> {
+        if self.monitor_size == Some(monitor) {
+            return None;
+        }
+
+        let max_x = (monitor[0] - outer_size[0]).max(0.0);
+        let max_y = (monitor[1] - outer_size[1]).max(0.0);
+        let clamped = (self.x.clamp(0.0, max_x), self.y.clamp(0.0, max_y));
+        (clamped != (self.x, self.y)).then_some(clamped)
+    }
 }
 
 impl Default for SystemSettings {
     fn default() -> Self {
         Self {
             autostart: false,
+            autostart_method: AutostartMethod::default(),
             sound_notifications: false,
+            desktop_notifications: false,
             window_position: None,
+            pause_mode: PauseMode::default(),
+            extend_step_minutes: default_extend_step_minutes(),
+            sound: SoundSettings::default(),
+            warnings: WarningSettings::default(),
+        }
+    }
+}
+
+/// Профиль: именованный набор интервалов со своим экраном по умолчанию.
+/// Экраны общие для всех профилей.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Profile {
+    pub id: u32,
+    pub name: String,
+    pub intervals: Vec<TimeInterval>,
+    pub default_screen_id: Option<u32>,
+    /// Дни недели, в которые профиль включается автоматически
+    #[serde(default)]
+    pub auto_days: Vec<Weekday>,
+}
+
+impl Profile {
+    pub fn new(id: u32, name: String, default_screen_id: Option<u32>) -> Self {
+        Self {
+            id,
+            name,
+            intervals: Vec::new(),
+            default_screen_id,
+            auto_days: Vec::new(),
         }
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
+    /// Версия формата файла, см. `migrations`
+    #[serde(default)]
+    pub schema_version: u32,
     pub language: LanguageIdentifier,
     pub screens: Vec<ScreenConfig>,
-    pub intervals: Vec<TimeInterval>,
-    pub default_screen_id: Option<u32>,
+    pub profiles: Vec<Profile>,
+    pub active_profile_id: u32,
+    /// День, когда последний раз применялся профиль по дню недели: ручное
+    /// переключение действует до следующего дня, даже если программу перезапустить
+    #[serde(default)]
+    pub auto_profile_date: Option<NaiveDate>,
     pub system_settings: SystemSettings,
 }
 
 impl AppConfig {
+    /// Активный профиль
+    pub fn active_profile(&self) -> &Profile {
+        let index = self.active_profile_index();
+        &self.profiles[index]
+    }
+
+    pub fn active_profile_mut(&mut self) -> &mut Profile {
+        let index = self.active_profile_index();
+        &mut self.profiles[index]
+    }
+
+    /// Индекс активного профиля; несуществующий id означает первый профиль
+    fn active_profile_index(&self) -> usize {
+        self.profiles
+            .iter()
+            .position(|p| p.id == self.active_profile_id)
+            .unwrap_or(0)
+    }
+
+    /// Интервалы активного профиля
+    pub fn intervals(&self) -> &[TimeInterval] {
+        &self.active_profile().intervals
+    }
+
+    /// Экран по умолчанию активного профиля
+    pub fn default_screen_id(&self) -> Option<u32> {
+        self.active_profile().default_screen_id
+    }
+
+    /// Интервалы всех профилей (id интервалов уникальны во всём файле)
+    pub fn all_intervals(&self) -> impl Iterator<Item = &TimeInterval> {
+        self.profiles.iter().flat_map(|p| p.intervals.iter())
+    }
+
+    /// Ищет профиль по id или по имени без учёта регистра
+    pub fn find_profile(&self, key: &str) -> Option<&Profile> {
+        let key = key.trim();
+        if let Ok(id) = key.parse::<u32>()
+            && let Some(profile) = self.profiles.iter().find(|p| p.id == id)
+        {
+            return Some(profile);
+        }
+        let key = key.to_lowercase();
+        self.profiles.iter().find(|p| p.name.to_lowercase() == key)
+    }
+
+    /// Делает профиль активным. Возвращает `false`, если профиля нет.
+    pub fn set_active_profile(&mut self, id: u32) -> bool {
+        if self.profiles.iter().any(|p| p.id == id) {
+            self.active_profile_id = id;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Применяет профиль, назначенный на день недели `today`, один раз за день.
+    /// Возвращает `true`, если конфигурация изменилась и её нужно сохранить.
+    pub fn apply_auto_profile(&mut self, today: NaiveDate) -> bool {
+        // Без назначенных дней файл не трогаем вовсе
+        if self.auto_profile_date == Some(today)
+            || self.profiles.iter().all(|p| p.auto_days.is_empty())
+        {
+            return false;
+        }
+        self.auto_profile_date = Some(today);
+        if let Some(profile) = self
+            .profiles
+            .iter()
+            .find(|p| p.auto_days.contains(&today.weekday()))
+        {
+            self.active_profile_id = profile.id;
+        }
+        true
+    }
+
+    /// Гарантирует хотя бы один профиль: без него негде хранить интервалы
+    pub fn ensure_profile(&mut self) {
+        if self.profiles.is_empty() {
+            let default_screen_id = self.screens.first().map(|s| s.id);
+            self.profiles.push(Profile::new(
+                1,
+                default_profile_name(&self.language),
+                default_screen_id,
+            ));
+        }
+        if !self.profiles.iter().any(|p| p.id == self.active_profile_id) {
+            self.active_profile_id = self.profiles[0].id;
+        }
+    }
+
     pub fn create_default_with_localization() -> Self {
-        // Определяем язык системы для выбора локализованных названий по умолчанию
-        let is_russian = std::env::var("LANG")
-            .or_else(|_| std::env::var("LC_ALL"))
-            .or_else(|_| std::env::var("LANGUAGE"))
-            .map(|lang| lang.starts_with("ru"))
-            .unwrap_or(true); // По умолчанию русский, так как это российский проект
+        // Язык системы выбирает и язык интерфейса, и названия экранов по умолчанию
+        let language = crate::locale::system_locale().unwrap_or(crate::locale::FALLBACK_LOCALE);
+        let is_russian = language.language.as_str() == "ru";
             
         let (work_title, work_subtitle, break_title, break_subtitle, prep_title) = if is_russian {
             ("Работа", "Фокус", "Перерыв", "Отдых", "Подготовка")
@@ -129,23 +579,44 @@ impl AppConfig {
         };
         
         let screens = vec![
-            ScreenConfig { 
-                id: 1, 
-                title: work_title.into(), 
-                subtitle: work_subtitle.into(), 
-                color: Rgba8 { r: 46, g: 204, b: 113, a: 255 } // зелёный
+            ScreenConfig {
+                id: 1,
+                title: work_title.into(),
+                subtitle: work_subtitle.into(),
+                color: Rgba8 {
+                    r: 46,
+                    g: 204,
+                    b: 113,
+                    a: 255,
+                }, // зелёный
+                sound: None,
+                notify: true,
             },
-            ScreenConfig { 
-                id: 2, 
-                title: break_title.into(), 
-                subtitle: break_subtitle.into(), 
-                color: Rgba8 { r: 231, g: 76, b: 60, a: 255 } // красный
+            ScreenConfig {
+                id: 2,
+                title: break_title.into(),
+                subtitle: break_subtitle.into(),
+                color: Rgba8 {
+                    r: 231,
+                    g: 76,
+                    b: 60,
+                    a: 255,
+                }, // красный
+                sound: None,
+                notify: true,
             },
-            ScreenConfig { 
-                id: 3, 
-                title: prep_title.into(), 
-                subtitle: "".into(), 
-                color: Rgba8 { r: 52, g: 152, b: 219, a: 255 } // синий
+            ScreenConfig {
+                id: 3,
+                title: prep_title.into(),
+                subtitle: "".into(),
+                color: Rgba8 {
+                    r: 52,
+                    g: 152,
+                    b: 219,
+                    a: 255,
+                }, // синий
+                sound: None,
+                notify: true,
             },
         ];
         
@@ -156,6 +627,9 @@ impl AppConfig {
                 start: TimeOfDay { hour: 9, minute: 0 },
                 end: TimeOfDay { hour: 12, minute: 0 },
                 mode: IntervalMode::Static { screen_id: 1 },
+                recurrence: Recurrence::Daily,
+                warning_minutes: None,
+                source: None,
             },
             TimeInterval {
                 id: 2,
@@ -168,44 +642,440 @@ impl AppConfig {
                         CycleStep { screen_id: 2, duration_minutes: 5 },
                     ],
                 },
+                recurrence: Recurrence::Daily,
+                warning_minutes: None,
+                source: None,
             },
         ];
 
+        let profile = Profile {
+            intervals,
+            ..Profile::new(1, default_profile_name(&language), Some(1))
+        };
+
         Self {
-            language: if is_russian { "ru-RU".parse().unwrap() } else { "en-US".parse().unwrap() },
+            schema_version: CURRENT_SCHEMA_VERSION,
+            language,
             screens,
-            intervals,
-            default_screen_id: Some(1),
+            profiles: vec![profile],
+            active_profile_id: 1,
+            auto_profile_date: None,
             system_settings: SystemSettings::default(),
         }
     }
 }
 
+/// Имя профиля, создаваемого по умолчанию (и при миграции старых файлов)
+pub fn default_profile_name(language: &LanguageIdentifier) -> String {
+    if language.language.as_str() == "ru" {
+        "Основной".into()
+    } else {
+        "Default".into()
+    }
+}
+
 impl Default for AppConfig {
     fn default() -> Self {
         Self::create_default_with_localization()
     }
 }
 
-pub fn load_or_default_config() -> (PathBuf, AppConfig) {
+#[derive(Debug)]
+pub enum ConfigError {
+    /// Файл не удалось прочитать
+    Io(std::io::Error),
+    /// Файл прочитан, но это не корректная конфигурация
+    Parse(serde_json::Error),
+    /// Старый формат не удалось обновить или файл записан более новой версией
+    Migration(MigrationError),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(err) => write!(f, "не удалось прочитать config.json: {}", err),
+            ConfigError::Parse(err) => write!(f, "ошибка в config.json: {}", err),
+            ConfigError::Migration(err) => write!(f, "config.json: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl From<std::io::Error> for ConfigError {
+    fn from(err: std::io::Error) -> Self {
+        ConfigError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for ConfigError {
+    fn from(err: serde_json::Error) -> Self {
+        ConfigError::Parse(err)
+    }
+}
+
+impl From<MigrationError> for ConfigError {
+    fn from(err: MigrationError) -> Self {
+        ConfigError::Migration(err)
+    }
+}
+
+impl ConfigError {
+    /// Файла ещё нет (первый запуск)
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, ConfigError::Io(err) if err.kind() == std::io::ErrorKind::NotFound)
+    }
+}
+
+/// config.json, который не удалось прочитать при запуске
+#[derive(Debug, Clone)]
+pub struct BrokenConfig {
+    pub error: String,
+    /// Копия испорченного файла (None, если её не удалось сделать)
+    pub backup: Option<PathBuf>,
+}
+
+/// Путь к config.json в каталоге настроек пользователя
+pub fn config_path() -> PathBuf {
     let dirs = ProjectDirs::from("dev", "pet_projects", "FlowTimer").expect("no valid home directory");
-    let config_dir = dirs.config_dir();
-    let _ = fs::create_dir_all(config_dir);
-    let config_path = config_dir.join("config.json");
+    dirs.config_dir().join("config.json")
+}
+
+/// Загружает конфигурацию. Если файла нет, создаёт его с настройками по умолчанию.
+/// Испорченный файл не перезаписывается: рядом сохраняется его копия, а вызывающий
+/// получает настройки по умолчанию и описание ошибки для экрана восстановления.
+pub fn load_or_default_config() -> (PathBuf, AppConfig, Option<BrokenConfig>) {
+    let config_path = config_path();
+    if let Some(config_dir) = config_path.parent() {
+        let _ = fs::create_dir_all(config_dir);
+    }
 
-    if let Ok(bytes) = fs::read(&config_path) {
-        if let Ok(cfg) = serde_json::from_slice::<AppConfig>(&bytes) {
-            return (config_path, cfg);
+    match load_config(&config_path) {
+        Ok(cfg) => (config_path, cfg, None),
+        Err(err) if err.is_not_found() => {
+            let cfg = AppConfig::default();
+            let _ = save_config(&config_path, &cfg);
+            (config_path, cfg, None)
+        }
+        Err(err) => {
+            let broken = BrokenConfig {
+                error: err.to_string(),
+                backup: backup_broken_config(&config_path).ok(),
+            };
+            (config_path, AppConfig::default(), Some(broken))
         }
     }
-    let cfg = AppConfig::default();
-    let _ = save_config(&config_path, &cfg);
-    (config_path, cfg)
 }
 
+/// Копирует испорченный файл в `config.json.broken-<время>`
+fn backup_broken_config(path: &Path) -> std::io::Result<PathBuf> {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(
+        ".broken-{}",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    let backup = path.with_file_name(name);
+    fs::copy(path, &backup)?;
+    Ok(backup)
+}
+
+/// Читает конфигурацию из файла
+pub fn load_config(path: &Path) -> Result<AppConfig, ConfigError> {
+    let bytes = fs::read(path)?;
+    parse_config(&bytes)
+}
+
+/// Разбирает config.json любой поддерживаемой версии формата
+pub fn parse_config(bytes: &[u8]) -> Result<AppConfig, ConfigError> {
+    let mut value: serde_json::Value = serde_json::from_slice(bytes)?;
+    migrations::migrate(&mut value)?;
+    let mut config: AppConfig = serde_json::from_value(value)?;
+    config.ensure_profile();
+    Ok(config)
+}
+
+/// Сохраняет конфигурацию атомарно: пишет временный файл рядом и переименовывает его,
+/// так что при сбое на диске остаётся либо старая, либо новая версия целиком.
 pub fn save_config(path: &PathBuf, cfg: &AppConfig) -> std::io::Result<()> {
     let json = serde_json::to_vec_pretty(cfg).expect("serialize config");
-    fs::write(path, json)
+
+    // config.json может быть ссылкой в репозиторий dotfiles: заменяем сам файл, а не ссылку
+    let target = fs::canonicalize(path).unwrap_or_else(|_| path.clone());
+    let mut tmp_name = target.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp = target.with_file_name(tmp_name);
+
+    let mut file = fs::File::create(&tmp)?;
+    file.write_all(&json)?;
+    file.sync_all()?;
+    drop(file);
+    fs::rename(&tmp, &target).inspect_err(|_| {
+        let _ = fs::remove_file(&tmp);
+    })
+}
+
+/// Как часто проверять config.json на изменения
+const WATCH_INTERVAL: StdDuration = StdDuration::from_secs(1);
+
+/// Отслеживает изменения config.json на диске (правка вручную, синхронизация dotfiles).
+/// Сравнивает время изменения и размер файла: этого достаточно и для записи на месте,
+/// и для замены файла через rename, как делают редакторы.
+pub struct ConfigWatcher {
+    path: PathBuf,
+    stamp: Option<(SystemTime, u64)>,
+    checked_at: Instant,
+}
+
+impl ConfigWatcher {
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            stamp: file_stamp(path),
+            checked_at: Instant::now(),
+        }
+    }
+
+    /// Запоминает текущее состояние файла, например после собственной записи
+    pub fn mark_seen(&mut self) {
+        self.stamp = file_stamp(&self.path);
+    }
+
+    /// Изменился ли файл с прошлой проверки. Диск читается не чаще раза в секунду.
+    pub fn poll(&mut self) -> bool {
+        if self.checked_at.elapsed() < WATCH_INTERVAL {
+            return false;
+        }
+        self.checked_at = Instant::now();
+
+        let stamp = file_stamp(&self.path);
+        // Удалённый файл не перечитываем: при следующем сохранении он появится снова
+        if stamp.is_none() || stamp == self.stamp {
+            return false;
+        }
+        self.stamp = stamp;
+        true
+    }
+}
+
+fn file_stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let meta = fs::metadata(path).ok()?;
+    Some((meta.modified().ok()?, meta.len()))
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn interval(start: (u8, u8), end: (u8, u8), recurrence: Recurrence) -> TimeInterval {
+        TimeInterval {
+            id: 1,
+            name: "Ночь".into(),
+            start: TimeOfDay {
+                hour: start.0,
+                minute: start.1,
+            },
+            end: TimeOfDay {
+                hour: end.0,
+                minute: end.1,
+            },
+            mode: IntervalMode::Static { screen_id: 1 },
+            recurrence,
+            warning_minutes: None,
+            source: None,
+        }
+    }
+
+    #[test]
+    fn duration_wraps_past_midnight() {
+        assert_eq!(
+            interval((9, 0), (12, 0), Recurrence::Daily).duration_minutes(),
+            180
+        );
+        assert_eq!(
+            interval((22, 0), (2, 0), Recurrence::Daily).duration_minutes(),
+            240
+        );
+        assert!(interval((22, 0), (2, 0), Recurrence::Daily).crosses_midnight());
+        assert_eq!(
+            interval((8, 0), (8, 0), Recurrence::Daily).duration_minutes(),
+            0
+        );
+    }
+
+    #[test]
+    fn minutes_into_covers_both_sides_of_midnight() {
+        let night = interval((22, 0), (2, 0), Recurrence::Daily);
+        assert_eq!(night.minutes_into(21 * 60 + 59), None);
+        assert_eq!(night.minutes_into(22 * 60), Some(0));
+        assert_eq!(night.minutes_into(23 * 60), Some(60));
+        assert_eq!(night.minutes_into(0), Some(120));
+        assert_eq!(night.minutes_into(60 + 30), Some(210));
+        assert_eq!(night.minutes_into(2 * 60), None);
+    }
+
+    #[test]
+    fn after_midnight_belongs_to_previous_day() {
+        // 2026-06-12 - пятница, 2026-06-14 - воскресенье
+        let night = interval((22, 0), (2, 0), Recurrence::Weekdays);
+        let friday = date(2026, 6, 12);
+        let saturday = date(2026, 6, 13);
+
+        assert_eq!(night.active_occurrence(friday, 23 * 60), Some((friday, 60)));
+        // Хвост пятничного вхождения в субботу ночью
+        assert_eq!(night.active_occurrence(saturday, 60), Some((friday, 180)));
+        // В субботу вечером интервал не начинается, а в воскресенье ночью нет хвоста
+        assert_eq!(night.active_occurrence(saturday, 23 * 60), None);
+        assert_eq!(night.active_occurrence(date(2026, 6, 14), 60), None);
+    }
+
+    #[test]
+    fn recurrence_kinds_match_their_days() {
+        // 2026-06-12 - пятница
+        let friday = date(2026, 6, 12);
+        let saturday = date(2026, 6, 13);
+
+        assert!(Recurrence::Weekdays.occurs_on(friday));
+        assert!(!Recurrence::Weekdays.occurs_on(saturday));
+        assert!(Recurrence::Weekends.occurs_on(saturday));
+        assert!(!Recurrence::Weekends.occurs_on(friday));
+
+        let days = Recurrence::Days {
+            days: vec![Weekday::Mon, Weekday::Sat],
+        };
+        assert!(days.occurs_on(saturday));
+        assert!(!days.occurs_on(friday));
+
+        let range = Recurrence::DateRange {
+            from: date(2026, 6, 10),
+            to: friday,
+        };
+        assert!(range.occurs_on(date(2026, 6, 10)));
+        assert!(range.occurs_on(friday));
+        assert!(!range.occurs_on(saturday));
+
+        let dates = Recurrence::Dates {
+            dates: vec![saturday],
+        };
+        assert!(dates.occurs_on(saturday));
+        assert!(!dates.occurs_on(friday));
+    }
 
+    #[test]
+    fn next_occurrence_is_found_without_scanning_ahead() {
+        // 2026-06-13 - суббота
+        let saturday = date(2026, 6, 13);
+        assert_eq!(Recurrence::Daily.next_occurrence(saturday), Some(saturday));
+        assert_eq!(
+            Recurrence::Weekdays.next_occurrence(saturday),
+            Some(date(2026, 6, 15))
+        );
+        assert_eq!(
+            Recurrence::Days { days: vec![] }.next_occurrence(saturday),
+            None
+        );
+
+        let range = Recurrence::DateRange {
+            from: date(2026, 7, 1),
+            to: date(2026, 7, 31),
+        };
+        assert_eq!(range.next_occurrence(saturday), Some(date(2026, 7, 1)));
+        assert_eq!(
+            range.next_occurrence(date(2026, 7, 10)),
+            Some(date(2026, 7, 10))
+        );
+        assert_eq!(range.next_occurrence(date(2026, 8, 1)), None);
+
+        // Даты идут в любом порядке и могут быть дальше года
+        let dates = Recurrence::Dates {
+            dates: vec![date(2028, 1, 1), date(2026, 6, 20), date(2026, 6, 1)],
+        };
+        assert_eq!(dates.next_occurrence(saturday), Some(date(2026, 6, 20)));
+        assert_eq!(
+            dates.next_occurrence(date(2026, 6, 21)),
+            Some(date(2028, 1, 1))
+        );
+        assert_eq!(dates.next_occurrence(date(2028, 1, 2)), None);
+    }
+
+    #[test]
+    fn shared_days_account_for_shift_and_bounds() {
+        assert!(!Recurrence::Weekdays.shares_day_with(&Recurrence::Weekends, 0));
+        // Пятница и следующая за ней суббота
+        assert!(Recurrence::Weekdays.shares_day_with(&Recurrence::Weekends, 1));
+
+        let monday = Recurrence::Days {
+            days: vec![Weekday::Mon],
+        };
+        let tuesday_date = Recurrence::Dates {
+            dates: vec![date(2026, 6, 9)],
+        };
+        assert!(!monday.shares_day_with(&tuesday_date, 0));
+        assert!(monday.shares_day_with(&tuesday_date, 1));
+        assert!(tuesday_date.shares_day_with(&monday, -1));
+
+        // Понедельник и вторник: пятницы в диапазоне нет
+        let short_range = Recurrence::DateRange {
+            from: date(2026, 6, 8),
+            to: date(2026, 6, 9),
+        };
+        let friday = Recurrence::Days {
+            days: vec![Weekday::Fri],
+        };
+        assert!(!short_range.shares_day_with(&friday, 0));
+        assert!(short_range.shares_day_with(&Recurrence::Weekdays, 0));
+
+        let june = Recurrence::DateRange {
+            from: date(2026, 6, 1),
+            to: date(2026, 6, 30),
+        };
+        let july = Recurrence::DateRange {
+            from: date(2026, 7, 1),
+            to: date(2026, 7, 31),
+        };
+        assert!(!june.shares_day_with(&july, 0));
+        // 30 июня и 1 июля
+        assert!(june.shares_day_with(&july, 1));
+    }
+
+    #[test]
+    fn overlaps_only_on_shared_days() {
+        let work = interval((9, 0), (12, 0), Recurrence::Weekdays);
+        let weekend = interval((10, 0), (11, 0), Recurrence::Weekends);
+        assert!(!work.overlaps(&weekend));
+        assert!(work.overlaps(&interval((11, 0), (13, 0), Recurrence::Daily)));
+        // Соприкосновение концами - не пересечение
+        assert!(!work.overlaps(&interval((12, 0), (13, 0), Recurrence::Daily)));
+        // Интервал нулевой длины ни с чем не пересекается
+        assert!(!work.overlaps(&interval((10, 0), (10, 0), Recurrence::Daily)));
+
+        // Хвост понедельничного вхождения приходится на вторник
+        let monday_night = interval(
+            (22, 0),
+            (2, 0),
+            Recurrence::Days {
+                days: vec![Weekday::Mon],
+            },
+        );
+        let tuesday_early = interval(
+            (1, 0),
+            (3, 0),
+            Recurrence::Days {
+                days: vec![Weekday::Tue],
+            },
+        );
+        let monday_early = interval(
+            (1, 0),
+            (3, 0),
+            Recurrence::Days {
+                days: vec![Weekday::Mon],
+            },
+        );
+        assert!(monday_night.overlaps(&tuesday_early));
+        assert!(tuesday_early.overlaps(&monday_night));
+        assert!(!monday_night.overlaps(&monday_early));
+    }
+}
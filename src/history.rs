@@ -0,0 +1,763 @@
+//! Журнал сессий: каждый отрезок времени, в течение которого показывался один
+//! экран, дописывается строкой JSON в `history.jsonl` рядом с config.json.
+//! Файл только растёт; испорченные строки при чтении пропускаются.
+//!
+//! По журналу считается статистика: время по экранам за день и неделю,
+//! число завершённых циклов и серии дней с ними. Для табелей журнал
+//! выгружается в CSV или JSON ([`export`]) с группировкой и округлением.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, TimeZone};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+/// Имя файла журнала (в каталоге config.json)
+pub const HISTORY_FILE: &str = "history.jsonl";
+
+/// Путь к журналу рядом с файлом конфигурации
+pub fn history_path(config_path: &Path) -> PathBuf {
+    config_path.with_file_name(HISTORY_FILE)
+}
+
+/// Шаг цикла, к которому относится запись
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CycleStepRef {
+    /// Индекс шага, с нуля
+    pub index: usize,
+    /// Число шагов в цикле
+    pub count: usize,
+}
+
+impl CycleStepRef {
+    pub fn is_last(&self) -> bool {
+        self.index + 1 == self.count
+    }
+}
+
+/// Запись журнала: отрезок показа одного экрана
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub screen_id: u32,
+    /// Заголовок экрана на момент записи (экран могли переименовать или удалить)
+    pub screen_title: String,
+    /// Интервал расписания; `None` для разовой сессии и вне интервалов
+    #[serde(default)]
+    pub interval_id: Option<u32>,
+    /// Название интервала или разовой сессии; `None` вне интервалов
+    #[serde(default)]
+    pub interval_name: Option<String>,
+    #[serde(default)]
+    pub step: Option<CycleStepRef>,
+    pub start: DateTime<Local>,
+    pub end: DateTime<Local>,
+    /// Сколько секунд из отрезка таймер стоял на паузе
+    #[serde(default)]
+    pub paused_secs: u64,
+    /// Шаг пропущен, перезапущен или сессия остановлена досрочно
+    #[serde(default)]
+    pub skipped: bool,
+    /// Отрезок оборван закрытием программы или сном компьютера
+    #[serde(default)]
+    pub interrupted: bool,
+}
+
+impl HistoryEntry {
+    /// Время без пауз, секунд
+    pub fn active_secs(&self) -> u64 {
+        let shown = (self.end - self.start).num_seconds().max(0) as u64;
+        shown.saturating_sub(self.paused_secs)
+    }
+
+    /// Отрезок завершил цикл: последний шаг дошёл до конца сам
+    pub fn completes_cycle(&self) -> bool {
+        self.step.is_some_and(|step| step.is_last()) && !self.skipped && !self.interrupted
+    }
+
+    /// Время без пауз, пришедшееся на промежуток `[from, to)`.
+    /// Пауза распределяется по отрезку пропорционально.
+    fn active_secs_within(&self, from: DateTime<Local>, to: DateTime<Local>) -> u64 {
+        let shown = (self.end - self.start).num_seconds();
+        let overlap = (self.end.min(to) - self.start.max(from)).num_seconds();
+        if shown <= 0 || overlap <= 0 {
+            return 0;
+        }
+        let active = self.active_secs() as i64;
+        (active * overlap / shown) as u64
+    }
+}
+
+/// Дописывает запись в конец журнала
+pub fn append_entry(path: &Path, entry: &HistoryEntry) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut line = serde_json::to_string(entry).map_err(io::Error::other)?;
+    line.push('\n');
+    // Строка пишется одним вызовом, чтобы не перемешаться с записью другого процесса
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(line.as_bytes())
+}
+
+/// Прочитанный журнал
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    pub entries: Vec<HistoryEntry>,
+    /// Строк, которые не удалось разобрать
+    pub broken_lines: usize,
+}
+
+impl History {
+    /// Читает журнал; отсутствующий файл - пустой журнал
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(text) => Ok(Self::parse(&text)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn parse(text: &str) -> Self {
+        let mut history = Self::default();
+        for line in text.lines().filter(|line| !line.trim().is_empty()) {
+            match serde_json::from_str(line) {
+                Ok(entry) => history.entries.push(entry),
+                Err(_) => history.broken_lines += 1,
+            }
+        }
+        history
+    }
+
+    /// Время по экранам за `days` дней начиная с `from`, по убыванию
+    pub fn screen_totals(&self, from: NaiveDate, days: u32) -> Vec<ScreenTotal> {
+        let (start, end) = (
+            day_start(from),
+            day_start(from + Duration::days(days as i64)),
+        );
+        let mut totals: HashMap<u32, ScreenTotal> = HashMap::new();
+        for entry in &self.entries {
+            let secs = entry.active_secs_within(start, end);
+            if secs == 0 {
+                continue;
+            }
+            let total = totals
+                .entry(entry.screen_id)
+                .or_insert_with(|| ScreenTotal {
+                    screen_id: entry.screen_id,
+                    title: entry.screen_title.clone(),
+                    secs: 0,
+                });
+            total.secs += secs;
+            // Записи идут по порядку: остаётся последний заголовок
+            total.title.clone_from(&entry.screen_title);
+        }
+        let mut totals: Vec<ScreenTotal> = totals.into_values().collect();
+        totals.sort_by(|a, b| b.secs.cmp(&a.secs).then(a.screen_id.cmp(&b.screen_id)));
+        totals
+    }
+
+    /// Число завершённых циклов за `days` дней начиная с `from` (по дню окончания)
+    pub fn completed_cycles(&self, from: NaiveDate, days: u32) -> usize {
+        let to = from + Duration::days(days as i64);
+        self.entries
+            .iter()
+            .filter(|entry| entry.completes_cycle())
+            .filter(|entry| (from..to).contains(&entry.end.date_naive()))
+            .count()
+    }
+
+    /// Серии дней подряд, в которые был завершён хотя бы один цикл
+    pub fn streaks(&self, today: NaiveDate) -> Streaks {
+        let days: BTreeSet<NaiveDate> = self
+            .entries
+            .iter()
+            .filter(|entry| entry.completes_cycle())
+            .map(|entry| entry.end.date_naive())
+            .collect();
+
+        let mut longest = 0;
+        let mut run = 0;
+        let mut prev: Option<NaiveDate> = None;
+        for &day in &days {
+            run = match prev {
+                Some(prev) if prev.succ_opt() == Some(day) => run + 1,
+                _ => 1,
+            };
+            longest = longest.max(run);
+            prev = Some(day);
+        }
+
+        // Текущая серия не прерывается, пока сегодня ещё не завершено ни одного цикла
+        let mut day = if days.contains(&today) {
+            today
+        } else {
+            today - Duration::days(1)
+        };
+        let mut current = 0;
+        while days.contains(&day) {
+            current += 1;
+            day -= Duration::days(1);
+        }
+
+        Streaks { current, longest }
+    }
+}
+
+/// Время, проведённое на одном экране
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScreenTotal {
+    pub screen_id: u32,
+    pub title: String,
+    /// Секунд без пауз
+    pub secs: u64,
+}
+
+/// Серии дней с завершёнными циклами
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Streaks {
+    /// Серия, продолжающаяся сегодня (или закончившаяся вчера)
+    pub current: u32,
+    pub longest: u32,
+}
+
+/// Как группируются строки выгрузки
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExportGrouping {
+    /// Каждый отрезок отдельной строкой
+    #[default]
+    Sessions,
+    /// Сумма по интервалу за каждый день
+    Interval,
+    /// Сумма по экрану за каждый день
+    Screen,
+}
+
+impl ExportGrouping {
+    pub const ALL: [ExportGrouping; 3] = [
+        ExportGrouping::Sessions,
+        ExportGrouping::Interval,
+        ExportGrouping::Screen,
+    ];
+
+    /// Имя в командной строке
+    pub fn name(self) -> &'static str {
+        match self {
+            ExportGrouping::Sessions => "sessions",
+            ExportGrouping::Interval => "interval",
+            ExportGrouping::Screen => "screen",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|grouping| grouping.name() == name)
+    }
+}
+
+/// Формат выгрузки, определяется по расширению
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    /// `.json` - JSON, всё остальное - CSV
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => ExportFormat::Json,
+            _ => ExportFormat::Csv,
+        }
+    }
+}
+
+/// Параметры выгрузки журнала
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportOptions {
+    /// Первый и последний день (включительно)
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub grouping: ExportGrouping,
+    /// Шаг округления вверх, минут; 0 - до ближайшей минуты
+    pub rounding_minutes: u32,
+}
+
+/// Выгружает журнал за период. Время считается без пауз и округляется
+/// в каждой строке отдельно: для отрезка или для суммы группы за день.
+/// Отрезки, выходящие за границы периода, учитываются только внутри него.
+pub fn export(history: &History, options: &ExportOptions, format: ExportFormat) -> String {
+    let (columns, rows) = match options.grouping {
+        ExportGrouping::Sessions => session_rows(history, options),
+        grouping => grouped_rows(history, options, grouping),
+    };
+    match format {
+        ExportFormat::Csv => to_csv(&columns, &rows),
+        ExportFormat::Json => {
+            let objects: Vec<JsonRow> = rows.iter().map(|row| JsonRow(&columns, row)).collect();
+            serde_json::to_string_pretty(&objects).expect("serialize history export")
+        }
+    }
+}
+
+type ExportTable = (Vec<&'static str>, Vec<Vec<Value>>);
+
+/// Строка выгрузки как объект JSON с полями в порядке столбцов
+struct JsonRow<'a>(&'a [&'static str], &'a [Value]);
+
+impl Serialize for JsonRow<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (column, value) in self.0.iter().zip(self.1) {
+            map.serialize_entry(column, value)?;
+        }
+        map.end()
+    }
+}
+
+fn session_rows(history: &History, options: &ExportOptions) -> ExportTable {
+    let columns = vec![
+        "date",
+        "start",
+        "end",
+        "screen",
+        "interval",
+        "step",
+        "minutes",
+        "hours",
+        "paused_minutes",
+        "skipped",
+    ];
+    // Отрезки на границе периода обрезаются, как и в сводных строках
+    let (from, to) = (
+        day_start(options.from),
+        day_start(options.to + Duration::days(1)),
+    );
+    let rows = history
+        .entries
+        .iter()
+        .filter_map(|entry| {
+            let (start, end) = (entry.start.max(from), entry.end.min(to));
+            if end <= start {
+                return None;
+            }
+            let active_secs = entry.active_secs_within(from, to);
+            let paused_secs = ((end - start).num_seconds() as u64).saturating_sub(active_secs);
+            let minutes = round_minutes(active_secs, options.rounding_minutes);
+            Some(vec![
+                json!(start.date_naive().to_string()),
+                json!(start.format("%H:%M:%S").to_string()),
+                json!(end.format("%H:%M:%S").to_string()),
+                json!(entry.screen_title),
+                json!(entry.interval_name),
+                json!(
+                    entry
+                        .step
+                        .map(|step| format!("{}/{}", step.index + 1, step.count))
+                ),
+                json!(minutes),
+                json!(hours(minutes)),
+                json!(round_minutes(paused_secs, 0)),
+                json!(entry.skipped),
+            ])
+        })
+        .collect();
+    (columns, rows)
+}
+
+fn grouped_rows(
+    history: &History,
+    options: &ExportOptions,
+    grouping: ExportGrouping,
+) -> ExportTable {
+    let name_column = if grouping == ExportGrouping::Screen {
+        "screen"
+    } else {
+        "interval"
+    };
+    let columns = vec!["date", name_column, "sessions", "minutes", "hours"];
+
+    let mut rows = Vec::new();
+    let mut date = options.from;
+    while date <= options.to {
+        let (start, end) = (day_start(date), day_start(date + Duration::days(1)));
+        // Имя группы -> (отрезков, секунд); вне интервалов имя отсутствует
+        let mut groups: Vec<(Option<&str>, usize, u64)> = Vec::new();
+        for entry in &history.entries {
+            let secs = entry.active_secs_within(start, end);
+            if secs == 0 {
+                continue;
+            }
+            let name = match grouping {
+                ExportGrouping::Screen => Some(entry.screen_title.as_str()),
+                _ => entry.interval_name.as_deref(),
+            };
+            match groups.iter_mut().find(|(group, ..)| *group == name) {
+                Some((_, sessions, total)) => {
+                    *sessions += 1;
+                    *total += secs;
+                }
+                None => groups.push((name, 1, secs)),
+            }
+        }
+        groups.sort_by(|a, b| a.0.cmp(&b.0));
+        for (name, sessions, secs) in groups {
+            let minutes = round_minutes(secs, options.rounding_minutes);
+            rows.push(vec![
+                json!(date.to_string()),
+                json!(name),
+                json!(sessions),
+                json!(minutes),
+                json!(hours(minutes)),
+            ]);
+        }
+        date += Duration::days(1);
+    }
+    (columns, rows)
+}
+
+/// Секунды в минуты: вверх до кратного `increment` или до ближайшей минуты при 0
+fn round_minutes(secs: u64, increment: u32) -> u64 {
+    if increment == 0 {
+        return (secs + 30) / 60;
+    }
+    let step = increment as u64 * 60;
+    secs.div_ceil(step) * increment as u64
+}
+
+/// Часы с двумя знаками после запятой
+fn hours(minutes: u64) -> f64 {
+    (minutes as f64 / 60.0 * 100.0).round() / 100.0
+}
+
+fn to_csv(columns: &[&str], rows: &[Vec<Value>]) -> String {
+    let mut out = columns.join(",");
+    out.push('\n');
+    for row in rows {
+        let cells: Vec<String> = row
+            .iter()
+            .map(|value| match value {
+                Value::Null => String::new(),
+                Value::String(text) => csv_field(text),
+                other => other.to_string(),
+            })
+            .collect();
+        out.push_str(&cells.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// Поле CSV: кавычки, если в значении есть разделитель, кавычка или перевод строки
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// Понедельник недели, в которую входит `date`
+pub fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+/// Начало суток по местному времени (при переводе часов - ближайший существующий момент)
+fn day_start(date: NaiveDate) -> DateTime<Local> {
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap_or_default();
+    Local
+        .from_local_datetime(&midnight)
+        .earliest()
+        .unwrap_or_else(|| Local.from_utc_datetime(&midnight))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+
+    use super::*;
+
+    fn at(value: &str) -> DateTime<Local> {
+        let naive = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").unwrap();
+        Local.from_local_datetime(&naive).unwrap()
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    fn entry(screen_id: u32, start: &str, end: &str) -> HistoryEntry {
+        HistoryEntry {
+            screen_id,
+            screen_title: format!("Экран {}", screen_id),
+            interval_id: Some(1),
+            interval_name: Some("Работа".into()),
+            step: None,
+            start: at(start),
+            end: at(end),
+            paused_secs: 0,
+            skipped: false,
+            interrupted: false,
+        }
+    }
+
+    /// Последний шаг цикла, дошедший до конца
+    fn cycle_end(end: &str) -> HistoryEntry {
+        let start = (at(end) - Duration::minutes(5)).format("%Y-%m-%d %H:%M");
+        HistoryEntry {
+            step: Some(CycleStepRef { index: 1, count: 2 }),
+            ..entry(2, &start.to_string(), end)
+        }
+    }
+
+    /// Журнал в том виде, в каком он лежит в файле
+    fn history(entries: &[HistoryEntry]) -> History {
+        let text: Vec<String> = entries
+            .iter()
+            .map(|entry| serde_json::to_string(entry).unwrap())
+            .collect();
+        History::parse(&text.join("\n"))
+    }
+
+    #[test]
+    fn broken_lines_are_counted() {
+        let mut text =
+            serde_json::to_string(&entry(1, "2026-06-10 09:00", "2026-06-10 09:25")).unwrap();
+        text.push_str("\n{\"screen_id\":\n\n");
+        let history = History::parse(&text);
+        assert_eq!(history.entries.len(), 1);
+        assert_eq!(history.broken_lines, 1);
+    }
+
+    #[test]
+    fn pause_is_split_in_proportion() {
+        let night = HistoryEntry {
+            paused_secs: 1200,
+            ..entry(1, "2026-06-10 23:00", "2026-06-11 01:00")
+        };
+        assert_eq!(night.active_secs(), 6000);
+
+        let day = |value| {
+            (
+                day_start(date(value)),
+                day_start(date(value) + Duration::days(1)),
+            )
+        };
+        let (from, to) = day("2026-06-10");
+        assert_eq!(night.active_secs_within(from, to), 3000);
+        let (from, to) = day("2026-06-11");
+        assert_eq!(night.active_secs_within(from, to), 3000);
+        let (from, to) = day("2026-06-12");
+        assert_eq!(night.active_secs_within(from, to), 0);
+    }
+
+    #[test]
+    fn screen_totals_keep_latest_title() {
+        let history = history(&[
+            entry(1, "2026-06-10 09:00", "2026-06-10 09:25"),
+            entry(2, "2026-06-10 09:25", "2026-06-10 09:30"),
+            HistoryEntry {
+                screen_title: "Фокус".into(),
+                paused_secs: 300,
+                ..entry(1, "2026-06-10 09:30", "2026-06-10 09:55")
+            },
+            // Наполовину на следующий день
+            entry(2, "2026-06-10 23:50", "2026-06-11 00:10"),
+        ]);
+
+        let totals = |from, days| {
+            history
+                .screen_totals(date(from), days)
+                .into_iter()
+                .map(|total| (total.screen_id, total.title, total.secs))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            totals("2026-06-10", 1),
+            vec![(1, "Фокус".into(), 2700), (2, "Экран 2".into(), 900)]
+        );
+        assert_eq!(totals("2026-06-11", 1), vec![(2, "Экран 2".into(), 600)]);
+        assert!(totals("2026-06-12", 7).is_empty());
+    }
+
+    #[test]
+    fn only_finished_cycles_count() {
+        let history = history(&[
+            cycle_end("2026-06-10 10:00"),
+            HistoryEntry {
+                skipped: true,
+                ..cycle_end("2026-06-10 11:00")
+            },
+            HistoryEntry {
+                interrupted: true,
+                ..cycle_end("2026-06-10 12:00")
+            },
+            // Не последний шаг
+            HistoryEntry {
+                step: Some(CycleStepRef { index: 0, count: 2 }),
+                ..cycle_end("2026-06-10 13:00")
+            },
+            // Засчитывается по дню окончания
+            cycle_end("2026-06-11 00:02"),
+        ]);
+        assert_eq!(history.completed_cycles(date("2026-06-10"), 1), 1);
+        assert_eq!(history.completed_cycles(date("2026-06-11"), 1), 1);
+        assert_eq!(history.completed_cycles(date("2026-06-08"), 7), 2);
+    }
+
+    fn options(from: &str, to: &str, grouping: ExportGrouping, rounding: u32) -> ExportOptions {
+        ExportOptions {
+            from: date(from),
+            to: date(to),
+            grouping,
+            rounding_minutes: rounding,
+        }
+    }
+
+    #[test]
+    fn minutes_round_up_to_increment() {
+        assert_eq!(round_minutes(0, 0), 0);
+        assert_eq!(round_minutes(29, 0), 0);
+        assert_eq!(round_minutes(30, 0), 1);
+        assert_eq!(round_minutes(1500, 0), 25);
+        assert_eq!(round_minutes(0, 15), 0);
+        assert_eq!(round_minutes(1, 15), 15);
+        assert_eq!(round_minutes(900, 15), 15);
+        assert_eq!(round_minutes(901, 15), 30);
+        assert_eq!(hours(25), 0.42);
+    }
+
+    #[test]
+    fn csv_fields_are_quoted_when_needed() {
+        assert_eq!(csv_field("Работа"), "Работа");
+        assert_eq!(csv_field("Почта, звонки"), "\"Почта, звонки\"");
+        assert_eq!(csv_field("Экран \"А\""), "\"Экран \"\"А\"\"\"");
+        assert_eq!(csv_field("две\nстроки"), "\"две\nстроки\"");
+    }
+
+    #[test]
+    fn sessions_are_clipped_to_period() {
+        let history = history(&[
+            HistoryEntry {
+                paused_secs: 1200,
+                ..entry(1, "2026-06-09 23:00", "2026-06-10 01:00")
+            },
+            HistoryEntry {
+                screen_title: "Почта, звонки".into(),
+                interval_name: None,
+                ..entry(2, "2026-06-10 09:00", "2026-06-10 09:20")
+            },
+            entry(1, "2026-06-11 09:00", "2026-06-11 10:00"),
+        ]);
+        let csv = export(
+            &history,
+            &options("2026-06-10", "2026-06-10", ExportGrouping::Sessions, 0),
+            ExportFormat::Csv,
+        );
+        assert_eq!(
+            csv,
+            "date,start,end,screen,interval,step,minutes,hours,paused_minutes,skipped\n\
+             2026-06-10,00:00:00,01:00:00,Экран 1,Работа,,50,0.83,10,false\n\
+             2026-06-10,09:00:00,09:20:00,\"Почта, звонки\",,,20,0.33,0,false\n"
+        );
+    }
+
+    #[test]
+    fn groups_sum_each_day() {
+        let history = history(&[
+            entry(1, "2026-06-10 09:00", "2026-06-10 09:25"),
+            entry(2, "2026-06-10 09:25", "2026-06-10 09:30"),
+            entry(1, "2026-06-10 09:30", "2026-06-10 09:55"),
+            HistoryEntry {
+                interval_name: Some("Вечер".into()),
+                ..entry(1, "2026-06-10 23:30", "2026-06-11 00:40")
+            },
+        ]);
+        let rows = |grouping, rounding| {
+            let json = export(
+                &history,
+                &options("2026-06-10", "2026-06-11", grouping, rounding),
+                ExportFormat::Json,
+            );
+            serde_json::from_str::<Vec<Value>>(&json)
+                .unwrap()
+                .into_iter()
+                .map(|row| {
+                    (
+                        row["date"].as_str().unwrap().to_string(),
+                        row[if grouping == ExportGrouping::Screen {
+                            "screen"
+                        } else {
+                            "interval"
+                        }]
+                        .as_str()
+                        .unwrap()
+                        .to_string(),
+                        row["sessions"].as_u64().unwrap(),
+                        row["minutes"].as_u64().unwrap(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let row = |date: &str, name: &str, sessions, minutes| {
+            (date.to_string(), name.to_string(), sessions, minutes)
+        };
+
+        assert_eq!(
+            rows(ExportGrouping::Interval, 0),
+            vec![
+                row("2026-06-10", "Вечер", 1, 30),
+                row("2026-06-10", "Работа", 3, 55),
+                row("2026-06-11", "Вечер", 1, 40),
+            ]
+        );
+        // Округляется сумма за день, а не каждый отрезок
+        assert_eq!(
+            rows(ExportGrouping::Screen, 15),
+            vec![
+                row("2026-06-10", "Экран 1", 3, 90),
+                row("2026-06-10", "Экран 2", 1, 15),
+                row("2026-06-11", "Экран 1", 1, 45),
+            ]
+        );
+    }
+
+    #[test]
+    fn streaks_survive_until_day_ends() {
+        let history = history(&[
+            cycle_end("2026-06-01 10:00"),
+            cycle_end("2026-06-02 10:00"),
+            cycle_end("2026-06-02 15:00"),
+            cycle_end("2026-06-03 10:00"),
+            // 4 июня - перерыв
+            cycle_end("2026-06-05 10:00"),
+            cycle_end("2026-06-06 10:00"),
+        ]);
+        let streaks = |today| history.streaks(date(today));
+
+        assert_eq!(
+            streaks("2026-06-06"),
+            Streaks {
+                current: 2,
+                longest: 3
+            }
+        );
+        // Сегодня циклов ещё нет, но вчерашняя серия не прервана
+        assert_eq!(streaks("2026-06-07").current, 2);
+        assert_eq!(streaks("2026-06-08").current, 0);
+        assert_eq!(streaks("2026-06-04").current, 3);
+        assert_eq!(
+            History::default().streaks(date("2026-06-06")),
+            Streaks::default()
+        );
+    }
+}
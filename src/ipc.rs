@@ -0,0 +1,573 @@
+//! Управление запущенным экземпляром через Unix-сокет.
+//!
+//! Сокет создаётся в `$XDG_RUNTIME_DIR/flowtimer.sock` (или во временном каталоге).
+//! Протокол построчный: клиент пишет JSON-запрос в одну строку и получает
+//! JSON-ответ в одну строку, например
+//!
+//! ```text
+//! $ echo '{"cmd":"pause"}' | socat - UNIX-CONNECT:$XDG_RUNTIME_DIR/flowtimer.sock
+//! {"ok":true,"state":{...}}
+//! ```
+//!
+//! Запросы принимаются в фоновом потоке и передаются в интерфейс через канал:
+//! состояние таймера меняется только в потоке интерфейса.
+//!
+//! Единственность экземпляра обеспечивает файл блокировки `flowtimer.lock`
+//! рядом с `config.json`: сокет создаёт и удаляет только владелец блокировки.
+//! Второй запуск окна отправляет `focus` и завершается, а консольные команды
+//! управления (`flow_timer pause`, `skip` и т.д.) передаются в работающий экземпляр.
+//! Сокет, принадлежащий другому пользователю, не используется.
+//!
+//! На платформах без Unix-сокетов остаётся только блокировка: окно уже
+//! работающего экземпляра не поднимается, а команды управления недоступны.
+
+use std::{
+    fmt,
+    fs::{self, File, OpenOptions, TryLockError},
+    io,
+    path::{Path, PathBuf},
+    sync::mpsc::{Receiver, Sender},
+    time::Duration,
+};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::{AppConfig, config_path},
+    timer::{ActiveScreenInfo, TimerState},
+};
+
+/// Сколько клиент ждёт ответа от интерфейса
+const REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Команда управления
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+pub enum IpcRequest {
+    /// Текущее состояние таймера
+    State,
+    Pause,
+    Resume,
+    TogglePause,
+    /// Пропустить текущий шаг цикла
+    Skip,
+    /// Продлить текущий шаг цикла (по умолчанию - на минуты из настроек)
+    Extend {
+        #[serde(default)]
+        minutes: Option<u32>,
+    },
+    /// Начать цикл заново
+    Restart,
+    /// Запустить разовую сессию: один экран (`screen_id`, `minutes`)
+    /// или повтор цикла интервала (`interval_id`, `repeats`)
+    StartAdhoc {
+        #[serde(default)]
+        screen_id: Option<u32>,
+        #[serde(default)]
+        minutes: Option<u32>,
+        #[serde(default)]
+        interval_id: Option<u32>,
+        #[serde(default)]
+        repeats: Option<u32>,
+    },
+    /// Остановить разовую сессию
+    StopAdhoc,
+    /// Перечитать config.json
+    Reload,
+    /// Показать окно и передать ему фокус
+    Focus,
+    /// Переключить профиль (по id или имени)
+    SwitchProfile {
+        profile: String,
+    },
+}
+
+/// Ответ на команду
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IpcResponse {
+    pub ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<StateSnapshot>,
+}
+
+impl IpcResponse {
+    pub fn ok(state: StateSnapshot) -> Self {
+        Self {
+            ok: true,
+            error: None,
+            state: Some(state),
+        }
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            ok: false,
+            error: Some(message.into()),
+            state: None,
+        }
+    }
+}
+
+/// Разовая сессия в ответе
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdhocSnapshot {
+    pub name: String,
+    pub started_at: DateTime<Local>,
+    pub duration_secs: i64,
+}
+
+/// Предупреждение о переходе в ответе
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WarningSnapshot {
+    pub at: DateTime<Local>,
+    pub next_screen_id: u32,
+    pub next_title: String,
+}
+
+/// Активный профиль в ответе
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileSnapshot {
+    pub id: u32,
+    pub name: String,
+}
+
+/// Состояние таймера для клиентов: `ActiveScreenInfo`, поля `TimerState` и профиль
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateSnapshot {
+    pub profile: ProfileSnapshot,
+    pub current_screen: Option<ActiveScreenInfo>,
+    pub next_transition: Option<DateTime<Local>>,
+    pub is_running: bool,
+    pub paused_at: Option<DateTime<Local>>,
+    pub cycle_offset_secs: i64,
+    pub end_extension_secs: i64,
+    pub adhoc: Option<AdhocSnapshot>,
+    pub warning: Option<WarningSnapshot>,
+}
+
+impl StateSnapshot {
+    pub fn new(state: &TimerState, config: &AppConfig) -> Self {
+        let profile = config.active_profile();
+        Self {
+            profile: ProfileSnapshot {
+                id: profile.id,
+                name: profile.name.clone(),
+            },
+            current_screen: state.current_screen.clone(),
+            next_transition: state.next_transition,
+            is_running: state.is_running,
+            paused_at: state.paused_at,
+            cycle_offset_secs: state.shift.cycle_offset_secs,
+            end_extension_secs: state.shift.end_extension_secs,
+            adhoc: state.adhoc.as_ref().map(|session| AdhocSnapshot {
+                name: session.name.clone(),
+                started_at: session.started_at,
+                duration_secs: session.duration_secs,
+            }),
+            warning: state.warning.as_ref().map(|warning| WarningSnapshot {
+                at: warning.at,
+                next_screen_id: warning.next_screen_id,
+                next_title: warning.next_title.clone(),
+            }),
+        }
+    }
+}
+
+/// Запрос, ожидающий обработки в интерфейсе
+pub struct IpcCall {
+    pub request: IpcRequest,
+    reply: Sender<IpcResponse>,
+}
+
+impl IpcCall {
+    /// Отправляет ответ клиенту
+    pub fn reply(self, response: IpcResponse) {
+        let _ = self.reply.send(response);
+    }
+}
+
+#[derive(Debug)]
+pub enum IpcError {
+    /// Платформа не поддерживает Unix-сокеты
+    Unsupported,
+    /// Сокет или файл блокировки уже занят работающим экземпляром
+    AlreadyRunning(PathBuf),
+    /// Сокет создан другим пользователем
+    ForeignOwner(PathBuf),
+    Io(io::Error),
+    /// Ответ не удалось разобрать
+    Protocol(String),
+}
+
+impl fmt::Display for IpcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IpcError::Unsupported => write!(f, "управление через сокет не поддерживается"),
+            IpcError::AlreadyRunning(path) => {
+                write!(f, "{} уже используется", path.display())
+            }
+            IpcError::ForeignOwner(path) => {
+                write!(f, "{} принадлежит другому пользователю", path.display())
+            }
+            IpcError::Io(err) => write!(f, "{}", err),
+            IpcError::Protocol(msg) => write!(f, "некорректный ответ: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for IpcError {}
+
+impl From<io::Error> for IpcError {
+    fn from(err: io::Error) -> Self {
+        IpcError::Io(err)
+    }
+}
+
+/// Путь к управляющему сокету
+pub fn socket_path() -> PathBuf {
+    let runtime_dir = directories::BaseDirs::new()
+        .and_then(|dirs| dirs.runtime_dir().map(|dir| dir.to_path_buf()));
+    match runtime_dir {
+        Some(dir) => dir.join("flowtimer.sock"),
+        None => {
+            let user = std::env::var("USER").unwrap_or_else(|_| "user".into());
+            std::env::temp_dir().join(format!("flowtimer-{}.sock", user))
+        }
+    }
+}
+
+/// Файл блокировки экземпляра
+fn lock_path() -> PathBuf {
+    config_path().with_file_name("flowtimer.lock")
+}
+
+/// Захватывает файл блокировки. Блокировка держится, пока файл открыт,
+/// и снимается системой даже после аварийного завершения.
+fn lock_instance(path: &Path) -> Result<File, IpcError> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path)?;
+    match file.try_lock() {
+        Ok(()) => Ok(file),
+        Err(TryLockError::WouldBlock) => Err(IpcError::AlreadyRunning(path.to_path_buf())),
+        Err(TryLockError::Error(err)) => Err(err.into()),
+    }
+}
+
+/// Принимает запросы на управляющем сокете
+pub struct IpcServer {
+    receiver: Receiver<IpcCall>,
+    #[cfg_attr(not(unix), allow(dead_code))]
+    path: PathBuf,
+    /// Захваченный файл блокировки; освобождается после удаления сокета
+    _lock: File,
+}
+
+impl IpcServer {
+    /// Начинает слушать сокет. `wake` вызывается после каждого запроса,
+    /// чтобы интерфейс обработал его, не дожидаясь следующего кадра.
+    pub fn start<F>(wake: F) -> Result<Self, IpcError>
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        // Сокет трогает только владелец блокировки: иначе два одновременных запуска
+        // могут удалить сокет друг друга
+        let lock = lock_instance(&lock_path())?;
+        imp::start(socket_path(), lock, wake)
+    }
+
+    /// Следующий необработанный запрос
+    pub fn try_recv(&self) -> Option<IpcCall> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+#[cfg(unix)]
+impl Drop for IpcServer {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Отправляет запрос запущенному экземпляру и ждёт ответ
+pub fn send_request(request: &IpcRequest) -> Result<IpcResponse, IpcError> {
+    imp::send_request(&socket_path(), &lock_path(), request)
+}
+
+#[cfg(unix)]
+mod imp {
+    use std::{
+        fs::{self, File, Metadata},
+        io::{self, BufRead, BufReader, Write},
+        os::unix::{
+            fs::{MetadataExt, PermissionsExt},
+            net::{UnixListener, UnixStream},
+        },
+        path::{Path, PathBuf},
+        sync::{Arc, mpsc},
+        thread,
+    };
+
+    use super::{IpcCall, IpcError, IpcRequest, IpcResponse, IpcServer, REPLY_TIMEOUT};
+
+    /// Слушает сокет от имени владельца блокировки `lock`
+    pub fn start<F>(path: PathBuf, lock: File, wake: F) -> Result<IpcServer, IpcError>
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        // Файл блокировки создан текущим пользователем
+        let owner = lock.metadata()?.uid();
+        match fs::symlink_metadata(&path) {
+            // Блокировка захвачена, значит, сокет остался от аварийного завершения
+            Ok(meta) => {
+                check_owner(&path, &meta, owner)?;
+                fs::remove_file(&path)?;
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err.into()),
+        }
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let listener = match UnixListener::bind(&path) {
+            Ok(listener) => listener,
+            // Без блокировки сокет мог занять только процесс другого пользователя
+            Err(err) if err.kind() == io::ErrorKind::AddrInUse => {
+                return Err(IpcError::ForeignOwner(path));
+            }
+            Err(err) => return Err(err.into()),
+        };
+        // Во временном каталоге сокет не должен быть доступен другим пользователям
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600))?;
+        let (sender, receiver) = mpsc::channel();
+        let wake = Arc::new(wake);
+
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let sender = sender.clone();
+                let wake = Arc::clone(&wake);
+                thread::spawn(move || {
+                    let _ = serve_client(stream, &sender, wake.as_ref());
+                });
+            }
+        });
+
+        Ok(IpcServer {
+            receiver,
+            path,
+            _lock: lock,
+        })
+    }
+
+    /// Во временном каталоге файл с именем сокета может заранее создать кто угодно
+    pub(super) fn check_owner(path: &Path, meta: &Metadata, owner: u32) -> Result<(), IpcError> {
+        if meta.uid() == owner {
+            Ok(())
+        } else {
+            Err(IpcError::ForeignOwner(path.to_path_buf()))
+        }
+    }
+
+    /// Обслуживает одного клиента: по строке запроса на строку ответа
+    fn serve_client(
+        stream: UnixStream,
+        sender: &mpsc::Sender<IpcCall>,
+        wake: &(dyn Fn() + Send + Sync),
+    ) -> io::Result<()> {
+        let mut writer = stream.try_clone()?;
+        for line in BufReader::new(stream).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let response = match serde_json::from_str::<IpcRequest>(&line) {
+                Ok(request) => {
+                    let (reply, reply_receiver) = mpsc::channel();
+                    if sender.send(IpcCall { request, reply }).is_err() {
+                        // Интерфейс закрылся
+                        return Ok(());
+                    }
+                    wake();
+                    reply_receiver
+                        .recv_timeout(REPLY_TIMEOUT)
+                        .unwrap_or_else(|_| IpcResponse::error("no reply from the timer"))
+                }
+                Err(err) => IpcResponse::error(format!("invalid request: {}", err)),
+            };
+
+            let mut json = serde_json::to_string(&response).map_err(io::Error::other)?;
+            json.push('\n');
+            writer.write_all(json.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Отправляет запрос, если сокет принадлежит владельцу файла блокировки `lock_path`
+    pub fn send_request(
+        path: &Path,
+        lock_path: &Path,
+        request: &IpcRequest,
+    ) -> Result<IpcResponse, IpcError> {
+        // Чужой сокет получил бы команды и подменил бы состояние таймера
+        let owner = fs::metadata(lock_path)?.uid();
+        check_owner(path, &fs::metadata(path)?, owner)?;
+
+        let mut stream = UnixStream::connect(path)?;
+        stream.set_read_timeout(Some(REPLY_TIMEOUT * 2))?;
+
+        let mut json = serde_json::to_string(request).map_err(io::Error::other)?;
+        json.push('\n');
+        stream.write_all(json.as_bytes())?;
+
+        let mut line = String::new();
+        BufReader::new(stream).read_line(&mut line)?;
+        serde_json::from_str(&line).map_err(|e| IpcError::Protocol(e.to_string()))
+    }
+}
+
+#[cfg(not(unix))]
+mod imp {
+    use std::{
+        fs::File,
+        path::{Path, PathBuf},
+        sync::mpsc,
+    };
+
+    use super::{IpcError, IpcRequest, IpcResponse, IpcServer};
+
+    /// Без сокета запросы не приходят: сервер только держит файл блокировки
+    pub fn start<F>(path: PathBuf, lock: File, _wake: F) -> Result<IpcServer, IpcError>
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        let (_sender, receiver) = mpsc::channel();
+        Ok(IpcServer {
+            receiver,
+            path,
+            _lock: lock,
+        })
+    }
+
+    pub fn send_request(
+        _path: &Path,
+        _lock_path: &Path,
+        _request: &IpcRequest,
+    ) -> Result<IpcResponse, IpcError> {
+        Err(IpcError::Unsupported)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Отдельный каталог для файлов теста
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("flowtimer-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn lock_file_admits_one_instance() {
+        let dir = scratch_dir("lock");
+        let path = dir.join("flowtimer.lock");
+
+        let lock = lock_instance(&path).unwrap();
+        assert!(matches!(
+            lock_instance(&path),
+            Err(IpcError::AlreadyRunning(busy)) if busy == path
+        ));
+        // После выхода первого экземпляра файл снова можно захватить
+        drop(lock);
+        assert!(lock_instance(&path).is_ok());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn socket_forwards_requests_to_running_instance() {
+        let dir = scratch_dir("socket");
+        let path = dir.join("flowtimer.sock");
+        let lock_path = dir.join("flowtimer.lock");
+
+        let server = imp::start(path.clone(), lock_instance(&lock_path).unwrap(), || {}).unwrap();
+        // Второй экземпляр останавливается на блокировке и не трогает сокет
+        assert!(matches!(
+            lock_instance(&lock_path),
+            Err(IpcError::AlreadyRunning(_))
+        ));
+        assert!(path.exists());
+
+        let client = std::thread::spawn({
+            let (path, lock_path) = (path.clone(), lock_path.clone());
+            move || imp::send_request(&path, &lock_path, &IpcRequest::Extend { minutes: Some(5) })
+        });
+        let call = loop {
+            if let Some(call) = server.try_recv() {
+                break call;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        };
+        assert_eq!(call.request, IpcRequest::Extend { minutes: Some(5) });
+        call.reply(IpcResponse::error("not in a cycle"));
+
+        let response = client.join().unwrap().unwrap();
+        assert!(!response.ok);
+        assert_eq!(response.error.as_deref(), Some("not in a cycle"));
+
+        // Сокет удаляется вместе с сервером, и следующий запуск его занимает
+        drop(server);
+        assert!(!path.exists());
+        assert!(imp::start(path.clone(), lock_instance(&lock_path).unwrap(), || {}).is_ok());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn stale_socket_is_replaced_by_lock_holder() {
+        let dir = scratch_dir("stale");
+        let path = dir.join("flowtimer.sock");
+        let lock_path = dir.join("flowtimer.lock");
+        // Сокет, оставшийся после аварийного завершения
+        drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+        assert!(path.exists());
+
+        let server = imp::start(path.clone(), lock_instance(&lock_path).unwrap(), || {}).unwrap();
+        assert!(std::os::unix::net::UnixStream::connect(&path).is_ok());
+
+        drop(server);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn foreign_socket_is_refused() {
+        use std::os::unix::fs::MetadataExt;
+
+        let dir = scratch_dir("foreign");
+        let path = dir.join("flowtimer.sock");
+        fs::write(&path, "").unwrap();
+        let meta = fs::metadata(&path).unwrap();
+
+        assert!(imp::check_owner(&path, &meta, meta.uid()).is_ok());
+        assert!(matches!(
+            imp::check_owner(&path, &meta, meta.uid() + 1),
+            Err(IpcError::ForeignOwner(foreign)) if foreign == path
+        ));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
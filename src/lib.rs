@@ -1,9 +1,19 @@
+pub mod autostart;
+pub mod calendar;
+pub mod cli;
 pub mod config;
+pub mod history;
+pub mod ipc;
+pub mod locale;
+pub mod migrations;
+pub mod notify;
+pub mod sharing;
+pub mod sound;
 pub mod timer;
 pub mod ui;
 pub mod utils;
+pub mod validation;
 
 // Re-export utility functions
-pub use utils::{make_bundle, tr, set_language};
-
-
+pub use locale::make_bundle;
+pub use utils::{set_language, tr};
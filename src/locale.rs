@@ -0,0 +1,362 @@
+//! Локализации интерфейса.
+//!
+//! Встроенные локали (`locales/*.ftl`) дополняются файлами `<тег>.ftl` из
+//! каталога [`user_locales_dir`]: файл с тегом встроенной локали переопределяет
+//! её сообщения, файл с новым тегом добавляет язык. Сообщение ищется по цепочке
+//! локалей от точной к общей, например de-AT → de → en-US.
+//!
+//! Ошибки разбора файлов и подстановки сообщений не теряются: каждая ошибка
+//! один раз печатается в stderr и попадает в список [`reported_errors`].
+
+use std::{fmt, fs, path::PathBuf, sync::Mutex};
+
+use directories::ProjectDirs;
+use fluent_bundle::{FluentBundle, FluentResource};
+use fluent_langneg::{NegotiationStrategy, negotiate_languages};
+use unic_langid::{LanguageIdentifier, langid};
+
+/// Последняя локаль любой цепочки: в ней есть все сообщения
+pub const FALLBACK_LOCALE: LanguageIdentifier = langid!("en-US");
+
+/// Локали, встроенные в программу
+const BUILTIN_LOCALES: &[(&str, &str)] = &[
+    ("en-US", include_str!("../locales/en-US.ftl")),
+    ("ru-RU", include_str!("../locales/ru-RU.ftl")),
+];
+
+/// Сообщение с названием языка на нём самом
+const NAME_MESSAGE: &str = "language-name";
+
+/// Язык, который можно выбрать в настройках
+#[derive(Debug, Clone, PartialEq)]
+pub struct AvailableLocale {
+    pub id: LanguageIdentifier,
+    /// Название языка на нём самом, а если его нет в файле - тег
+    pub name: String,
+    /// Файл в каталоге пользователя (для встроенной локали - если он её дополняет)
+    pub file: Option<PathBuf>,
+    builtin: Option<&'static str>,
+}
+
+impl AsRef<LanguageIdentifier> for AvailableLocale {
+    fn as_ref(&self) -> &LanguageIdentifier {
+        &self.id
+    }
+}
+
+impl AvailableLocale {
+    /// Сообщения локали: встроенные, затем из файла пользователя
+    fn resources(&self) -> Vec<FluentResource> {
+        let builtin = self
+            .builtin
+            .map(|text| parse_resource(&format!("{} (встроенная)", self.id), text.to_string()));
+        let file = self
+            .file
+            .as_ref()
+            .and_then(|path| match fs::read_to_string(path) {
+                Ok(text) => Some(parse_resource(&path.display().to_string(), text)),
+                Err(err) => {
+                    report(LocaleError::Io {
+                        path: path.clone(),
+                        error: err.to_string(),
+                    });
+                    None
+                }
+            });
+        builtin.into_iter().chain(file).collect()
+    }
+}
+
+/// Ошибка локализации
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LocaleError {
+    /// Файл локали не удалось прочитать
+    Io { path: PathBuf, error: String },
+    /// В файле есть синтаксические ошибки; остальные сообщения загружены
+    Parse { source: String, errors: Vec<String> },
+    /// Сообщения нет ни в одной локали цепочки
+    MissingMessage {
+        locale: LanguageIdentifier,
+        id: String,
+    },
+    /// Сообщение собрано не полностью, например без нужного параметра
+    Format {
+        locale: LanguageIdentifier,
+        id: String,
+        error: String,
+    },
+}
+
+impl fmt::Display for LocaleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LocaleError::Io { path, error } => {
+                write!(
+                    f,
+                    "не удалось прочитать локаль {}: {}",
+                    path.display(),
+                    error
+                )
+            }
+            LocaleError::Parse { source, errors } => {
+                write!(f, "ошибки в локали {}: {}", source, errors.join("; "))
+            }
+            LocaleError::MissingMessage { locale, id } => {
+                write!(f, "{}: нет сообщения «{}»", locale, id)
+            }
+            LocaleError::Format { locale, id, error } => {
+                write!(f, "{}: ошибка в сообщении «{}»: {}", locale, id, error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LocaleError {}
+
+/// Уже сообщённые ошибки: интерфейс переводит строки каждый кадр, а печатать
+/// одну и ту же ошибку нужно один раз
+static REPORTED: Mutex<Vec<LocaleError>> = Mutex::new(Vec::new());
+
+/// Сообщает об ошибке локализации, если о ней ещё не сообщали
+pub fn report(error: LocaleError) {
+    let mut reported = REPORTED.lock().unwrap_or_else(|err| err.into_inner());
+    if !reported.contains(&error) {
+        eprintln!("flow_timer: {}", error);
+        reported.push(error);
+    }
+}
+
+/// Ошибки локализации, встреченные с запуска программы
+pub fn reported_errors() -> Vec<LocaleError> {
+    REPORTED
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .clone()
+}
+
+/// Каталог пользовательских локалей: `locales` в каталоге данных программы
+pub fn user_locales_dir() -> Option<PathBuf> {
+    ProjectDirs::from("dev", "pet_projects", "FlowTimer")
+        .map(|dirs| dirs.data_dir().join("locales"))
+}
+
+/// Встроенные локали и файлы `<тег>.ftl` из каталога пользователя, по тегу.
+/// Файлы, имя которых не является тегом языка, пропускаются.
+pub fn available_locales() -> Vec<AvailableLocale> {
+    let mut locales: Vec<AvailableLocale> = BUILTIN_LOCALES
+        .iter()
+        .map(|(tag, text)| AvailableLocale {
+            id: tag.parse().expect("тег встроенной локали"),
+            name: String::new(),
+            file: None,
+            builtin: Some(text),
+        })
+        .collect();
+
+    let entries = user_locales_dir().and_then(|dir| fs::read_dir(dir).ok());
+    for path in entries
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|e| e.path())
+    {
+        if path.extension().is_none_or(|ext| ext != "ftl") {
+            continue;
+        }
+        let Some(id) = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.parse::<LanguageIdentifier>().ok())
+        else {
+            continue;
+        };
+        match locales.iter_mut().find(|locale| locale.id == id) {
+            Some(locale) => locale.file = Some(path),
+            None => locales.push(AvailableLocale {
+                id,
+                name: String::new(),
+                file: Some(path),
+                builtin: None,
+            }),
+        }
+    }
+
+    for locale in &mut locales {
+        locale.name = own_name(locale).unwrap_or_else(|| locale.id.to_string());
+    }
+    locales.sort_by_key(|locale| locale.id.to_string());
+    locales
+}
+
+/// Название языка из его собственных сообщений, без цепочки
+fn own_name(locale: &AvailableLocale) -> Option<String> {
+    let mut bundle = FluentBundle::new(vec![locale.id.clone()]);
+    for resource in locale.resources() {
+        bundle.add_resource_overriding(resource);
+    }
+    let pattern = bundle.get_message(NAME_MESSAGE)?.value()?;
+    let mut errors = Vec::new();
+    Some(
+        bundle
+            .format_pattern(pattern, None, &mut errors)
+            .into_owned(),
+    )
+}
+
+/// Разбирает FTL; при синтаксических ошибках сообщает о них и оставляет
+/// остальные сообщения
+fn parse_resource(source: &str, text: String) -> FluentResource {
+    FluentResource::try_new(text).unwrap_or_else(|(resource, errors)| {
+        let errors = errors
+            .iter()
+            .map(|err| {
+                let line = resource.source()[..err.pos.start].matches('\n').count() + 1;
+                format!("строка {}: {}", line, err)
+            })
+            .collect();
+        report(LocaleError::Parse {
+            source: source.to_string(),
+            errors,
+        });
+        resource
+    })
+}
+
+/// Цепочка локалей для языка: подходящие доступные локали от точной к общей,
+/// в конце - [`FALLBACK_LOCALE`]
+pub fn fallback_chain(
+    requested: &LanguageIdentifier,
+    available: &[AvailableLocale],
+) -> Vec<LanguageIdentifier> {
+    let ids: Vec<LanguageIdentifier> = available.iter().map(|locale| locale.id.clone()).collect();
+    negotiate_languages(
+        &[requested],
+        &ids,
+        Some(&FALLBACK_LOCALE),
+        NegotiationStrategy::Filtering,
+    )
+    .into_iter()
+    .cloned()
+    .collect()
+}
+
+/// Язык системы по первой заданной переменной окружения в порядке приоритета
+/// gettext: `LANGUAGE` (список через двоеточие), `LC_ALL`, `LC_MESSAGES`, `LANG`.
+/// Значения `C` и `POSIX` языка не задают.
+pub fn system_locale() -> Option<LanguageIdentifier> {
+    let value = ["LANGUAGE", "LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())?;
+    value.split(':').find_map(parse_posix_locale)
+}
+
+/// `de_AT.UTF-8@euro` → de-AT
+fn parse_posix_locale(value: &str) -> Option<LanguageIdentifier> {
+    let tag = value.split(['.', '@']).next()?;
+    if tag.is_empty() || tag == "C" || tag == "POSIX" {
+        return None;
+    }
+    tag.replace('_', "-").parse().ok()
+}
+
+/// Собирает bundle для языка из уже найденных локалей ([`available_locales`]).
+/// Сообщения локалей цепочки накладываются от общей к точной, так что
+/// недостающие в точной берутся из следующей по цепочке.
+pub fn make_bundle(
+    lang: &LanguageIdentifier,
+    available: &[AvailableLocale],
+) -> FluentBundle<FluentResource> {
+    let chain = fallback_chain(lang, available);
+
+    let mut bundle = FluentBundle::new(chain.clone());
+    for id in chain.iter().rev() {
+        let Some(locale) = available.iter().find(|locale| &locale.id == id) else {
+            continue;
+        };
+        for resource in locale.resources() {
+            bundle.add_resource_overriding(resource);
+        }
+    }
+    bundle
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Локаль с сообщениями в тексте, без файла пользователя
+    fn locale(tag: &str, text: &'static str) -> AvailableLocale {
+        AvailableLocale {
+            id: tag.parse().unwrap(),
+            name: tag.to_string(),
+            file: None,
+            builtin: Some(text),
+        }
+    }
+
+    fn locales() -> Vec<AvailableLocale> {
+        vec![
+            locale("de", "greeting = Hallo\nfarewell = Tschüss"),
+            locale("de-AT", "greeting = Servus"),
+            locale("en-US", "greeting = Hello\nfarewell = Bye\ntitle = Timer"),
+            locale("ru-RU", "greeting = Привет"),
+        ]
+    }
+
+    fn chain(tag: &str) -> Vec<String> {
+        let requested: LanguageIdentifier = tag.parse().unwrap();
+        fallback_chain(&requested, &locales())
+            .iter()
+            .map(|id| id.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn posix_locales_are_parsed() {
+        let parse = |value| parse_posix_locale(value).map(|id| id.to_string());
+        assert_eq!(parse("de_AT.UTF-8@euro").as_deref(), Some("de-AT"));
+        assert_eq!(parse("ru_RU.UTF-8").as_deref(), Some("ru-RU"));
+        assert_eq!(parse("sr_RS@latin").as_deref(), Some("sr-RS"));
+        assert_eq!(parse("fr").as_deref(), Some("fr"));
+        assert_eq!(parse("C"), None);
+        assert_eq!(parse("C.UTF-8"), None);
+        assert_eq!(parse("POSIX"), None);
+        assert_eq!(parse(""), None);
+    }
+
+    #[test]
+    fn chain_goes_from_exact_to_fallback() {
+        assert_eq!(chain("de-AT"), vec!["de-AT", "de", "en-US"]);
+        // Соседний регион лучше английского, но уступает общему языку
+        assert_eq!(chain("de-CH"), vec!["de", "de-AT", "en-US"]);
+        assert_eq!(chain("ru"), vec!["ru-RU", "en-US"]);
+        assert_eq!(chain("fr-FR"), vec!["en-US"]);
+        assert_eq!(chain("en-US"), vec!["en-US"]);
+    }
+
+    #[test]
+    fn bundle_falls_back_per_message() {
+        let bundle = make_bundle(&"de-AT".parse().unwrap(), &locales());
+        let message = |id| {
+            let pattern = bundle.get_message(id)?.value()?;
+            let mut errors = Vec::new();
+            Some(
+                bundle
+                    .format_pattern(pattern, None, &mut errors)
+                    .into_owned(),
+            )
+        };
+        assert_eq!(message("greeting").as_deref(), Some("Servus"));
+        assert_eq!(message("farewell").as_deref(), Some("Tschüss"));
+        assert_eq!(message("title").as_deref(), Some("Timer"));
+        assert_eq!(message("missing"), None);
+    }
+
+    #[test]
+    fn own_name_ignores_fallback() {
+        let named = locale("de", "language-name = Deutsch");
+        assert_eq!(own_name(&named).as_deref(), Some("Deutsch"));
+        assert_eq!(own_name(&locale("de-AT", "greeting = Servus")), None);
+    }
+}
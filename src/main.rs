@@ -1,25 +1,103 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+use std::sync::{Arc, OnceLock};
+
 use eframe::egui;
 
+mod autostart;
+mod calendar;
+mod cli;
 mod config;
+mod history;
+mod ipc;
+mod locale;
+mod migrations;
+mod notify;
+mod sharing;
+mod sound;
 mod timer;
 mod ui;
 mod utils;
+mod validation;
 
+use config::ConfigWatcher;
 use config::load_or_default_config;
-use ui::{AppState, SettingsTab};
-use utils::make_bundle;
+use ipc::{IpcError, IpcRequest, IpcServer};
+use locale::make_bundle;
+use notify::DesktopNotifications;
+use sound::SoundPlayer;
 use timer::TimerScheduler;
+use ui::{AppState, ConfigIssue, HistoryState, SettingsTab, SharingState, WindowTracking};
+
+const WINDOW_SIZE: [f32; 2] = [500.0, 300.0];
 
 fn main() -> eframe::Result<()> {
-    let viewport = egui::ViewportBuilder::default()
-        .with_inner_size([500.0, 300.0])
-        .with_min_inner_size([500.0, 300.0])
-        .with_max_inner_size([500.0, 300.0])
+    // Подкоманды (status, next, schedule, validate) работают без окна
+    match cli::parse_args(std::env::args().skip(1)) {
+        Ok(Some(args)) => std::process::exit(cli::run(&args)),
+        Ok(None) => {}
+        Err(err) => {
+            eprintln!("flow_timer: {}\nflow_timer help - список команд", err);
+            std::process::exit(2);
+        }
+    }
+
+    // Второй экземпляр не запускает свой планировщик и не пишет config.json:
+    // он поднимает окно уже работающего и завершается
+    let repaint_ctx: Arc<OnceLock<egui::Context>> = Arc::new(OnceLock::new());
+    let ipc = match IpcServer::start({
+        let repaint_ctx = Arc::clone(&repaint_ctx);
+        move || {
+            if let Some(ctx) = repaint_ctx.get() {
+                ctx.request_repaint();
+            }
+        }
+    }) {
+        Ok(server) => Some(server),
+        Err(IpcError::AlreadyRunning(_)) => match ipc::send_request(&IpcRequest::Focus) {
+            Ok(_) => std::process::exit(0),
+            // Без сокета (файл блокировки) окно первого экземпляра не поднять
+            Err(IpcError::Unsupported) => {
+                eprintln!("flow_timer: программа уже запущена");
+                std::process::exit(0);
+            }
+            Err(err) => {
+                eprintln!(
+                    "flow_timer: программа уже запущена, но не отвечает: {}",
+                    err
+                );
+                std::process::exit(1);
+            }
+        },
+        Err(err @ IpcError::ForeignOwner(_)) => {
+            eprintln!("flow_timer: управление через сокет отключено: {}", err);
+            None
+        }
+        // Без сокета и блокировки (нет прав) работаем как обычно
+        Err(_) => None,
+    };
+
+    // Испорченный config.json не перезаписывается: окно откроется с экраном восстановления
+    let (config_path, config, broken_config) = load_or_default_config();
+
+    // Восстанавливаем сохранённое положение и размер; ограничения min/max
+    // всё равно удерживают окно в пределах фиксированного размера
+    let saved_position = config.system_settings.window_position.clone();
+    let inner_size = saved_position
+        .as_ref()
+        .and_then(|pos| pos.size)
+        .unwrap_or(WINDOW_SIZE);
+
+    let mut viewport = egui::ViewportBuilder::default()
+        .with_inner_size(inner_size)
+        .with_min_inner_size(WINDOW_SIZE)
+        .with_max_inner_size(WINDOW_SIZE)
         .with_decorations(false)
         .with_resizable(false)
         .with_always_on_top();
+    if let Some(pos) = &saved_position {
+        viewport = viewport.with_position([pos.x, pos.y]);
+    }
 
     let native_options = eframe::NativeOptions {
         viewport,
@@ -29,7 +107,15 @@ fn main() -> eframe::Result<()> {
     eframe::run_native(
         "FlowTimer",
         native_options,
-        Box::new(|cc| Ok(Box::new(MyApp::new(cc)))),
+        Box::new(|cc| {
+            let _ = repaint_ctx.set(cc.egui_ctx.clone());
+            Ok(Box::new(MyApp::new(
+                config_path,
+                config,
+                broken_config,
+                ipc,
+            )))
+        }),
     )
 }
 
@@ -38,15 +124,27 @@ fn main() -> eframe::Result<()> {
 struct MyApp(AppState);
 
 impl MyApp {
-    fn new(_cc: &eframe::CreationContext<'_>) -> Self {
-        let (config_path, config) = load_or_default_config();
-        let bundle = make_bundle(&config.language);
+    fn new(
+        config_path: std::path::PathBuf,
+        config: config::AppConfig,
+        broken_config: Option<config::BrokenConfig>,
+        ipc: Option<IpcServer>,
+    ) -> Self {
+        let locales = locale::available_locales();
+        let bundle = make_bundle(&config.language, &locales);
+        // Запись автозапуска могла остаться от старого расположения программы
+        let autostart_notice = match autostart::sync(&config.system_settings) {
+            Ok(status) => status.map(Ok),
+            Err(err) => Some(Err(err.to_string())),
+        };
+        let config_watcher = ConfigWatcher::new(&config_path);
         let next_screen_id = config.screens.iter().map(|s| s.id).max().unwrap_or(0) + 1;
-        let next_interval_id = config.intervals.iter().map(|i| i.id).max().unwrap_or(0) + 1;
+        let next_interval_id = config.all_intervals().map(|i| i.id).max().unwrap_or(0) + 1;
         Self(AppState {
             config,
             config_path,
             bundle,
+            locales,
             show_settings: false,
             settings_tab: SettingsTab::Timers,
             editing_screen: None,
@@ -54,6 +152,20 @@ impl MyApp {
             next_screen_id,
             next_interval_id,
             timer_scheduler: TimerScheduler::new(),
+            adhoc_draft: None,
+            sound_player: SoundPlayer::new(),
+            autostart_notice,
+            window_tracking: WindowTracking::default(),
+            notifications: DesktopNotifications::new(),
+            ipc,
+            config_watcher,
+            // Пока файл не исправлен или не сброшен, сохранение в него запрещено
+            config_issue: broken_config
+                .as_ref()
+                .map(|broken| ConfigIssue::ParseError(broken.error.clone())),
+            config_recovery: broken_config,
+            sharing: SharingState::default(),
+            history: HistoryState::default(),
         })
     }
 }
@@ -62,14 +174,9 @@ impl eframe::App for MyApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         self.0.update_ui(ctx);
     }
-}
-
-
-
-
-
-
-
-
-
 
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.0.save_window_position();
+        self.0.finish_history();
+    }
+}
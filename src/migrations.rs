@@ -0,0 +1,158 @@
+//! Миграции формата config.json.
+//!
+//! Файл разбирается сначала в `serde_json::Value`, затем последовательно
+//! проходит миграции от своей `schema_version` до [`CURRENT_SCHEMA_VERSION`]
+//! и только после этого превращается в `AppConfig`. Каждая миграция переводит
+//! конфигурацию ровно на одну версию вперёд.
+//!
+//! Как добавить версию: увеличить `CURRENT_SCHEMA_VERSION`, дописать функцию
+//! в `MIGRATIONS` и положить пример файла предыдущей версии в
+//! `tests/fixtures/config/`.
+
+use std::fmt;
+
+use serde_json::{Map, Value, json};
+
+/// Версия формата, которую записывает текущая сборка
+pub const CURRENT_SCHEMA_VERSION: u32 = 2;
+
+const VERSION_KEY: &str = "schema_version";
+
+type Migration = fn(&mut Map<String, Value>) -> Result<(), MigrationError>;
+
+/// `MIGRATIONS[n]` переводит конфигурацию из версии `n` в версию `n + 1`
+const MIGRATIONS: [Migration; CURRENT_SCHEMA_VERSION as usize] = [v0_to_v1, v1_to_v2];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MigrationError {
+    /// Корень файла - не JSON-объект
+    NotAnObject,
+    /// `schema_version` не является неотрицательным целым числом
+    InvalidVersion(Value),
+    /// Файл записан более новой версией программы
+    UnsupportedVersion(u64),
+    /// Поле имеет неожиданный вид
+    InvalidField {
+        path: String,
+        expected: &'static str,
+    },
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MigrationError::NotAnObject => write!(f, "ожидается JSON-объект"),
+            MigrationError::InvalidVersion(value) => {
+                write!(f, "некорректная schema_version: {}", value)
+            }
+            MigrationError::UnsupportedVersion(version) => write!(
+                f,
+                "schema_version {} записана более новой версией программы (поддерживается до {})",
+                version, CURRENT_SCHEMA_VERSION
+            ),
+            MigrationError::InvalidField { path, expected } => {
+                write!(f, "поле {}: ожидается {}", path, expected)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MigrationError {}
+
+/// Версия формата файла. Файлы без поля считаются версией 0.
+pub fn schema_version(value: &Value) -> Result<u32, MigrationError> {
+    let object = value.as_object().ok_or(MigrationError::NotAnObject)?;
+    let Some(version) = object.get(VERSION_KEY) else {
+        return Ok(0);
+    };
+    let version = version
+        .as_u64()
+        .ok_or_else(|| MigrationError::InvalidVersion(version.clone()))?;
+    if version > CURRENT_SCHEMA_VERSION as u64 {
+        return Err(MigrationError::UnsupportedVersion(version));
+    }
+    Ok(version as u32)
+}
+
+/// Приводит конфигурацию к текущей версии формата.
+/// Возвращает исходную версию файла.
+pub fn migrate(value: &mut Value) -> Result<u32, MigrationError> {
+    migrate_to(value, CURRENT_SCHEMA_VERSION)
+}
+
+/// Приводит конфигурацию к версии `target` (не выше текущей).
+/// Возвращает исходную версию файла.
+pub fn migrate_to(value: &mut Value, target: u32) -> Result<u32, MigrationError> {
+    let from = schema_version(value)?;
+    let object = value.as_object_mut().ok_or(MigrationError::NotAnObject)?;
+
+    let steps = MIGRATIONS
+        .iter()
+        .enumerate()
+        .take(target as usize)
+        .skip(from as usize);
+    for (version, migration) in steps {
+        migration(object)?;
+        object.insert(VERSION_KEY.into(), Value::from(version as u32 + 1));
+    }
+    Ok(from)
+}
+
+/// Версия 0 - все файлы, записанные до появления `schema_version`.
+/// Новые поля тогда добавлялись только со значениями по умолчанию, поэтому
+/// структура уже совместима с версией 1; проверяем лишь обязательные разделы,
+/// чтобы ошибка указывала на конкретное поле.
+fn v0_to_v1(config: &mut Map<String, Value>) -> Result<(), MigrationError> {
+    for key in ["screens", "intervals"] {
+        if !config.get(key).is_some_and(Value::is_array) {
+            return Err(MigrationError::InvalidField {
+                path: key.into(),
+                expected: "массив",
+            });
+        }
+    }
+    if !config.get("system_settings").is_some_and(Value::is_object) {
+        return Err(MigrationError::InvalidField {
+            path: "system_settings".into(),
+            expected: "объект",
+        });
+    }
+    Ok(())
+}
+
+/// Версия 2: интервалы и экран по умолчанию переехали в профили.
+/// Прежний единственный набор интервалов становится профилем 1.
+fn v1_to_v2(config: &mut Map<String, Value>) -> Result<(), MigrationError> {
+    let intervals = config.remove("intervals").unwrap_or_else(|| json!([]));
+    if !intervals.is_array() {
+        return Err(MigrationError::InvalidField {
+            path: "intervals".into(),
+            expected: "массив",
+        });
+    }
+    let default_screen_id = config.remove("default_screen_id").unwrap_or(Value::Null);
+
+    // Имя зафиксировано здесь, а не берётся из текущего кода: миграция не должна меняться
+    let russian = config
+        .get("language")
+        .and_then(Value::as_str)
+        .is_some_and(|language| language.starts_with("ru"));
+    let name = if russian {
+        "Основной"
+    } else {
+        "Default"
+    };
+
+    config.insert(
+        "profiles".into(),
+        json!([{
+            "id": 1,
+            "name": name,
+            "intervals": intervals,
+            "default_screen_id": default_screen_id,
+            "auto_days": [],
+        }]),
+    );
+    config.insert("active_profile_id".into(), json!(1));
+    Ok(())
+}
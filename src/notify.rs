@@ -0,0 +1,412 @@
+//! Уведомления рабочего стола о переходах.
+//!
+//! На Linux уведомления отправляются в `org.freedesktop.Notifications` на сессионной
+//! шине D-Bus. Адрес шины берётся из `DBUS_SESSION_BUS_ADDRESS`, поэтому для проверки
+//! достаточно запустить приложение под `dbus-run-session` с подставным сервисом
+//! уведомлений или передать адрес в [`DbusNotifier::connect_address`].
+//!
+//! Вызовы D-Bus выполняются в отдельном потоке: если сервис уведомлений не отвечает,
+//! интерфейс не должен подвисать.
+
+use std::{
+    fmt,
+    sync::{
+        Arc, Mutex,
+        mpsc::{self, Sender},
+    },
+    thread,
+};
+
+/// Содержимое уведомления
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification {
+    /// Заголовок (название экрана)
+    pub summary: String,
+    /// Текст (интервал и время следующего перехода)
+    pub body: String,
+}
+
+#[derive(Debug)]
+pub enum NotifyError {
+    /// Уведомления не поддерживаются на этой платформе или в этой сборке
+    Unsupported,
+    /// Не удалось подключиться к шине или вызвать сервис уведомлений
+    Bus(String),
+}
+
+impl fmt::Display for NotifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotifyError::Unsupported => write!(f, "уведомления не поддерживаются"),
+            NotifyError::Bus(msg) => write!(f, "D-Bus: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for NotifyError {}
+
+/// Способ показа уведомлений
+pub trait Notifier: Send {
+    fn notify(&mut self, notification: &Notification) -> Result<(), NotifyError>;
+}
+
+/// Notifier без вывода: запоминает отправленные уведомления.
+/// Копии разделяют список, так что отправленное видно и после передачи в поток.
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(not(test), allow(dead_code))]
+pub struct NullNotifier {
+    pub sent: Arc<Mutex<Vec<Notification>>>,
+}
+
+impl Notifier for NullNotifier {
+    fn notify(&mut self, notification: &Notification) -> Result<(), NotifyError> {
+        if let Ok(mut sent) = self.sent.lock() {
+            sent.push(notification.clone());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "notifications"))]
+pub use dbus::DbusNotifier;
+
+#[cfg(all(target_os = "linux", feature = "notifications"))]
+mod dbus {
+    use std::collections::HashMap;
+
+    use zbus::{blocking::Connection, zvariant::Value};
+
+    use super::{Notification, Notifier, NotifyError};
+
+    const DESTINATION: &str = "org.freedesktop.Notifications";
+    const PATH: &str = "/org/freedesktop/Notifications";
+    /// Время показа уведомления, мс
+    const EXPIRE_TIMEOUT_MS: i32 = 10_000;
+    /// Важность уведомления (подсказка `urgency`): обычная, не критическая
+    const URGENCY_NORMAL: u8 = 1;
+
+    /// Уведомления через `org.freedesktop.Notifications`
+    pub struct DbusNotifier {
+        connection: Connection,
+        /// Id предыдущего уведомления: новое заменяет его, а не копится в списке
+        last_id: u32,
+    }
+
+    impl DbusNotifier {
+        /// Подключается к сессионной шине (`DBUS_SESSION_BUS_ADDRESS`)
+        pub fn connect() -> Result<Self, NotifyError> {
+            let connection = Connection::session().map_err(bus_error)?;
+            Ok(Self {
+                connection,
+                last_id: 0,
+            })
+        }
+
+        /// Подключается к шине по явному адресу, например `unix:path=/tmp/bus`
+        #[cfg_attr(not(test), allow(dead_code))]
+        pub fn connect_address(address: &str) -> Result<Self, NotifyError> {
+            let connection = zbus::blocking::connection::Builder::address(address)
+                .and_then(|builder| builder.build())
+                .map_err(bus_error)?;
+            Ok(Self {
+                connection,
+                last_id: 0,
+            })
+        }
+    }
+
+    fn bus_error(err: zbus::Error) -> NotifyError {
+        NotifyError::Bus(err.to_string())
+    }
+
+    impl Notifier for DbusNotifier {
+        fn notify(&mut self, notification: &Notification) -> Result<(), NotifyError> {
+            let actions: Vec<&str> = Vec::new();
+            let hints: HashMap<&str, Value<'_>> =
+                HashMap::from([("urgency", Value::from(URGENCY_NORMAL))]);
+            let reply = self
+                .connection
+                .call_method(
+                    Some(DESTINATION),
+                    PATH,
+                    Some(DESTINATION),
+                    "Notify",
+                    &(
+                        "FlowTimer",
+                        self.last_id,
+                        "",
+                        notification.summary.as_str(),
+                        notification.body.as_str(),
+                        actions,
+                        hints,
+                        EXPIRE_TIMEOUT_MS,
+                    ),
+                )
+                .map_err(bus_error)?;
+            self.last_id = reply.body().deserialize::<u32>().map_err(bus_error)?;
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::{
+            io::{BufRead, BufReader},
+            process::{Child, Command, Stdio},
+            sync::{Arc, Mutex},
+        };
+
+        use zbus::zvariant::OwnedValue;
+
+        use super::*;
+
+        /// Отдельная сессионная шина; процесс завершается вместе с тестом
+        struct PrivateBus(Child);
+
+        impl Drop for PrivateBus {
+            fn drop(&mut self) {
+                let _ = self.0.kill();
+                let _ = self.0.wait();
+            }
+        }
+
+        /// Запускает `dbus-daemon` и возвращает адрес шины
+        fn private_bus() -> (PrivateBus, String) {
+            let mut child = Command::new("dbus-daemon")
+                .args(["--session", "--nofork", "--print-address"])
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn()
+                .expect("dbus-daemon не запустился");
+            let stdout = child.stdout.take().unwrap();
+            let bus = PrivateBus(child);
+            let mut address = String::new();
+            BufReader::new(stdout).read_line(&mut address).unwrap();
+            (bus, address.trim().to_string())
+        }
+
+        /// Полученное уведомление: id заменяемого, заголовок, текст и важность
+        type Received = (u32, String, String, Option<u8>);
+
+        /// Подставной сервис уведомлений
+        #[derive(Default)]
+        struct FakeNotifications {
+            received: Arc<Mutex<Vec<Received>>>,
+        }
+
+        #[zbus::interface(name = "org.freedesktop.Notifications")]
+        impl FakeNotifications {
+            #[allow(clippy::too_many_arguments)]
+            fn notify(
+                &self,
+                _app_name: String,
+                replaces_id: u32,
+                _app_icon: String,
+                summary: String,
+                body: String,
+                _actions: Vec<String>,
+                hints: HashMap<String, OwnedValue>,
+                _expire_timeout: i32,
+            ) -> u32 {
+                let urgency = hints
+                    .get("urgency")
+                    .and_then(|value| u8::try_from(value).ok());
+                let mut received = self.received.lock().unwrap();
+                received.push((replaces_id, summary, body, urgency));
+                received.len() as u32
+            }
+        }
+
+        // Запуск: cargo test -- --ignored
+        #[test]
+        #[ignore = "нужен dbus-daemon"]
+        fn notification_reaches_service() {
+            let (_bus, address) = private_bus();
+            let service = FakeNotifications::default();
+            let received = Arc::clone(&service.received);
+            let _server = zbus::blocking::connection::Builder::address(address.as_str())
+                .unwrap()
+                .name(DESTINATION)
+                .unwrap()
+                .serve_at(PATH, service)
+                .unwrap()
+                .build()
+                .unwrap();
+
+            let mut notifier = DbusNotifier::connect_address(&address).unwrap();
+            for (summary, body) in [("Работа", "Фокус до 10:25"), ("Перерыв", "до 10:30")]
+            {
+                notifier
+                    .notify(&Notification {
+                        summary: summary.into(),
+                        body: body.into(),
+                    })
+                    .unwrap();
+            }
+
+            // Второе уведомление заменяет первое
+            assert_eq!(
+                *received.lock().unwrap(),
+                vec![
+                    (
+                        0,
+                        "Работа".into(),
+                        "Фокус до 10:25".into(),
+                        Some(URGENCY_NORMAL)
+                    ),
+                    (1, "Перерыв".into(), "до 10:30".into(), Some(URGENCY_NORMAL)),
+                ]
+            );
+        }
+    }
+}
+
+/// Открывает системный сервис уведомлений
+pub fn open_notifier() -> Result<Box<dyn Notifier>, NotifyError> {
+    #[cfg(all(target_os = "linux", feature = "notifications"))]
+    {
+        DbusNotifier::connect().map(|notifier| Box::new(notifier) as Box<dyn Notifier>)
+    }
+    #[cfg(not(all(target_os = "linux", feature = "notifications")))]
+    {
+        Err(NotifyError::Unsupported)
+    }
+}
+
+/// Отправляет уведомления в фоновом потоке
+pub struct DesktopNotifications {
+    sender: Option<Sender<Notification>>,
+    last_error: Arc<Mutex<Option<String>>>,
+}
+
+impl DesktopNotifications {
+    pub fn new() -> Self {
+        Self {
+            sender: None,
+            last_error: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Ставит уведомление в очередь. Подключение к сервису создаётся при первом вызове.
+    pub fn send(&mut self, notification: Notification) {
+        let sender = self
+            .sender
+            .get_or_insert_with(|| spawn_worker(open_notifier, Arc::clone(&self.last_error)));
+        if let Err(mpsc::SendError(notification)) = sender.send(notification) {
+            // Поток завершился - пробуем подключиться заново
+            let sender = spawn_worker(open_notifier, Arc::clone(&self.last_error));
+            let _ = sender.send(notification);
+            self.sender = Some(sender);
+        }
+    }
+
+    /// Последняя ошибка отправки (показывается в настройках)
+    pub fn last_error(&self) -> Option<String> {
+        self.last_error.lock().ok().and_then(|error| error.clone())
+    }
+}
+
+impl Default for DesktopNotifications {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn spawn_worker<F>(open: F, last_error: Arc<Mutex<Option<String>>>) -> Sender<Notification>
+where
+    F: FnOnce() -> Result<Box<dyn Notifier>, NotifyError> + Send + 'static,
+{
+    let (sender, receiver) = mpsc::channel::<Notification>();
+    thread::spawn(move || {
+        let set_error = |error: Option<String>| {
+            if let Ok(mut last) = last_error.lock() {
+                *last = error;
+            }
+        };
+
+        let mut notifier = match open() {
+            Ok(notifier) => notifier,
+            Err(err) => {
+                set_error(Some(err.to_string()));
+                return;
+            }
+        };
+        for notification in receiver {
+            set_error(notifier.notify(&notification).err().map(|e| e.to_string()));
+        }
+    });
+    sender
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::*;
+
+    fn notification(summary: &str) -> Notification {
+        Notification {
+            summary: summary.into(),
+            body: String::new(),
+        }
+    }
+
+    /// Ждёт, пока фоновый поток не выполнит условие
+    fn wait_for(mut done: impl FnMut() -> bool) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !done() {
+            assert!(Instant::now() < deadline, "фоновый поток не ответил");
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    /// Notifier, который не может достучаться до сервиса
+    struct Unreachable;
+
+    impl Notifier for Unreachable {
+        fn notify(&mut self, _notification: &Notification) -> Result<(), NotifyError> {
+            Err(NotifyError::Bus("нет ответа".into()))
+        }
+    }
+
+    #[test]
+    fn worker_sends_in_order() {
+        let notifier = NullNotifier::default();
+        let sent = Arc::clone(&notifier.sent);
+        let last_error = Arc::new(Mutex::new(None));
+        let sender = spawn_worker(
+            move || Ok(Box::new(notifier) as Box<dyn Notifier>),
+            Arc::clone(&last_error),
+        );
+
+        sender.send(notification("Работа")).unwrap();
+        sender.send(notification("Перерыв")).unwrap();
+        wait_for(|| sent.lock().unwrap().len() == 2);
+        assert_eq!(
+            *sent.lock().unwrap(),
+            vec![notification("Работа"), notification("Перерыв")]
+        );
+        assert_eq!(*last_error.lock().unwrap(), None);
+    }
+
+    #[test]
+    fn worker_reports_errors() {
+        let last_error = Arc::new(Mutex::new(None));
+        let sender = spawn_worker(|| Err(NotifyError::Unsupported), Arc::clone(&last_error));
+        wait_for(|| last_error.lock().unwrap().is_some());
+        // Поток без подключения завершается, и очередь закрывается
+        wait_for(|| sender.send(notification("Работа")).is_err());
+
+        let last_error = Arc::new(Mutex::new(None));
+        let sender = spawn_worker(
+            || Ok(Box::new(Unreachable) as Box<dyn Notifier>),
+            Arc::clone(&last_error),
+        );
+        sender.send(notification("Работа")).unwrap();
+        wait_for(|| last_error.lock().unwrap().is_some());
+        assert_eq!(
+            last_error.lock().unwrap().as_deref(),
+            Some("D-Bus: нет ответа")
+        );
+    }
+}
@@ -0,0 +1,539 @@
+//! Обмен расписаниями: выбранные экраны и интервалы сохраняются в отдельный
+//! файл JSON или TOML, который можно передать коллегам и влить в свою
+//! конфигурацию.
+//!
+//! При импорте занятые id получают новые значения, а ссылки на экраны внутри
+//! `IntervalMode` переписываются под них. Перед слиянием строится
+//! [`ImportPlan`], который показывает, что именно изменится.
+
+use std::{collections::HashMap, fmt, fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::{AppConfig, IntervalMode, ScreenConfig, TimeInterval},
+    validation::{ValidationIssue, validate_profiles},
+};
+
+/// Версия формата файла обмена
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// Набор экранов и интервалов для обмена
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduleBundle {
+    pub bundle_version: u32,
+    #[serde(default)]
+    pub screens: Vec<ScreenConfig>,
+    #[serde(default)]
+    pub intervals: Vec<TimeInterval>,
+}
+
+/// Формат файла, определяется по расширению
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BundleFormat {
+    Json,
+    Toml,
+}
+
+impl BundleFormat {
+    /// `.toml` - TOML, всё остальное - JSON
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("toml") => BundleFormat::Toml,
+            _ => BundleFormat::Json,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum BundleError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    TomlParse(toml::de::Error),
+    TomlWrite(toml::ser::Error),
+    /// Файл записан более новой версией программы
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for BundleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BundleError::Io(err) => write!(f, "ошибка ввода-вывода: {}", err),
+            BundleError::Json(err) => write!(f, "некорректный JSON: {}", err),
+            BundleError::TomlParse(err) => write!(f, "некорректный TOML: {}", err),
+            BundleError::TomlWrite(err) => write!(f, "не удалось записать TOML: {}", err),
+            BundleError::UnsupportedVersion(version) => write!(
+                f,
+                "bundle_version {} записана более новой версией программы (поддерживается до {})",
+                version, BUNDLE_VERSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BundleError {}
+
+impl From<std::io::Error> for BundleError {
+    fn from(err: std::io::Error) -> Self {
+        BundleError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for BundleError {
+    fn from(err: serde_json::Error) -> Self {
+        BundleError::Json(err)
+    }
+}
+
+impl From<toml::de::Error> for BundleError {
+    fn from(err: toml::de::Error) -> Self {
+        BundleError::TomlParse(err)
+    }
+}
+
+impl From<toml::ser::Error> for BundleError {
+    fn from(err: toml::ser::Error) -> Self {
+        BundleError::TomlWrite(err)
+    }
+}
+
+impl ScheduleBundle {
+    /// Собирает набор из выбранных экранов и интервалов активного профиля.
+    /// Экраны, на которые ссылаются выбранные интервалы, попадают в набор
+    /// автоматически, чтобы файл был самодостаточным. Пути к собственным звукам
+    /// экранов не сохраняются: на другом компьютере файлов по ним нет.
+    pub fn export(cfg: &AppConfig, screen_ids: &[u32], interval_ids: &[u32]) -> Self {
+        let intervals: Vec<TimeInterval> = cfg
+            .intervals()
+            .iter()
+            .filter(|i| interval_ids.contains(&i.id))
+            // Метка импорта из календаря имеет смысл только на этом компьютере
+            .map(|i| TimeInterval {
+                source: None,
+                ..i.clone()
+            })
+            .collect();
+        let screens = cfg
+            .screens
+            .iter()
+            .filter(|s| {
+                screen_ids.contains(&s.id)
+                    || intervals
+                        .iter()
+                        .any(|i| screen_refs(&i.mode).contains(&s.id))
+            })
+            .map(|s| ScreenConfig {
+                sound: None,
+                ..s.clone()
+            })
+            .collect();
+        Self {
+            bundle_version: BUNDLE_VERSION,
+            screens,
+            intervals,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.screens.is_empty() && self.intervals.is_empty()
+    }
+
+    pub fn to_text(&self, format: BundleFormat) -> Result<String, BundleError> {
+        Ok(match format {
+            BundleFormat::Json => serde_json::to_string_pretty(self)?,
+            BundleFormat::Toml => toml::to_string_pretty(self)?,
+        })
+    }
+
+    pub fn parse(text: &str, format: BundleFormat) -> Result<Self, BundleError> {
+        let bundle: Self = match format {
+            BundleFormat::Json => serde_json::from_str(text)?,
+            BundleFormat::Toml => toml::from_str(text)?,
+        };
+        if bundle.bundle_version > BUNDLE_VERSION {
+            return Err(BundleError::UnsupportedVersion(bundle.bundle_version));
+        }
+        Ok(bundle)
+    }
+
+    /// Записывает набор в файл; формат выбирается по расширению
+    pub fn write(&self, path: &Path) -> Result<(), BundleError> {
+        let text = self.to_text(BundleFormat::from_path(path))?;
+        fs::write(path, text)?;
+        Ok(())
+    }
+
+    /// Читает набор из файла; формат выбирается по расширению
+    pub fn read(path: &Path) -> Result<Self, BundleError> {
+        let text = fs::read_to_string(path)?;
+        Self::parse(&text, BundleFormat::from_path(path))
+    }
+}
+
+/// Что произойдёт с элементом набора при импорте
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportAction {
+    /// Добавляется с тем же id
+    Add,
+    /// Добавляется под новым id, потому что прежний занят
+    Remap { from: u32 },
+    /// Такой же элемент уже есть, используется он
+    Existing { from: u32 },
+    /// Не импортируется: интервал ссылается на экран, которого нет в наборе.
+    /// Экран получателя с тем же id может оказаться совсем другим.
+    MissingScreen { screen_id: u32 },
+}
+
+/// Строка предпросмотра импорта
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportItem {
+    pub name: String,
+    /// id элемента после слияния
+    pub id: u32,
+    pub action: ImportAction,
+}
+
+/// Предпросмотр слияния набора с конфигурацией
+#[derive(Debug, Clone)]
+pub struct ImportPlan {
+    pub screens: Vec<ImportItem>,
+    pub intervals: Vec<ImportItem>,
+    /// Проблемы проверки, которых не было до слияния (например, пересечения
+    /// с уже существующими интервалами)
+    pub new_issues: Vec<ValidationIssue>,
+    new_screens: Vec<ScreenConfig>,
+    new_intervals: Vec<TimeInterval>,
+}
+
+impl ImportPlan {
+    /// Сопоставляет набор с конфигурацией. Интервалы добавляются в активный профиль.
+    pub fn new(cfg: &AppConfig, bundle: ScheduleBundle) -> Self {
+        let mut next_screen_id = cfg
+            .screens
+            .iter()
+            .map(|s| s.id)
+            .chain(bundle.screens.iter().map(|s| s.id))
+            .max()
+            .unwrap_or(0)
+            + 1;
+        let mut next_interval_id = cfg
+            .all_intervals()
+            .map(|i| i.id)
+            .chain(bundle.intervals.iter().map(|i| i.id))
+            .max()
+            .unwrap_or(0)
+            + 1;
+
+        // Экраны: одинаковый уже есть - берём его, id занят - выдаём новый
+        let mut screen_ids: HashMap<u32, u32> = HashMap::new();
+        let mut screens = Vec::new();
+        let mut new_screens: Vec<ScreenConfig> = Vec::new();
+        for mut screen in bundle.screens {
+            let from = screen.id;
+            if screen_ids.contains_key(&from) {
+                // Повтор id внутри набора: ссылки уже указывают на первый экран
+                continue;
+            }
+            if let Some(existing) = cfg.screens.iter().find(|s| same_screen(s, &screen)) {
+                screen_ids.insert(from, existing.id);
+                screens.push(ImportItem {
+                    name: screen.title,
+                    id: existing.id,
+                    action: ImportAction::Existing { from },
+                });
+                continue;
+            }
+            let action = if cfg.screens.iter().any(|s| s.id == from) {
+                screen.id = next_screen_id;
+                next_screen_id += 1;
+                ImportAction::Remap { from }
+            } else {
+                ImportAction::Add
+            };
+            screen_ids.insert(from, screen.id);
+            screens.push(ImportItem {
+                name: screen.title.clone(),
+                id: screen.id,
+                action,
+            });
+            new_screens.push(screen);
+        }
+
+        // Интервалы: переписываем ссылки на экраны, затем разбираемся с id
+        let mut intervals = Vec::new();
+        let mut new_intervals: Vec<TimeInterval> = Vec::new();
+        for mut interval in bundle.intervals {
+            let from = interval.id;
+            let missing = screen_refs(&interval.mode)
+                .into_iter()
+                .find(|id| !screen_ids.contains_key(id));
+            if let Some(screen_id) = missing {
+                intervals.push(ImportItem {
+                    name: interval.name,
+                    id: from,
+                    action: ImportAction::MissingScreen { screen_id },
+                });
+                continue;
+            }
+            remap_screens(&mut interval.mode, &screen_ids);
+
+            // Метку календаря экспорт не сохраняет, поэтому она не сравнивается
+            if let Some(existing) = cfg.intervals().iter().find(|i| {
+                TimeInterval {
+                    id: i.id,
+                    source: i.source.clone(),
+                    ..interval.clone()
+                } == **i
+            }) {
+                intervals.push(ImportItem {
+                    name: interval.name,
+                    id: existing.id,
+                    action: ImportAction::Existing { from },
+                });
+                continue;
+            }
+            let taken = cfg.all_intervals().any(|i| i.id == from)
+                || new_intervals.iter().any(|i| i.id == from);
+            let action = if taken {
+                interval.id = next_interval_id;
+                next_interval_id += 1;
+                ImportAction::Remap { from }
+            } else {
+                ImportAction::Add
+            };
+            intervals.push(ImportItem {
+                name: interval.name.clone(),
+                id: interval.id,
+                action,
+            });
+            new_intervals.push(interval);
+        }
+
+        let mut plan = Self {
+            screens,
+            intervals,
+            new_issues: Vec::new(),
+            new_screens,
+            new_intervals,
+        };
+
+        let before = validate_profiles(cfg);
+        let mut merged = cfg.clone();
+        plan.clone().apply(&mut merged);
+        plan.new_issues = validate_profiles(&merged)
+            .into_iter()
+            .filter(|issue| !before.contains(issue))
+            .collect();
+        plan
+    }
+
+    /// Нечего добавлять: всё уже есть или ничего нельзя импортировать
+    pub fn is_empty(&self) -> bool {
+        self.new_screens.is_empty() && self.new_intervals.is_empty()
+    }
+
+    /// Вливает набор в конфигурацию
+    pub fn apply(self, cfg: &mut AppConfig) {
+        cfg.screens.extend(self.new_screens);
+        cfg.active_profile_mut()
+            .intervals
+            .extend(self.new_intervals);
+    }
+}
+
+/// Экраны совпадают по содержимому (id и собственный звук не важны:
+/// звук в набор не попадает)
+fn same_screen(a: &ScreenConfig, b: &ScreenConfig) -> bool {
+    ScreenConfig {
+        id: a.id,
+        sound: a.sound.clone(),
+        ..b.clone()
+    } == *a
+}
+
+/// id экранов, на которые ссылается интервал
+fn screen_refs(mode: &IntervalMode) -> Vec<u32> {
+    match mode {
+        IntervalMode::Static { screen_id } => vec![*screen_id],
+        IntervalMode::Cycle { steps } => steps.iter().map(|s| s.screen_id).collect(),
+    }
+}
+
+/// Переписывает ссылки на экраны набора под их id после слияния
+fn remap_screens(mode: &mut IntervalMode, screen_ids: &HashMap<u32, u32>) {
+    let remap = |id: &mut u32| {
+        if let Some(new_id) = screen_ids.get(id) {
+            *id = *new_id;
+        }
+    };
+    match mode {
+        IntervalMode::Static { screen_id } => remap(screen_id),
+        IntervalMode::Cycle { steps } => steps.iter_mut().for_each(|s| remap(&mut s.screen_id)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{CycleStep, Recurrence, Rgba8, TimeOfDay};
+
+    fn screen(id: u32, title: &str) -> ScreenConfig {
+        ScreenConfig {
+            id,
+            title: title.into(),
+            subtitle: String::new(),
+            color: Rgba8 {
+                r: 10,
+                g: 20,
+                b: 30,
+                a: 255,
+            },
+            sound: None,
+            notify: true,
+        }
+    }
+
+    fn interval(id: u32, start: u8, end: u8, mode: IntervalMode) -> TimeInterval {
+        TimeInterval {
+            id,
+            name: format!("Интервал {}", id),
+            start: TimeOfDay {
+                hour: start,
+                minute: 0,
+            },
+            end: TimeOfDay {
+                hour: end,
+                minute: 0,
+            },
+            mode,
+            recurrence: Recurrence::Daily,
+            warning_minutes: None,
+            source: None,
+        }
+    }
+
+    fn bundle(screens: Vec<ScreenConfig>, intervals: Vec<TimeInterval>) -> ScheduleBundle {
+        ScheduleBundle {
+            bundle_version: BUNDLE_VERSION,
+            screens,
+            intervals,
+        }
+    }
+
+    fn actions(items: &[ImportItem]) -> Vec<(u32, ImportAction)> {
+        items.iter().map(|item| (item.id, item.action)).collect()
+    }
+
+    #[test]
+    fn export_keeps_only_portable_data() {
+        let mut cfg = AppConfig::default();
+        cfg.screens[0].sound = Some("/home/user/bell.ogg".into());
+        cfg.profiles[0].intervals[1].source = Some("/home/user/work.ics".into());
+
+        // Помодоро ссылается на экраны 1 и 2
+        let exported = ScheduleBundle::export(&cfg, &[], &[2]);
+        assert_eq!(
+            exported.screens.iter().map(|s| s.id).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        assert!(exported.screens.iter().all(|s| s.sound.is_none()));
+        assert_eq!(exported.intervals.len(), 1);
+        assert_eq!(exported.intervals[0].source, None);
+
+        let text = exported.to_text(BundleFormat::Toml).unwrap();
+        assert_eq!(
+            ScheduleBundle::parse(&text, BundleFormat::Toml).unwrap(),
+            exported
+        );
+
+        // У себя те же экраны и интервал узнаются, хотя звука в файле нет
+        let plan = ImportPlan::new(&cfg, exported);
+        assert_eq!(
+            actions(&plan.screens),
+            vec![
+                (1, ImportAction::Existing { from: 1 }),
+                (2, ImportAction::Existing { from: 2 })
+            ]
+        );
+        assert!(plan.is_empty());
+    }
+
+    #[test]
+    fn busy_ids_are_remapped_with_references() {
+        let mut cfg = AppConfig::default();
+        let steps = vec![
+            CycleStep {
+                screen_id: 1,
+                duration_minutes: 50,
+            },
+            CycleStep {
+                screen_id: 7,
+                duration_minutes: 10,
+            },
+        ];
+        let plan = ImportPlan::new(
+            &cfg,
+            bundle(
+                vec![screen(1, "Чтение"), screen(7, "Разминка")],
+                vec![interval(1, 20, 22, IntervalMode::Cycle { steps })],
+            ),
+        );
+
+        assert_eq!(
+            actions(&plan.screens),
+            vec![(8, ImportAction::Remap { from: 1 }), (7, ImportAction::Add)]
+        );
+        assert_eq!(
+            actions(&plan.intervals),
+            vec![(3, ImportAction::Remap { from: 1 })]
+        );
+        assert!(plan.new_issues.is_empty());
+
+        plan.apply(&mut cfg);
+        let imported = cfg.intervals().iter().find(|i| i.id == 3).unwrap();
+        let IntervalMode::Cycle { steps } = &imported.mode else {
+            panic!("ожидался цикл");
+        };
+        assert_eq!(
+            steps.iter().map(|s| s.screen_id).collect::<Vec<_>>(),
+            vec![8, 7]
+        );
+    }
+
+    #[test]
+    fn screens_outside_bundle_are_not_guessed() {
+        let cfg = AppConfig::default();
+        // Экран 2 у получателя есть, но в файле его нет: это может быть другой экран
+        let plan = ImportPlan::new(
+            &cfg,
+            bundle(
+                Vec::new(),
+                vec![interval(5, 20, 21, IntervalMode::Static { screen_id: 2 })],
+            ),
+        );
+        assert_eq!(
+            actions(&plan.intervals),
+            vec![(5, ImportAction::MissingScreen { screen_id: 2 })]
+        );
+        assert!(plan.is_empty());
+    }
+
+    #[test]
+    fn new_overlaps_are_reported() {
+        let cfg = AppConfig::default();
+        let plan = ImportPlan::new(
+            &cfg,
+            bundle(
+                vec![screen(4, "Почта")],
+                vec![interval(4, 11, 13, IntervalMode::Static { screen_id: 4 })],
+            ),
+        );
+        assert_eq!(
+            plan.new_issues
+                .iter()
+                .map(|issue| (issue.code(), issue.interval_ids()))
+                .collect::<Vec<_>>(),
+            vec![("overlap", vec![1, 4])]
+        );
+    }
+}
@@ -0,0 +1,337 @@
+use std::{fmt, path::PathBuf};
+
+use crate::{
+    config::{Chime, ScreenConfig, SoundSettings},
+    timer::TransitionKind,
+};
+
+/// Что нужно проиграть
+#[derive(Debug, Clone, PartialEq)]
+pub enum Sound {
+    /// Встроенный синтезированный сигнал
+    Chime(Chime),
+    /// Звуковой файл WAV или OGG
+    File(PathBuf),
+}
+
+#[derive(Debug)]
+pub enum SoundError {
+    /// Аудиоустройство недоступно
+    Device(String),
+    /// Не удалось открыть звуковой файл
+    Io(std::io::Error),
+    /// Файл не удалось декодировать
+    Decode(String),
+}
+
+impl fmt::Display for SoundError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SoundError::Device(msg) => write!(f, "аудиоустройство недоступно: {}", msg),
+            SoundError::Io(err) => write!(f, "не удалось открыть звуковой файл: {}", err),
+            SoundError::Decode(msg) => write!(f, "не удалось декодировать звук: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SoundError {}
+
+impl From<std::io::Error> for SoundError {
+    fn from(err: std::io::Error) -> Self {
+        SoundError::Io(err)
+    }
+}
+
+/// Способ воспроизведения звука
+pub trait AudioBackend {
+    /// Запускает воспроизведение, не дожидаясь его окончания
+    fn play(&mut self, sound: &Sound, volume: f32) -> Result<(), SoundError>;
+
+    /// Есть ли реальный вывод звука
+    fn is_audible(&self) -> bool;
+}
+
+/// Backend без вывода звука: используется, когда аудиоустройства нет
+/// (или сборка без feature `sound`). Запоминает, что должно было прозвучать.
+#[derive(Debug, Default)]
+pub struct NullBackend {
+    pub played: Vec<(Sound, f32)>,
+}
+
+impl AudioBackend for NullBackend {
+    fn play(&mut self, sound: &Sound, volume: f32) -> Result<(), SoundError> {
+        self.played.push((sound.clone(), volume));
+        Ok(())
+    }
+
+    fn is_audible(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(feature = "sound")]
+mod rodio_backend {
+    use std::{fs::File, io::BufReader, time::Duration};
+
+    use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink, Source, source::SineWave};
+
+    use super::{AudioBackend, Sound, SoundError};
+    use crate::config::Chime;
+
+    /// Ноты встроенных сигналов: частота в Гц и длительность в мс
+    fn chime_notes(chime: Chime) -> &'static [(f32, u64)] {
+        match chime {
+            Chime::Soft => &[(660.0, 250)],
+            Chime::Bell => &[(880.0, 180), (660.0, 320)],
+            Chime::Rising => &[(523.25, 140), (659.25, 140), (783.99, 260)],
+        }
+    }
+
+    /// Воспроизведение через системное аудиоустройство
+    pub struct RodioBackend {
+        // Поток должен жить, пока играет звук
+        _stream: OutputStream,
+        handle: OutputStreamHandle,
+    }
+
+    impl RodioBackend {
+        pub fn open() -> Result<Self, SoundError> {
+            let (stream, handle) =
+                OutputStream::try_default().map_err(|e| SoundError::Device(e.to_string()))?;
+            Ok(Self {
+                _stream: stream,
+                handle,
+            })
+        }
+    }
+
+    impl AudioBackend for RodioBackend {
+        fn play(&mut self, sound: &Sound, volume: f32) -> Result<(), SoundError> {
+            let sink =
+                Sink::try_new(&self.handle).map_err(|e| SoundError::Device(e.to_string()))?;
+            sink.set_volume(volume);
+
+            match sound {
+                Sound::Chime(chime) => {
+                    for &(freq, millis) in chime_notes(*chime) {
+                        sink.append(
+                            SineWave::new(freq)
+                                .take_duration(Duration::from_millis(millis))
+                                .fade_in(Duration::from_millis(10))
+                                .amplify(0.3),
+                        );
+                    }
+                }
+                Sound::File(path) => {
+                    let file = BufReader::new(File::open(path)?);
+                    let source =
+                        Decoder::new(file).map_err(|e| SoundError::Decode(e.to_string()))?;
+                    sink.append(source);
+                }
+            }
+
+            // Звук доиграет в фоне после удаления sink
+            sink.detach();
+            Ok(())
+        }
+
+        fn is_audible(&self) -> bool {
+            true
+        }
+    }
+}
+
+/// Открывает системное аудиоустройство, а если его нет - пустой backend
+pub fn open_backend() -> Box<dyn AudioBackend> {
+    #[cfg(feature = "sound")]
+    if let Ok(backend) = rodio_backend::RodioBackend::open() {
+        return Box::new(backend);
+    }
+    Box::new(NullBackend::default())
+}
+
+/// Проигрывает звуки переходов согласно настройкам
+pub struct SoundPlayer {
+    /// Устройство открывается при первом воспроизведении
+    backend: Option<Box<dyn AudioBackend>>,
+    /// Последняя ошибка воспроизведения (показывается в настройках)
+    pub last_error: Option<String>,
+}
+
+impl SoundPlayer {
+    pub fn new() -> Self {
+        Self {
+            backend: None,
+            last_error: None,
+        }
+    }
+
+    /// Выбирает звук перехода: звук экрана, затем звук типа перехода, затем встроенный сигнал
+    pub fn sound_for_transition(
+        settings: &SoundSettings,
+        screen: Option<&ScreenConfig>,
+        kind: TransitionKind,
+    ) -> Sound {
+        let transition_sound = match kind {
+            TransitionKind::IntervalStart => &settings.interval_start,
+            TransitionKind::IntervalEnd => &settings.interval_end,
+            TransitionKind::Step => &settings.step,
+        };
+
+        screen
+            .and_then(|s| s.sound.clone())
+            .or_else(|| transition_sound.clone())
+            .map(Sound::File)
+            .unwrap_or(Sound::Chime(settings.chime))
+    }
+
+    /// Проигрывает звук перехода. Если собственный файл не проигрывается,
+    /// звучит встроенный сигнал, а ошибка сохраняется в `last_error`.
+    pub fn play_transition(
+        &mut self,
+        settings: &SoundSettings,
+        screen: Option<&ScreenConfig>,
+        kind: TransitionKind,
+    ) {
+        let sound = Self::sound_for_transition(settings, screen, kind);
+        if let Err(err) = self.play(&sound, settings.volume)
+            && matches!(sound, Sound::File(_))
+        {
+            let _ = self.play(&Sound::Chime(settings.chime), settings.volume);
+            // Успешный сигнал не должен стирать ошибку файла
+            self.last_error = Some(err.to_string());
+        }
+    }
+
+    /// Проигрывает звук с указанной громкостью
+    pub fn play(&mut self, sound: &Sound, volume: f32) -> Result<(), SoundError> {
+        let backend = self.backend.get_or_insert_with(open_backend);
+        let result = backend.play(sound, volume.clamp(0.0, 1.0));
+        match &result {
+            Ok(()) => self.last_error = None,
+            Err(err) => self.last_error = Some(err.to_string()),
+        }
+        result
+    }
+
+    /// Есть ли реальный вывод звука (false - устройство не найдено)
+    pub fn is_audible(&mut self) -> bool {
+        self.backend.get_or_insert_with(open_backend).is_audible()
+    }
+}
+
+impl Default for SoundPlayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, io, rc::Rc};
+
+    use super::*;
+    use crate::config::Rgba8;
+
+    /// Backend, который не может открыть ни один файл
+    struct NoFiles(Rc<RefCell<NullBackend>>);
+
+    impl AudioBackend for NoFiles {
+        fn play(&mut self, sound: &Sound, volume: f32) -> Result<(), SoundError> {
+            match sound {
+                Sound::File(_) => Err(io::Error::from(io::ErrorKind::NotFound).into()),
+                Sound::Chime(_) => self.0.borrow_mut().play(sound, volume),
+            }
+        }
+
+        fn is_audible(&self) -> bool {
+            false
+        }
+    }
+
+    fn player(backend: impl AudioBackend + 'static) -> SoundPlayer {
+        SoundPlayer {
+            backend: Some(Box::new(backend)),
+            last_error: None,
+        }
+    }
+
+    fn screen(sound: Option<&str>) -> ScreenConfig {
+        ScreenConfig {
+            id: 1,
+            title: "Работа".into(),
+            subtitle: String::new(),
+            color: Rgba8 {
+                r: 0,
+                g: 0,
+                b: 0,
+                a: 255,
+            },
+            sound: sound.map(PathBuf::from),
+            notify: true,
+        }
+    }
+
+    #[test]
+    fn screen_sound_wins_over_transition_sound() {
+        let settings = SoundSettings {
+            step: Some("step.ogg".into()),
+            ..SoundSettings::default()
+        };
+        let sound = |screen: Option<&ScreenConfig>, kind| {
+            SoundPlayer::sound_for_transition(&settings, screen, kind)
+        };
+
+        assert_eq!(
+            sound(Some(&screen(Some("screen.wav"))), TransitionKind::Step),
+            Sound::File("screen.wav".into())
+        );
+        assert_eq!(
+            sound(Some(&screen(None)), TransitionKind::Step),
+            Sound::File("step.ogg".into())
+        );
+        assert_eq!(
+            sound(None, TransitionKind::IntervalEnd),
+            Sound::Chime(settings.chime)
+        );
+    }
+
+    #[test]
+    fn volume_is_clamped() {
+        let backend = NoFiles(Rc::new(RefCell::new(NullBackend::default())));
+        let played = backend.0.clone();
+        let mut player = player(backend);
+
+        player.play(&Sound::Chime(Chime::Bell), 1.5).unwrap();
+        player.play(&Sound::Chime(Chime::Soft), -0.5).unwrap();
+        assert_eq!(
+            played.borrow().played,
+            vec![
+                (Sound::Chime(Chime::Bell), 1.0),
+                (Sound::Chime(Chime::Soft), 0.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn broken_file_falls_back_to_chime_and_keeps_error() {
+        let settings = SoundSettings {
+            interval_start: Some("missing.ogg".into()),
+            ..SoundSettings::default()
+        };
+        let backend = NoFiles(Rc::new(RefCell::new(NullBackend::default())));
+        let played = backend.0.clone();
+        let mut player = player(backend);
+
+        player.play_transition(&settings, None, TransitionKind::IntervalStart);
+        assert_eq!(
+            played.borrow().played,
+            vec![(Sound::Chime(settings.chime), settings.volume)]
+        );
+        assert!(player.last_error.is_some());
+
+        // Следующий успешный звук ошибку сбрасывает
+        player.play_transition(&settings, None, TransitionKind::Step);
+        assert!(player.last_error.is_none());
+    }
+}
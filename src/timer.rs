@@ -1,17 +1,190 @@
-use chrono::{DateTime, Duration, Local, Timelike};
+use chrono::{DateTime, Duration, Local, NaiveDate, Timelike};
+use fluent_bundle::{FluentArgs, FluentBundle, FluentResource};
+use serde::{Deserialize, Serialize};
 use std::time::SystemTime;
 
-use crate::config::{AppConfig, IntervalMode, Rgba8, TimeInterval};
+use crate::{
+    config::{AppConfig, CycleStep, IntervalMode, MINUTES_PER_DAY, PauseMode, Rgba8, TimeInterval},
+    history::{CycleStepRef, HistoryEntry},
+    utils::{tr, tr_with_args},
+};
 
-#[derive(Debug, Clone)]
+/// Почему показывается экран. Подписи к нему собираются из ключей
+/// `locales/*.ftl` при выводе.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ScreenKind {
+    /// Вне интервалов: экран по умолчанию до начала следующего
+    Waiting,
+    /// Статичный режим интервала
+    Static,
+    /// Шаг циклического режима
+    Cycle { step: CycleStepRef },
+    /// Экран статичного интервала не найден, вместо него показан первый экран
+    MissingScreen { screen_id: u32 },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ActiveScreenInfo {
+    /// Заголовок показанного экрана, как он задан в настройках
     pub title: String,
+    /// Подзаголовок показанного экрана, как он задан в настройках
     pub subtitle: String,
     pub color: Rgba8,
     pub remaining_seconds: u64,
-    pub interval_name: String,
+    /// Название интервала; `None` вне интервалов
+    pub interval_name: Option<String>,
     pub screen_id: u32,
-    pub is_default_screen: bool,
+    pub kind: ScreenKind,
+}
+
+impl ActiveScreenInfo {
+    pub fn is_default_screen(&self) -> bool {
+        self.kind == ScreenKind::Waiting
+    }
+
+    /// Заголовок для показа; для ненайденного экрана - предупреждение с его id
+    pub fn display_title(&self, bundle: &FluentBundle<FluentResource>) -> String {
+        match self.kind {
+            ScreenKind::MissingScreen { screen_id } => {
+                let mut args = FluentArgs::new();
+                args.set("id", screen_id);
+                tr_with_args(bundle, "screen-not-found", Some(&args))
+            }
+            _ => self.title.clone(),
+        }
+    }
+
+    /// Подзаголовок для показа: подзаголовок экрана и сведения о режиме
+    pub fn display_subtitle(&self, bundle: &FluentBundle<FluentResource>) -> String {
+        let (info, separator) = match self.kind {
+            ScreenKind::Waiting => return self.subtitle.clone(),
+            ScreenKind::MissingScreen { .. } => return tr(bundle, "default-fallback"),
+            ScreenKind::Static => (tr(bundle, "static-mode-suffix"), " "),
+            ScreenKind::Cycle { step } => {
+                let mut args = FluentArgs::new();
+                args.set("current", step.index + 1);
+                args.set("total", step.count);
+                (tr_with_args(bundle, "cycle-step-info", Some(&args)), " — ")
+            }
+        };
+        if self.subtitle.is_empty() {
+            info
+        } else {
+            format!("{}{}{}", self.subtitle, separator, info)
+        }
+    }
+
+    /// Название интервала для показа; вне интервалов - подпись `default-waiting`
+    pub fn interval_label(&self, bundle: &FluentBundle<FluentResource>) -> String {
+        match &self.interval_name {
+            Some(name) => name.clone(),
+            None => tr(bundle, "default-waiting"),
+        }
+    }
+}
+
+/// Конкретное вхождение того, что сейчас отсчитывает таймер
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Occurrence {
+    /// Вхождение интервала расписания: id интервала и дата его начала
+    Interval { id: u32, date: NaiveDate },
+    /// Разовая сессия, запущенная в указанный момент
+    Adhoc { started_at: DateTime<Local> },
+}
+
+/// Сдвиг расписания одного вхождения интервала (накапливается за время пауз)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScheduleShift {
+    /// Вхождение, к которому относится сдвиг
+    pub occurrence: Option<Occurrence>,
+    /// На сколько секунд позже идут шаги цикла
+    pub cycle_offset_secs: i64,
+    /// На сколько секунд продлен конец интервала
+    pub end_extension_secs: i64,
+}
+
+impl ScheduleShift {
+    /// Учитывает время, проведенное на паузе
+    pub fn add_paused(&mut self, paused_secs: i64, mode: PauseMode) {
+        self.cycle_offset_secs += paused_secs;
+        if mode == PauseMode::ExtendInterval {
+            self.end_extension_secs += paused_secs;
+        }
+    }
+}
+
+/// Тип перехода между экранами
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransitionKind {
+    /// Начался интервал или разовая сессия
+    IntervalStart,
+    /// Интервал закончился, показывается экран по умолчанию
+    IntervalEnd,
+    /// Смена шага внутри цикла
+    Step,
+}
+
+/// Предстоящий переход между экранами
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpcomingTransition {
+    /// Момент перехода
+    pub at: DateTime<Local>,
+    pub kind: TransitionKind,
+    /// Экран, который будет показан после перехода
+    pub next_screen_id: u32,
+    pub next_title: String,
+}
+
+/// События, которые планировщик накапливает при обновлениях.
+/// Интерфейс забирает их через [`TimerScheduler::take_events`] и сам решает,
+/// как на них реагировать (звук, уведомление, подсветка).
+#[derive(Debug, Clone, PartialEq)]
+pub enum TimerEvent {
+    /// Экран сменился
+    Transition {
+        kind: TransitionKind,
+        screen_id: u32,
+    },
+    /// До перехода осталось не больше заданного времени предупреждения
+    Warning(UpcomingTransition),
+    /// Закончился отрезок показа экрана; интерфейс дописывает его в журнал
+    Finished(HistoryEntry),
+}
+
+/// Разовая сессия, запущенная вручную поверх расписания.
+/// Пока она идет, расписание не учитывается; после окончания таймер возвращается к нему.
+#[derive(Debug, Clone)]
+pub struct AdhocSession {
+    /// Название сессии (показывается вместо названия интервала)
+    pub name: String,
+    pub mode: IntervalMode,
+    /// Полная длительность сессии в секундах
+    pub duration_secs: i64,
+    pub started_at: DateTime<Local>,
+}
+
+impl AdhocSession {
+    /// Сессия с одним экраном на `minutes` минут
+    pub fn single(name: String, screen_id: u32, minutes: u32, now: DateTime<Local>) -> Self {
+        Self {
+            name,
+            mode: IntervalMode::Static { screen_id },
+            duration_secs: minutes as i64 * 60,
+            started_at: now,
+        }
+    }
+
+    /// Сессия, повторяющая последовательность шагов `repeats` раз
+    pub fn cycle(name: String, steps: Vec<CycleStep>, repeats: u32, now: DateTime<Local>) -> Self {
+        let cycle_minutes: u32 = steps.iter().map(|s| s.duration_minutes).sum();
+        Self {
+            name,
+            mode: IntervalMode::Cycle { steps },
+            duration_secs: cycle_minutes as i64 * 60 * repeats as i64,
+            started_at: now,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -20,6 +193,16 @@ pub struct TimerState {
     pub next_transition: Option<DateTime<Local>>,
     pub is_running: bool,
     pub last_update: SystemTime,
+    /// Момент постановки на паузу
+    pub paused_at: Option<DateTime<Local>>,
+    /// Накопленный сдвиг расписания текущего вхождения интервала
+    pub shift: ScheduleShift,
+    /// Разовая сессия, временно заменяющая расписание
+    pub adhoc: Option<AdhocSession>,
+    /// Действующее предупреждение о приближающемся переходе
+    pub warning: Option<UpcomingTransition>,
+    /// События, ещё не забранные интерфейсом
+    pub events: Vec<TimerEvent>,
 }
 
 impl Default for TimerState {
@@ -29,19 +212,50 @@ impl Default for TimerState {
             next_transition: None,
             is_running: true,
             last_update: SystemTime::now(),
+            paused_at: None,
+            shift: ScheduleShift::default(),
+            adhoc: None,
+            warning: None,
+            events: Vec::new(),
         }
     }
 }
 
+/// Перерыв между обновлениями, после которого отрезок считается оборванным
+/// (компьютер спал или программа зависла)
+const SLEEP_GAP_SECS: i64 = 5 * 60;
+
+/// Что именно показывается: отрезок заканчивается, когда это меняется
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SegmentKey {
+    occurrence: Option<Occurrence>,
+    /// Индекс шага и номер круга цикла
+    step: Option<(usize, i64)>,
+    screen_id: u32,
+}
+
+/// Отрезок показа экрана, который ещё идёт
+#[derive(Debug, Clone)]
+struct OpenSegment {
+    key: SegmentKey,
+    entry: HistoryEntry,
+    /// С какого момента этот отрезок стоит на паузе
+    paused_since: Option<DateTime<Local>>,
+    /// Последнее обновление планировщика внутри отрезка
+    last_seen: DateTime<Local>,
+}
+
 /// Основной планировщик таймера
 pub struct TimerScheduler {
     pub state: TimerState,
+    segment: Option<OpenSegment>,
 }
 
 impl TimerScheduler {
     pub fn new() -> Self {
         Self {
             state: TimerState::default(),
+            segment: None,
         }
     }
 
@@ -50,81 +264,476 @@ impl TimerScheduler {
         let now = Local::now();
         let prev_screen_id = self.state.current_screen.as_ref().map(|s| s.screen_id);
 
-        self.state.current_screen = determine_active_screen(config, now);
+        // Сдвиг относится к одному вхождению интервала: при смене интервала он сбрасывается,
+        // а пауза продолжается уже для нового вхождения
+        let mut shift = self.effective_shift(config, now);
+        let adhoc = self.state.adhoc.as_ref();
+        let active_occurrence =
+            find_active_interval(config, now, &shift, adhoc).map(|a| a.occurrence);
+
+        // Закончившаяся разовая сессия уступает место расписанию
+        if self.state.adhoc.is_some()
+            && !matches!(active_occurrence, Some(Occurrence::Adhoc { .. }))
+        {
+            self.state.adhoc = None;
+        }
+
+        let occurrence_changed = active_occurrence != self.state.shift.occurrence;
+        if occurrence_changed {
+            self.anchor_shift(active_occurrence, now);
+            shift = self.effective_shift(config, now);
+        }
+
+        let adhoc = self.state.adhoc.as_ref();
+        self.state.current_screen = determine_active_screen(config, now, &shift, adhoc);
         self.state.last_update = SystemTime::now();
 
         // Определяем, изменился ли экран
         let current_screen_id = self.state.current_screen.as_ref().map(|s| s.screen_id);
         let screen_changed = prev_screen_id != current_screen_id;
 
+        // Первое обновление после запуска переходом не считается
+        if screen_changed
+            && prev_screen_id.is_some()
+            && let Some(screen_id) = current_screen_id
+        {
+            let kind = transition_kind(occurrence_changed, active_occurrence);
+            self.state
+                .events
+                .push(TimerEvent::Transition { kind, screen_id });
+        }
+        if screen_changed {
+            self.state.warning = None;
+        }
+
         // Вычисляем время следующего перехода
-        self.state.next_transition = calculate_next_transition(config, now);
+        self.state.next_transition = calculate_next_transition(config, now, &shift, adhoc);
+        self.update_warning(config, now, &shift);
+        self.track_segment(config, now, &shift);
 
         screen_changed
     }
 
-    /// Возвращает true, если таймер должен быть обновлен
-    pub fn should_update(&self) -> bool {
-        self.state.is_running
-            && self
-                .state
-                .last_update
-                .elapsed()
-                .unwrap_or(std::time::Duration::from_secs(0))
-                >= std::time::Duration::from_secs(1)
+    /// Забирает накопленные события
+    pub fn take_events(&mut self) -> Vec<TimerEvent> {
+        std::mem::take(&mut self.state.events)
+    }
+
+    /// Выставляет предупреждение, когда до следующего перехода осталось меньше
+    /// времени предупреждения. Событие выдается один раз; пока предупреждение действует,
+    /// момент перехода обновляется (например, во время паузы).
+    fn update_warning(&mut self, config: &AppConfig, now: DateTime<Local>, shift: &ScheduleShift) {
+        let adhoc = self.state.adhoc.as_ref();
+        let upcoming = self.state.next_transition.and_then(|at| {
+            let lead_secs = warning_lead_minutes(config, now, shift, adhoc)? as i64 * 60;
+            if (at - now).num_seconds() > lead_secs {
+                return None;
+            }
+            upcoming_transition(config, now, at, shift, adhoc)
+        });
+
+        match (upcoming, &mut self.state.warning) {
+            (Some(upcoming), Some(warning)) => *warning = upcoming,
+            (Some(upcoming), None) => {
+                self.state
+                    .events
+                    .push(TimerEvent::Warning(upcoming.clone()));
+                self.state.warning = Some(upcoming);
+            }
+            // Переход отодвинулся (продление шага) - предупреждение снимается
+            (None, _) => self.state.warning = None,
+        }
+    }
+
+    /// Закрывает отрезок показа экрана, когда сменилось вхождение, шаг или экран,
+    /// и открывает следующий. Закрытый отрезок выдается событием `Finished`.
+    fn track_segment(&mut self, config: &AppConfig, now: DateTime<Local>, shift: &ScheduleShift) {
+        let Some((screen_id, screen_title)) = self
+            .state
+            .current_screen
+            .as_ref()
+            .map(|s| (s.screen_id, s.title.clone()))
+        else {
+            return;
+        };
+        let active = find_active_interval(config, now, shift, self.state.adhoc.as_ref());
+        let step = active.and_then(|active| {
+            let IntervalMode::Cycle { steps } = active.mode else {
+                return None;
+            };
+            let position = active.elapsed_secs - active.cycle_offset_secs;
+            let (index, _) = cycle_step_at(steps, position)?;
+            let total: i64 = steps.iter().map(|s| s.duration_minutes as i64 * 60).sum();
+            let step = CycleStepRef {
+                index,
+                count: steps.len(),
+            };
+            Some((step, position.div_euclid(total)))
+        });
+        let key = SegmentKey {
+            occurrence: active.map(|a| a.occurrence),
+            step: step.map(|(step, round)| (step.index, round)),
+            screen_id,
+        };
+        let interval_name = active.map(|a| a.name.to_string());
+
+        if let Some(segment) = &mut self.segment {
+            if (now - segment.last_seen).num_seconds() > SLEEP_GAP_SECS {
+                let end = segment.last_seen;
+                self.close_segment(end, true);
+            } else if segment.key == key {
+                segment.last_seen = now;
+                return;
+            } else {
+                self.close_segment(now, false);
+            }
+        }
+
+        let interval_id = match key.occurrence {
+            Some(Occurrence::Interval { id, .. }) => Some(id),
+            _ => None,
+        };
+        self.segment = Some(OpenSegment {
+            key,
+            entry: HistoryEntry {
+                screen_id,
+                screen_title,
+                interval_id,
+                interval_name,
+                step: step.map(|(step, _)| step),
+                start: now,
+                end: now,
+                paused_secs: 0,
+                skipped: false,
+                interrupted: false,
+            },
+            paused_since: self.state.paused_at.map(|_| now),
+            last_seen: now,
+        });
+    }
+
+    /// Закрывает текущий отрезок в момент `end` и выдает его событием
+    fn close_segment(&mut self, end: DateTime<Local>, interrupted: bool) {
+        if let Some(entry) = self.take_segment(end, interrupted) {
+            self.state.events.push(TimerEvent::Finished(entry));
+        }
+    }
+
+    fn take_segment(&mut self, end: DateTime<Local>, interrupted: bool) -> Option<HistoryEntry> {
+        let OpenSegment {
+            mut entry,
+            paused_since,
+            ..
+        } = self.segment.take()?;
+        if let Some(since) = paused_since {
+            entry.paused_secs += (end - since).num_seconds().max(0) as u64;
+        }
+        entry.end = end;
+        entry.interrupted |= interrupted;
+        (entry.end > entry.start).then_some(entry)
+    }
+
+    /// Закрывает текущий отрезок при выходе из программы
+    pub fn finish(&mut self) -> Option<HistoryEntry> {
+        self.take_segment(Local::now(), true)
+    }
+
+    /// Отмечает, что текущий отрезок прерван вручную
+    fn mark_skipped(&mut self) {
+        if let Some(segment) = &mut self.segment {
+            segment.entry.skipped = true;
+        }
+    }
+
+    /// Запускает разовую сессию поверх расписания
+    pub fn start_adhoc(&mut self, session: AdhocSession) {
+        self.state.adhoc = Some(session);
+    }
+
+    /// Досрочно завершает разовую сессию и возвращает таймер к расписанию
+    pub fn stop_adhoc(&mut self) {
+        if self.state.adhoc.take().is_some() {
+            self.mark_skipped();
+        }
+    }
+
+    /// Привязывает сдвиг к новому вхождению интервала, сбрасывая накопленное
+    fn anchor_shift(&mut self, occurrence: Option<Occurrence>, now: DateTime<Local>) {
+        self.state.shift = ScheduleShift {
+            occurrence,
+            ..ScheduleShift::default()
+        };
+        if self.state.paused_at.is_some() {
+            self.state.paused_at = Some(now);
+        }
+    }
+
+    /// Сдвиг расписания с учетом текущей (еще не завершенной) паузы
+    fn effective_shift(&self, config: &AppConfig, now: DateTime<Local>) -> ScheduleShift {
+        let mut shift = self.state.shift;
+        if let Some(paused_at) = self.state.paused_at {
+            shift.add_paused(
+                (now - paused_at).num_seconds(),
+                config.system_settings.pause_mode,
+            );
+        }
+        shift
     }
 
     /// Приостанавливает или возобновляет таймер
-    pub fn toggle_pause(&mut self) {
-        self.state.is_running = !self.state.is_running;
+    pub fn toggle_pause(&mut self, config: &AppConfig) {
+        if self.state.is_running {
+            self.pause();
+        } else {
+            self.resume(config);
+        }
+    }
+
+    /// Ставит таймер на паузу: отсчет текущего шага замирает до возобновления
+    pub fn pause(&mut self) {
+        self.pause_at(Local::now());
+    }
+
+    fn pause_at(&mut self, now: DateTime<Local>) {
+        if self.state.is_running {
+            self.state.is_running = false;
+            self.state.paused_at = Some(now);
+            if let Some(segment) = &mut self.segment {
+                segment.paused_since = Some(now);
+            }
+        }
+    }
+
+    /// Возобновляет таймер: время паузы сдвигает оставшиеся шаги интервала
+    pub fn resume(&mut self, config: &AppConfig) {
+        self.resume_at(config, Local::now());
+    }
+
+    fn resume_at(&mut self, config: &AppConfig, now: DateTime<Local>) {
+        if let Some(paused_at) = self.state.paused_at.take() {
+            let paused_secs = (now - paused_at).num_seconds();
+            self.state
+                .shift
+                .add_paused(paused_secs, config.system_settings.pause_mode);
+        }
+        if let Some(segment) = &mut self.segment
+            && let Some(since) = segment.paused_since.take()
+        {
+            segment.entry.paused_secs += (now - since).num_seconds().max(0) as u64;
+        }
+        self.state.is_running = true;
+    }
+
+    /// Находится ли таймер сейчас в циклическом интервале (доступны пропуск и продление шага)
+    pub fn in_cycle(&self, config: &AppConfig) -> bool {
+        let now = Local::now();
+        let shift = self.effective_shift(config, now);
+        find_active_interval(config, now, &shift, self.state.adhoc.as_ref())
+            .is_some_and(|active| matches!(active.mode, IntervalMode::Cycle { .. }))
+    }
+
+    /// Текущий шаг активного циклического интервала: позиция в цикле (секунд),
+    /// секунд до конца шага и до конца интервала
+    fn current_cycle_step(
+        &mut self,
+        config: &AppConfig,
+        now: DateTime<Local>,
+    ) -> Option<(i64, i64, i64)> {
+        let shift = self.effective_shift(config, now);
+        let active = find_active_interval(config, now, &shift, self.state.adhoc.as_ref())?;
+        let IntervalMode::Cycle { steps } = active.mode else {
+            return None;
+        };
+
+        let position = active.elapsed_secs - active.cycle_offset_secs;
+        let (_, remaining_in_step) = cycle_step_at(steps, position)?;
+        let remaining_to_end = active.remaining_secs();
+        let occurrence = active.occurrence;
+
+        // Интервал мог начаться после последнего обновления: привязываем сдвиг к нему.
+        // Сдвиг другого вхождения к нему не применялся, так что посчитанные значения верны.
+        if self.state.shift.occurrence != Some(occurrence) {
+            self.anchor_shift(Some(occurrence), now);
+        }
+
+        Some((position, remaining_in_step, remaining_to_end))
+    }
+
+    /// Досрочно завершает текущий шаг цикла: следующий шаг начинается сейчас,
+    /// остальные идут за ним в прежнем порядке. Изменение видно после следующего `update`.
+    pub fn skip_step(&mut self, config: &AppConfig) -> bool {
+        self.skip_step_at(config, Local::now())
+    }
+
+    fn skip_step_at(&mut self, config: &AppConfig, now: DateTime<Local>) -> bool {
+        let Some((_, remaining_in_step, _)) = self.current_cycle_step(config, now) else {
+            return false;
+        };
+        self.state.shift.cycle_offset_secs -= remaining_in_step;
+        self.mark_skipped();
+        true
+    }
+
+    /// Продлевает текущий шаг цикла на `minutes` минут, сдвигая оставшиеся шаги.
+    /// Если продленный шаг выходит за конец интервала, интервал продлевается до конца шага.
+    pub fn extend_step(&mut self, config: &AppConfig, minutes: u32) -> bool {
+        self.extend_step_at(config, minutes, Local::now())
     }
 
-    /// Принудительно обновляет таймер
-    pub fn force_update(&mut self, config: &AppConfig) -> bool {
-        self.update(config)
+    fn extend_step_at(&mut self, config: &AppConfig, minutes: u32, now: DateTime<Local>) -> bool {
+        let Some((_, remaining_in_step, remaining_to_end)) = self.current_cycle_step(config, now)
+        else {
+            return false;
+        };
+        let extra_secs = minutes as i64 * 60;
+        self.state.shift.cycle_offset_secs += extra_secs;
+
+        let overflow = remaining_in_step + extra_secs - remaining_to_end;
+        if overflow > 0 {
+            self.state.shift.end_extension_secs += overflow;
+        }
+        true
+    }
+
+    /// Начинает цикл заново с первого шага с текущего момента
+    pub fn restart_cycle(&mut self, config: &AppConfig) -> bool {
+        self.restart_cycle_at(config, Local::now())
+    }
+
+    fn restart_cycle_at(&mut self, config: &AppConfig, now: DateTime<Local>) -> bool {
+        let Some((position, _, _)) = self.current_cycle_step(config, now) else {
+            return false;
+        };
+        self.state.shift.cycle_offset_secs += position;
+        self.mark_skipped();
+        true
+    }
+}
+
+/// Вхождение интервала (или разовая сессия), активное в заданный момент
+#[derive(Debug, Clone, Copy)]
+pub struct ActiveInterval<'a> {
+    pub name: &'a str,
+    pub mode: &'a IntervalMode,
+    pub occurrence: Occurrence,
+    /// Сколько секунд прошло с начала вхождения
+    pub elapsed_secs: i64,
+    /// Длительность вхождения без учета продления, секунд
+    pub duration_secs: i64,
+    /// На сколько секунд позже идут шаги цикла
+    pub cycle_offset_secs: i64,
+    /// На сколько секунд продлен конец интервала
+    pub end_extension_secs: i64,
+}
+
+impl ActiveInterval<'_> {
+    /// Секунд до конца вхождения с учетом продления
+    pub fn remaining_secs(&self) -> i64 {
+        self.duration_secs + self.end_extension_secs - self.elapsed_secs
     }
 }
 
+/// Находит вхождение интервала, активное в момент `now`.
+///
+/// Разовая сессия перекрывает расписание, пока не закончится. Затем первым проверяется
+/// вхождение, к которому относится сдвиг: продленный паузой интервал остается активным
+/// и после своего обычного конца.
+pub fn find_active_interval<'a>(
+    cfg: &'a AppConfig,
+    now: DateTime<Local>,
+    shift: &ScheduleShift,
+    adhoc: Option<&'a AdhocSession>,
+) -> Option<ActiveInterval<'a>> {
+    // Сдвиг применяется только к тому вхождению, к которому он привязан
+    let with_shift = |mut active: ActiveInterval<'a>| {
+        if shift.occurrence == Some(active.occurrence) {
+            active.cycle_offset_secs = shift.cycle_offset_secs;
+            active.end_extension_secs = shift.end_extension_secs;
+        }
+        active
+    };
+
+    if let Some(session) = adhoc {
+        let active = with_shift(ActiveInterval {
+            name: &session.name,
+            mode: &session.mode,
+            occurrence: Occurrence::Adhoc {
+                started_at: session.started_at,
+            },
+            elapsed_secs: (now - session.started_at).num_seconds(),
+            duration_secs: session.duration_secs,
+            cycle_offset_secs: 0,
+            end_extension_secs: 0,
+        });
+        if active.remaining_secs() > 0 {
+            return Some(active);
+        }
+    }
+
+    let today = now.date_naive();
+    let secs_of_day = now.num_seconds_from_midnight() as i64;
+
+    if let Some(Occurrence::Interval { id, date }) = shift.occurrence
+        && let Some(interval) = cfg.intervals().iter().find(|i| i.id == id)
+    {
+        let elapsed_secs = (today - date).num_days() * 86400 + secs_of_day
+            - interval.start.to_minutes() as i64 * 60;
+        let active = with_shift(ActiveInterval {
+            name: &interval.name,
+            mode: &interval.mode,
+            occurrence: Occurrence::Interval { id, date },
+            elapsed_secs,
+            duration_secs: interval.duration_minutes() as i64 * 60,
+            cycle_offset_secs: 0,
+            end_extension_secs: 0,
+        });
+        if elapsed_secs >= 0 && active.remaining_secs() > 0 {
+            return Some(active);
+        }
+    }
+
+    let now_min = now.hour() * 60 + now.minute();
+    cfg.intervals().iter().find_map(|interval| {
+        let (date, into_interval) = interval.active_occurrence(today, now_min)?;
+        Some(with_shift(ActiveInterval {
+            name: &interval.name,
+            mode: &interval.mode,
+            occurrence: Occurrence::Interval {
+                id: interval.id,
+                date,
+            },
+            elapsed_secs: into_interval as i64 * 60 + now.second() as i64,
+            duration_secs: interval.duration_minutes() as i64 * 60,
+            cycle_offset_secs: 0,
+            end_extension_secs: 0,
+        }))
+    })
+}
+
 pub fn determine_active_screen(
     cfg: &AppConfig,
     now: chrono::DateTime<Local>,
+    shift: &ScheduleShift,
+    adhoc: Option<&AdhocSession>,
 ) -> Option<ActiveScreenInfo> {
-    let now_min = (now.hour() as u32) * 60 + (now.minute() as u32);
-
-    // Найдем активный интервал
-    for interval in &cfg.intervals {
-        let start_min = interval.start.to_minutes();
-        let end_min = interval.end.to_minutes();
-
-        if start_min <= now_min && now_min < end_min {
-            // Этот интервал активен
-            return determine_screen_in_interval(cfg, interval, now_min, now.second());
-        }
+    // Найдем активный интервал (или разовую сессию)
+    if let Some(active) = find_active_interval(cfg, now, shift, adhoc) {
+        return determine_screen_in_interval(cfg, &active);
     }
 
     // Вне всех интервалов - показываем экран по умолчанию
     let screen = cfg
-        .default_screen_id
+        .default_screen_id()
         .and_then(|id| cfg.screens.iter().find(|s| s.id == id))
         .or_else(|| cfg.screens.first())?;
 
-    // Найдем ближайший будущий интервал
-    let mut next_start: Option<u32> = None;
-    for interval in &cfg.intervals {
-        let start = interval.start.to_minutes();
-        if start > now_min {
-            next_start = Some(match next_start {
-                Some(ns) => ns.min(start),
-                None => start,
-            });
-        }
-    }
-
-    let remaining_seconds = if let Some(ns) = next_start {
-        ((ns - now_min) as u64) * 60 + (60 - now.second() as u64 % 60)
+    // Найдем ближайший будущий интервал (возможно, уже в следующих сутках)
+    let now_min = now.hour() * 60 + now.minute();
+    let remaining_seconds = if let Some((minutes, _)) = find_next_start(cfg, now) {
+        (minutes as u64 - 1) * 60 + (60 - now.second() as u64)
     } else {
         // до конца дня
-        ((24 * 60 - now_min) as u64) * 60 + (60 - now.second() as u64 % 60)
+        ((MINUTES_PER_DAY - now_min) as u64 - 1) * 60 + (60 - now.second() as u64)
     };
 
     Some(ActiveScreenInfo {
@@ -132,213 +741,211 @@ pub fn determine_active_screen(
         subtitle: screen.subtitle.clone(),
         color: screen.color,
         remaining_seconds,
-        interval_name: "Ожидание".to_string(),
+        interval_name: None,
         screen_id: screen.id,
-        is_default_screen: true,
+        kind: ScreenKind::Waiting,
     })
 }
 
+/// Находит ближайшее начало интервала: возвращает число минут до него и сам интервал
+fn find_next_start(cfg: &AppConfig, now: DateTime<Local>) -> Option<(u32, &TimeInterval)> {
+    let now_min = now.hour() * 60 + now.minute();
+    let today = now.date_naive();
+
+    cfg.intervals()
+        .iter()
+        .filter_map(|interval| {
+            let start = interval.start.to_minutes();
+            // Сегодняшнее начало подходит, только если оно еще впереди
+            let from = if start > now_min {
+                today
+            } else {
+                today.succ_opt()?
+            };
+            let date = interval.recurrence.next_occurrence(from)?;
+            let days = (date - today).num_days() as u32;
+            Some((days * MINUTES_PER_DAY + start - now_min, interval))
+        })
+        .min_by_key(|(minutes, _)| *minutes)
+}
+
+/// Тип перехода по тому, сменилось ли вхождение интервала
+fn transition_kind(occurrence_changed: bool, occurrence: Option<Occurrence>) -> TransitionKind {
+    match (occurrence_changed, occurrence) {
+        (false, _) => TransitionKind::Step,
+        (true, Some(_)) => TransitionKind::IntervalStart,
+        (true, None) => TransitionKind::IntervalEnd,
+    }
+}
+
+/// Время предупреждения для ближайшего перехода: настройка текущего интервала,
+/// а вне интервалов - того, который начнется следующим; иначе глобальная.
+/// `None`, если предупреждения выключены.
+fn warning_lead_minutes(
+    cfg: &AppConfig,
+    now: DateTime<Local>,
+    shift: &ScheduleShift,
+    adhoc: Option<&AdhocSession>,
+) -> Option<u32> {
+    let settings = &cfg.system_settings.warnings;
+    if !settings.enabled {
+        return None;
+    }
+
+    let interval_lead = match find_active_interval(cfg, now, shift, adhoc).map(|a| a.occurrence) {
+        Some(Occurrence::Interval { id, .. }) => cfg
+            .intervals()
+            .iter()
+            .find(|i| i.id == id)
+            .and_then(|i| i.warning_minutes),
+        Some(Occurrence::Adhoc { .. }) => None,
+        None => find_next_start(cfg, now).and_then(|(_, interval)| interval.warning_minutes),
+    };
+
+    let lead = interval_lead.unwrap_or(settings.lead_minutes);
+    (lead > 0).then_some(lead)
+}
+
+/// Описывает переход, который произойдет в момент `at`
+pub fn upcoming_transition(
+    cfg: &AppConfig,
+    now: DateTime<Local>,
+    at: DateTime<Local>,
+    shift: &ScheduleShift,
+    adhoc: Option<&AdhocSession>,
+) -> Option<UpcomingTransition> {
+    let occurrence_now = find_active_interval(cfg, now, shift, adhoc).map(|a| a.occurrence);
+    let occurrence_then = find_active_interval(cfg, at, shift, adhoc).map(|a| a.occurrence);
+    let next_screen = determine_active_screen(cfg, at, shift, adhoc)?;
+
+    Some(UpcomingTransition {
+        at,
+        kind: transition_kind(occurrence_now != occurrence_then, occurrence_then),
+        next_screen_id: next_screen.screen_id,
+        next_title: next_screen.title,
+    })
+}
+
+/// Находит шаг цикла по позиции в цикле (в секундах, с учетом сдвига):
+/// возвращает индекс шага и сколько секунд до его окончания
+fn cycle_step_at(steps: &[CycleStep], position_secs: i64) -> Option<(usize, i64)> {
+    let total_cycle: i64 = steps.iter().map(|s| s.duration_minutes as i64 * 60).sum();
+    if total_cycle == 0 {
+        return None;
+    }
+
+    let pos_in_cycle = position_secs.rem_euclid(total_cycle);
+    let mut acc = 0;
+    for (step_idx, step) in steps.iter().enumerate() {
+        let next_acc = acc + step.duration_minutes as i64 * 60;
+        if pos_in_cycle < next_acc {
+            return Some((step_idx, next_acc - pos_in_cycle));
+        }
+        acc = next_acc;
+    }
+    None
+}
+
 fn determine_screen_in_interval(
     cfg: &AppConfig,
-    interval: &TimeInterval,
-    now_min: u32,
-    now_sec: u32,
+    active: &ActiveInterval<'_>,
 ) -> Option<ActiveScreenInfo> {
-    let start_min = interval.start.to_minutes();
-    let end_min = interval.end.to_minutes();
+    let remaining_to_interval_end = active.remaining_secs().max(0);
 
-    match &interval.mode {
+    match active.mode {
         IntervalMode::Static { screen_id } => {
             // Статичный режим - показываем один экран весь интервал
-            if let Some(screen) = cfg.screens.iter().find(|s| s.id == *screen_id) {
-                let remaining_min = end_min.saturating_sub(now_min);
-                // Улучшенный расчет оставшегося времени с учетом секунд
-                let seconds = if remaining_min > 0 {
-                    (remaining_min - 1) as u64 * 60 + (60 - now_sec as u64)
-                } else {
-                    60 - now_sec as u64
-                };
+            let seconds = remaining_to_interval_end as u64;
 
+            if let Some(screen) = cfg.screens.iter().find(|s| s.id == *screen_id) {
                 Some(ActiveScreenInfo {
                     title: screen.title.clone(),
-                    subtitle: format!("{} (статичный режим)", screen.subtitle),
+                    subtitle: screen.subtitle.clone(),
                     color: screen.color,
                     remaining_seconds: seconds,
-                    interval_name: interval.name.clone(),
+                    interval_name: Some(active.name.to_string()),
                     screen_id: screen.id,
-                    is_default_screen: false,
+                    kind: ScreenKind::Static,
                 })
             } else {
                 // Если экран не найден, показываем экран по умолчанию
                 let default_screen = cfg.screens.first()?;
-                let remaining_min = end_min.saturating_sub(now_min);
-                let seconds = if remaining_min > 0 {
-                    (remaining_min - 1) as u64 * 60 + (60 - now_sec as u64)
-                } else {
-                    60 - now_sec as u64
-                };
 
                 Some(ActiveScreenInfo {
-                    title: format!("⚠ Экран не найден (ID: {})", screen_id),
-                    subtitle: "Используется экран по умолчанию".to_string(),
+                    title: default_screen.title.clone(),
+                    subtitle: default_screen.subtitle.clone(),
                     color: default_screen.color,
                     remaining_seconds: seconds,
-                    interval_name: interval.name.clone(),
+                    interval_name: Some(active.name.to_string()),
                     screen_id: default_screen.id,
-                    is_default_screen: false,
+                    kind: ScreenKind::MissingScreen {
+                        screen_id: *screen_id,
+                    },
                 })
             }
         }
         IntervalMode::Cycle { steps } => {
-            // Циклический режим - переключаем экраны по шагам
-            if steps.is_empty() {
-                return None;
-            }
+            // Циклический режим - переключаем экраны по шагам; пауза сдвигает позицию в цикле
+            let position = active.elapsed_secs - active.cycle_offset_secs;
+            let (step_idx, remaining_in_step) = cycle_step_at(steps, position)?;
+            let step = &steps[step_idx];
+            let screen = cfg.screens.iter().find(|s| s.id == step.screen_id)?;
 
-            let into_interval = now_min - start_min;
-            let total_cycle: u32 = steps.iter().map(|s| s.duration_minutes).sum();
+            // Не выходим за границу интервала
+            let seconds = remaining_in_step.min(remaining_to_interval_end) as u64;
 
-            if total_cycle == 0 {
-                return None;
-            }
-
-            let pos_in_cycle = into_interval % total_cycle;
-            let mut acc = 0;
-
-            for step in steps {
-                let next_acc = acc + step.duration_minutes;
-                if pos_in_cycle < next_acc {
-                    if let Some(screen) = cfg.screens.iter().find(|s| s.id == step.screen_id) {
-                        let remaining_in_step = next_acc - pos_in_cycle;
-                        // Не выходим за границу интервала
-                        let remaining_to_interval_end = end_min - now_min;
-                        let remaining_minutes = remaining_in_step.min(remaining_to_interval_end);
-
-                        // Улучшенный расчет времени с учетом секунд
-                        let seconds = if remaining_minutes > 0 {
-                            (remaining_minutes - 1) as u64 * 60 + (60 - now_sec as u64)
-                        } else {
-                            60 - now_sec as u64
-                        };
-
-                        // Показываем информацию о шаге в подзаголовке
-                        let step_info = format!(
-                            "Шаг {}/{} (цикл)",
-                            steps
-                                .iter()
-                                .position(|s| s.screen_id == step.screen_id)
-                                .unwrap_or(0)
-                                + 1,
-                            steps.len()
-                        );
-                        let subtitle = if screen.subtitle.is_empty() {
-                            step_info
-                        } else {
-                            format!("{} — {}", screen.subtitle, step_info)
-                        };
-
-                        return Some(ActiveScreenInfo {
-                            title: screen.title.clone(),
-                            subtitle,
-                            color: screen.color,
-                            remaining_seconds: seconds,
-                            interval_name: interval.name.clone(),
-                            screen_id: screen.id,
-                            is_default_screen: false,
-                        });
-                    }
-                    break;
-                }
-                acc = next_acc;
-            }
-            None
+            Some(ActiveScreenInfo {
+                title: screen.title.clone(),
+                subtitle: screen.subtitle.clone(),
+                color: screen.color,
+                remaining_seconds: seconds,
+                interval_name: Some(active.name.to_string()),
+                screen_id: screen.id,
+                kind: ScreenKind::Cycle {
+                    step: CycleStepRef {
+                        index: step_idx,
+                        count: steps.len(),
+                    },
+                },
+            })
         }
     }
 }
 
+/// Начало текущей минуты: переходы вне интервалов происходят на границах минут
+fn start_of_minute(now: DateTime<Local>) -> Option<DateTime<Local>> {
+    now.with_second(0)?.with_nanosecond(0)
+}
+
 /// Вычисляет время следующего перехода между экранами
-pub fn calculate_next_transition(cfg: &AppConfig, now: DateTime<Local>) -> Option<DateTime<Local>> {
-    let now_min = (now.hour() as u32) * 60 + (now.minute() as u32);
-
-    // Проверяем, находимся ли мы в активном интервале
-    for interval in &cfg.intervals {
-        let start_min = interval.start.to_minutes();
-        let end_min = interval.end.to_minutes();
-
-        if start_min <= now_min && now_min < end_min {
-            // Мы в активном интервале
-            match &interval.mode {
-                IntervalMode::Static { .. } => {
-                    // В статичном режиме следующий переход - конец интервала
-                    let end_time = now.date_naive().and_hms_opt(
-                        (interval.end.hour) as u32,
-                        interval.end.minute as u32,
-                        0,
-                    )?;
-                    return Some(end_time.and_local_timezone(Local).single()?);
-                }
-                IntervalMode::Cycle { steps } => {
-                    // В циклическом режиме находим следующий переход
-                    if steps.is_empty() {
-                        return None;
-                    }
-
-                    let into_interval = now_min - start_min;
-                    let total_cycle: u32 = steps.iter().map(|s| s.duration_minutes).sum();
-
-                    if total_cycle == 0 {
-                        return None;
-                    }
-
-                    let pos_in_cycle = into_interval % total_cycle;
-                    let mut acc = 0;
-
-                    for step in steps {
-                        let next_acc = acc + step.duration_minutes;
-                        if pos_in_cycle < next_acc {
-                            // Следующий переход - конец текущего шага
-                            let minutes_to_next = next_acc - pos_in_cycle;
-                            let next_time = now + Duration::minutes(minutes_to_next as i64);
-
-                            // Но не позже конца интервала
-                            let end_time = now.date_naive().and_hms_opt(
-                                interval.end.hour as u32,
-                                interval.end.minute as u32,
-                                0,
-                            )?;
-                            let end_datetime = end_time.and_local_timezone(Local).single()?;
-
-                            return Some(next_time.min(end_datetime));
-                        }
-                        acc = next_acc;
-                    }
-                }
+pub fn calculate_next_transition(
+    cfg: &AppConfig,
+    now: DateTime<Local>,
+    shift: &ScheduleShift,
+    adhoc: Option<&AdhocSession>,
+) -> Option<DateTime<Local>> {
+    // Проверяем, находимся ли мы в активном интервале (или разовой сессии)
+    if let Some(active) = find_active_interval(cfg, now, shift, adhoc) {
+        // Конец интервала может прийтись на следующие сутки
+        let remaining_to_interval_end = active.remaining_secs().max(0);
+        let second_start = now.with_nanosecond(0)?;
+
+        let secs_to_next = match active.mode {
+            // В статичном режиме следующий переход - конец интервала
+            IntervalMode::Static { .. } => remaining_to_interval_end,
+            // В циклическом режиме - конец текущего шага, но не позже конца интервала
+            IntervalMode::Cycle { steps } => {
+                let position = active.elapsed_secs - active.cycle_offset_secs;
+                let (_, remaining_in_step) = cycle_step_at(steps, position)?;
+                remaining_in_step.min(remaining_to_interval_end)
             }
-        }
+        };
+        return Some(second_start + Duration::seconds(secs_to_next));
     }
 
     // Мы вне всех интервалов - следующий переход это начало ближайшего интервала
-    let mut next_start: Option<(u32, &TimeInterval)> = None;
-    for interval in &cfg.intervals {
-        let start = interval.start.to_minutes();
-        if start > now_min {
-            next_start = Some(match next_start {
-                Some((ns, _)) if ns < start => next_start.unwrap(),
-                _ => (start, interval),
-            });
-        }
-    }
-
-    if let Some((_, interval)) = next_start {
-        let start_time = now.date_naive().and_hms_opt(
-            interval.start.hour as u32,
-            interval.start.minute as u32,
-            0,
-        )?;
-        return Some(start_time.and_local_timezone(Local).single()?);
-    }
-
-    None
+    let (minutes, _) = find_next_start(cfg, now)?;
+    Some(start_of_minute(now)? + Duration::minutes(minutes as i64))
 }
 
 pub fn format_duration_hhmmss(total_secs: u64) -> String {
@@ -352,118 +959,690 @@ pub fn format_duration_hhmmss(total_secs: u64) -> String {
     }
 }
 
-/// Форматирует время до следующего перехода
-pub fn format_time_until_transition(next_transition: Option<DateTime<Local>>) -> String {
-    if let Some(next) = next_transition {
-        let now = Local::now();
-        if next > now {
-            let duration = next - now;
-            let total_seconds = duration.num_seconds() as u64;
-            return format_duration_hhmmss(total_seconds);
+/// Переход в дневном расписании
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DailyTransition {
+    /// Время в минутах от полуночи
+    pub minute: u32,
+    pub kind: TransitionKind,
+    pub interval_name: String,
+    /// Шаг цикла, который начинается; только для `TransitionKind::Step`
+    pub step: Option<CycleStepRef>,
+}
+
+impl DailyTransition {
+    /// Описание перехода для показа
+    pub fn description(&self, bundle: &FluentBundle<FluentResource>) -> String {
+        let mut args = FluentArgs::new();
+        let key = match (self.kind, self.step) {
+            (TransitionKind::Step, Some(step)) => {
+                args.set("step", step.index + 1);
+                args.set("total", step.count);
+                args.set("interval", self.interval_name.as_str());
+                "transition-step"
+            }
+            (TransitionKind::IntervalEnd, _) => {
+                args.set("name", self.interval_name.as_str());
+                "transition-end"
+            }
+            _ => {
+                args.set("name", self.interval_name.as_str());
+                "transition-start"
+            }
+        };
+        tr_with_args(bundle, key, Some(&args))
+    }
+}
+
+/// Получает список всех переходов в течение указанной даты.
+///
+/// Время указано в минутах от полуночи. Переходы интервалов, идущих через полночь,
+/// попадают в начало суток (хвост вчерашнего вхождения).
+pub fn get_daily_transitions(cfg: &AppConfig, date: NaiveDate) -> Vec<DailyTransition> {
+    let mut transitions = Vec::new();
+    let yesterday = date.pred_opt();
+
+    for interval in cfg.intervals() {
+        let occurs_today = interval.recurrence.occurs_on(date);
+        let occurred_yesterday = yesterday.is_some_and(|d| interval.recurrence.occurs_on(d));
+
+        for mut transition in interval_transitions(interval) {
+            if transition.minute < MINUTES_PER_DAY {
+                if occurs_today {
+                    transitions.push(transition);
+                }
+            } else if occurred_yesterday {
+                transition.minute -= MINUTES_PER_DAY;
+                transitions.push(transition);
+            }
         }
     }
-    "—".to_string()
+
+    // Сортируем по времени
+    transitions.sort_by_key(|t| t.minute);
+    transitions
 }
 
-/// Валидирует интервалы на предмет пересечений и корректности
-pub fn validate_intervals(intervals: &[TimeInterval]) -> Vec<String> {
-    let mut errors = Vec::new();
+/// Переходы одного вхождения интервала; время в минутах от полуночи дня начала
+/// (для интервалов через полночь может превышать сутки)
+fn interval_transitions(interval: &TimeInterval) -> Vec<DailyTransition> {
+    let start_min = interval.start.to_minutes();
+    let transition = |minute, kind, step| DailyTransition {
+        minute,
+        kind,
+        interval_name: interval.name.clone(),
+        step,
+    };
 
-    // Проверяем каждый интервал на корректность
-    for (idx, interval) in intervals.iter().enumerate() {
-        let start_min = interval.start.to_minutes();
-        let end_min = interval.end.to_minutes();
+    // Добавляем начало и конец интервала
+    let mut transitions = vec![
+        transition(start_min, TransitionKind::IntervalStart, None),
+        transition(
+            start_min + interval.duration_minutes(),
+            TransitionKind::IntervalEnd,
+            None,
+        ),
+    ];
 
-        // Проверяем, что начало раньше конца
-        if start_min >= end_min {
-            errors.push(format!(
-                "Интервал '{}': время начала ({:02}:{:02}) должно быть раньше времени окончания ({:02}:{:02})",
-                interval.name,
-                interval.start.hour, interval.start.minute,
-                interval.end.hour, interval.end.minute
+    // Для циклических режимов добавляем переходы между шагами до конца интервала
+    if let IntervalMode::Cycle { steps } = &interval.mode {
+        // Первый шаг начинается вместе с интервалом
+        for (offset, _, step_idx) in cycle_step_blocks(interval).into_iter().skip(1) {
+            let step = CycleStepRef {
+                index: step_idx,
+                count: steps.len(),
+            };
+            transitions.push(transition(
+                start_min + offset,
+                TransitionKind::Step,
+                Some(step),
             ));
         }
+    }
 
-        // Проверяем пересечения с другими интервалами
-        for (other_idx, other_interval) in intervals.iter().enumerate() {
-            if idx != other_idx {
-                let other_start = other_interval.start.to_minutes();
-                let other_end = other_interval.end.to_minutes();
+    transitions
+}
 
-                // Проверяем пересечение
-                if start_min < other_end && end_min > other_start {
-                    errors.push(format!(
-                        "Интервалы '{}' и '{}' пересекаются по времени",
-                        interval.name, other_interval.name
-                    ));
-                }
-            }
-        }
+/// Шаги цикла в одном вхождении интервала: смещение от начала интервала в минутах,
+/// длительность и индекс шага. Цикл повторяется до конца интервала, последний шаг
+/// обрезается. Для статичного режима список пуст.
+pub fn cycle_step_blocks(interval: &TimeInterval) -> Vec<(u32, u32, usize)> {
+    let IntervalMode::Cycle { steps } = &interval.mode else {
+        return Vec::new();
+    };
+    let total_cycle: u32 = steps.iter().map(|s| s.duration_minutes).sum();
+    if total_cycle == 0 {
+        return Vec::new();
+    }
 
-        // Проверяем корректность режимов
-        match &interval.mode {
-            IntervalMode::Cycle { steps } => {
-                if steps.is_empty() {
-                    errors.push(format!(
-                        "Интервал '{}': циклический режим должен содержать хотя бы один шаг",
-                        interval.name
-                    ));
-                } else {
-                    let total_duration: u32 = steps.iter().map(|s| s.duration_minutes).sum();
-                    if total_duration == 0 {
-                        errors.push(format!(
-                            "Интервал '{}': общая длительность шагов не может быть нулевой",
-                            interval.name
-                        ));
-                    }
-                }
+    let duration = interval.duration_minutes();
+    let mut blocks = Vec::new();
+    let mut acc_minutes = 0;
+    'cycles: loop {
+        for (step_idx, step) in steps.iter().enumerate() {
+            if acc_minutes >= duration {
+                break 'cycles;
             }
-            IntervalMode::Static { .. } => {
-                // Для статичного режима дополнительных проверок пока не требуется
+            let length = step.duration_minutes.min(duration - acc_minutes);
+            if length > 0 {
+                blocks.push((acc_minutes, length, step_idx));
             }
+            acc_minutes += step.duration_minutes;
         }
     }
-
-    errors
+    blocks
 }
 
-/// Получает список всех переходов в течение дня
-pub fn get_daily_transitions(cfg: &AppConfig) -> Vec<(u32, String, String)> {
-    let mut transitions = Vec::new();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{CycleStep, PauseMode, Recurrence, TimeOfDay};
+    use chrono::{NaiveDateTime, TimeZone};
+
+    fn at(value: &str) -> DateTime<Local> {
+        let naive = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").unwrap();
+        Local.from_local_datetime(&naive).unwrap()
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    fn interval(id: u32, start: (u8, u8), end: (u8, u8), mode: IntervalMode) -> TimeInterval {
+        TimeInterval {
+            id,
+            name: format!("Интервал {}", id),
+            start: TimeOfDay {
+                hour: start.0,
+                minute: start.1,
+            },
+            end: TimeOfDay {
+                hour: end.0,
+                minute: end.1,
+            },
+            mode,
+            recurrence: Recurrence::Daily,
+            warning_minutes: None,
+            source: None,
+        }
+    }
+
+    fn config(intervals: Vec<TimeInterval>) -> AppConfig {
+        let mut cfg = AppConfig::default();
+        cfg.profiles[0].intervals = intervals;
+        cfg
+    }
+
+    fn night() -> TimeInterval {
+        interval(1, (22, 0), (2, 0), IntervalMode::Static { screen_id: 2 })
+    }
 
-    // Добавляем начала и концы интервалов
-    for interval in &cfg.intervals {
-        transitions.push((
-            interval.start.to_minutes(),
-            format!("Начало: {}", interval.name),
-            "start".to_string(),
-        ));
-
-        transitions.push((
-            interval.end.to_minutes(),
-            format!("Конец: {}", interval.name),
-            "end".to_string(),
-        ));
-
-        // Для циклических режимов добавляем переходы между шагами
-        if let IntervalMode::Cycle { steps } = &interval.mode {
-            let mut acc_minutes = 0;
-            for (step_idx, step) in steps.iter().enumerate() {
-                acc_minutes += step.duration_minutes;
-                let transition_time = interval.start.to_minutes() + acc_minutes;
-
-                if transition_time < interval.end.to_minutes() {
-                    transitions.push((
-                        transition_time,
-                        format!("Шаг {}/{} в '{}'", step_idx + 2, steps.len(), interval.name),
-                        "step".to_string(),
-                    ));
+    /// 09:00-11:00, цикл 25 минут экрана 1 и 5 минут экрана 2
+    fn pomodoro() -> TimeInterval {
+        interval(
+            1,
+            (9, 0),
+            (11, 0),
+            IntervalMode::Cycle {
+                steps: vec![
+                    CycleStep {
+                        screen_id: 1,
+                        duration_minutes: 25,
+                    },
+                    CycleStep {
+                        screen_id: 2,
+                        duration_minutes: 5,
+                    },
+                ],
+            },
+        )
+    }
+
+    fn pomodoro_occurrence() -> Option<Occurrence> {
+        Some(Occurrence::Interval {
+            id: 1,
+            date: date("2026-06-10"),
+        })
+    }
+
+    /// Шаг цикла и секунды до конца шага и до конца интервала в момент `now`
+    fn cycle_state(
+        cfg: &AppConfig,
+        now: &str,
+        shift: &ScheduleShift,
+    ) -> (Option<CycleStepRef>, u64, i64) {
+        let now = at(now);
+        let info = determine_active_screen(cfg, now, shift, None).unwrap();
+        let step = match info.kind {
+            ScreenKind::Cycle { step } => Some(step),
+            _ => None,
+        };
+        let remaining_to_end =
+            find_active_interval(cfg, now, shift, None).map_or(0, |active| active.remaining_secs());
+        (step, info.remaining_seconds, remaining_to_end)
+    }
+
+    fn step(index: usize) -> Option<CycleStepRef> {
+        Some(CycleStepRef { index, count: 2 })
+    }
+
+    #[test]
+    fn paused_time_shifts_steps_or_interval_end() {
+        let mut shift = ScheduleShift::default();
+        shift.add_paused(120, PauseMode::ShiftSteps);
+        shift.add_paused(60, PauseMode::ShiftSteps);
+        assert_eq!(
+            (shift.cycle_offset_secs, shift.end_extension_secs),
+            (180, 0)
+        );
+
+        let mut shift = ScheduleShift::default();
+        shift.add_paused(180, PauseMode::ExtendInterval);
+        assert_eq!(
+            (shift.cycle_offset_secs, shift.end_extension_secs),
+            (180, 180)
+        );
+    }
+
+    #[test]
+    fn pause_delays_cycle_steps() {
+        let mut cfg = config(vec![pomodoro()]);
+        cfg.system_settings.pause_mode = PauseMode::ShiftSteps;
+        let mut scheduler = TimerScheduler::new();
+        scheduler.anchor_shift(pomodoro_occurrence(), at("2026-06-10 09:00:00"));
+
+        // Пауза 10 минут посреди первого шага
+        scheduler.pause_at(at("2026-06-10 09:10:00"));
+        scheduler.resume_at(&cfg, at("2026-06-10 09:20:00"));
+        let shift = scheduler.state.shift;
+        assert_eq!(shift.cycle_offset_secs, 600);
+
+        // Первый шаг теперь кончается в 09:35, конец интервала прежний
+        assert_eq!(
+            cycle_state(&cfg, "2026-06-10 09:30:00", &shift),
+            (step(0), 300, 5400)
+        );
+        assert_eq!(
+            cycle_state(&cfg, "2026-06-10 09:35:00", &shift),
+            (step(1), 300, 5100)
+        );
+        // Последний шаг обрезается концом интервала
+        assert_eq!(
+            cycle_state(&cfg, "2026-06-10 10:55:00", &shift),
+            (step(0), 300, 300)
+        );
+        assert!(
+            determine_active_screen(&cfg, at("2026-06-10 11:00:00"), &shift, None)
+                .unwrap()
+                .is_default_screen()
+        );
+    }
+
+    #[test]
+    fn pause_extends_interval() {
+        let mut cfg = config(vec![pomodoro()]);
+        cfg.system_settings.pause_mode = PauseMode::ExtendInterval;
+        let mut scheduler = TimerScheduler::new();
+        scheduler.anchor_shift(pomodoro_occurrence(), at("2026-06-10 09:00:00"));
+
+        scheduler.pause_at(at("2026-06-10 10:50:00"));
+        // Пока пауза идет, сдвиг растет вместе с ней
+        let shift = scheduler.effective_shift(&cfg, at("2026-06-10 10:55:00"));
+        assert_eq!(
+            (shift.cycle_offset_secs, shift.end_extension_secs),
+            (300, 300)
+        );
+
+        scheduler.resume_at(&cfg, at("2026-06-10 11:00:00"));
+        let shift = scheduler.state.shift;
+        assert_eq!(
+            (shift.cycle_offset_secs, shift.end_extension_secs),
+            (600, 600)
+        );
+
+        // Интервал идет до 11:10, хотя по расписанию кончился в 11:00
+        assert_eq!(
+            cycle_state(&cfg, "2026-06-10 11:05:00", &shift),
+            (step(1), 300, 300)
+        );
+        assert_eq!(
+            calculate_next_transition(&cfg, at("2026-06-10 11:05:00"), &shift, None),
+            Some(at("2026-06-10 11:10:00"))
+        );
+        assert!(
+            determine_active_screen(&cfg, at("2026-06-10 11:10:00"), &shift, None)
+                .unwrap()
+                .is_default_screen()
+        );
+    }
+
+    #[test]
+    fn shift_of_another_occurrence_is_ignored() {
+        let cfg = config(vec![pomodoro()]);
+        let shift = ScheduleShift {
+            occurrence: Some(Occurrence::Interval {
+                id: 1,
+                date: date("2026-06-09"),
+            }),
+            cycle_offset_secs: 600,
+            end_extension_secs: 600,
+        };
+        assert_eq!(
+            cycle_state(&cfg, "2026-06-10 09:27:00", &shift),
+            (step(1), 180, 5580)
+        );
+    }
+
+    #[test]
+    fn cycle_step_by_position() {
+        let steps = match pomodoro().mode {
+            IntervalMode::Cycle { steps } => steps,
+            _ => unreachable!(),
+        };
+        assert_eq!(cycle_step_at(&steps, 0), Some((0, 1500)));
+        assert_eq!(cycle_step_at(&steps, 1499), Some((0, 1)));
+        assert_eq!(cycle_step_at(&steps, 1500), Some((1, 300)));
+        // Следующий круг и позиция до начала цикла (после пропуска шага)
+        assert_eq!(cycle_step_at(&steps, 1800 + 60), Some((0, 1440)));
+        assert_eq!(cycle_step_at(&steps, -60), Some((1, 60)));
+        assert_eq!(cycle_step_at(&[], 0), None);
+    }
+
+    #[test]
+    fn skip_starts_next_step_now() {
+        let cfg = config(vec![pomodoro()]);
+        let mut scheduler = TimerScheduler::new();
+
+        assert!(scheduler.skip_step_at(&cfg, at("2026-06-10 09:10:00")));
+        let shift = scheduler.state.shift;
+        assert_eq!(shift.occurrence, pomodoro_occurrence());
+        assert_eq!(shift.cycle_offset_secs, -900);
+
+        assert_eq!(
+            cycle_state(&cfg, "2026-06-10 09:10:00", &shift),
+            (step(1), 300, 6600)
+        );
+        assert_eq!(
+            cycle_state(&cfg, "2026-06-10 09:15:00", &shift),
+            (step(0), 1500, 6300)
+        );
+
+        // Вне циклического интервала пропускать нечего
+        assert!(!scheduler.skip_step_at(&cfg, at("2026-06-10 12:00:00")));
+    }
+
+    #[test]
+    fn extend_delays_following_steps() {
+        let cfg = config(vec![pomodoro()]);
+        let mut scheduler = TimerScheduler::new();
+
+        assert!(scheduler.extend_step_at(&cfg, 10, at("2026-06-10 09:20:00")));
+        let shift = scheduler.state.shift;
+        assert_eq!(
+            (shift.cycle_offset_secs, shift.end_extension_secs),
+            (600, 0)
+        );
+
+        assert_eq!(
+            cycle_state(&cfg, "2026-06-10 09:20:00", &shift),
+            (step(0), 900, 6000)
+        );
+        assert_eq!(
+            cycle_state(&cfg, "2026-06-10 09:35:00", &shift),
+            (step(1), 300, 5100)
+        );
+    }
+
+    #[test]
+    fn extend_past_interval_end_extends_interval() {
+        let cfg = config(vec![pomodoro()]);
+        let mut scheduler = TimerScheduler::new();
+
+        // Последний перерыв кончается вместе с интервалом в 11:00
+        assert_eq!(
+            cycle_state(&cfg, "2026-06-10 10:55:00", &ScheduleShift::default()),
+            (step(1), 300, 300)
+        );
+        assert!(scheduler.extend_step_at(&cfg, 10, at("2026-06-10 10:55:00")));
+        let shift = scheduler.state.shift;
+        assert_eq!(
+            (shift.cycle_offset_secs, shift.end_extension_secs),
+            (600, 600)
+        );
+
+        assert_eq!(
+            cycle_state(&cfg, "2026-06-10 11:05:00", &shift),
+            (step(1), 300, 300)
+        );
+        assert_eq!(
+            calculate_next_transition(&cfg, at("2026-06-10 11:05:00"), &shift, None),
+            Some(at("2026-06-10 11:10:00"))
+        );
+    }
+
+    #[test]
+    fn restart_begins_first_step_now() {
+        let cfg = config(vec![pomodoro()]);
+        let mut scheduler = TimerScheduler::new();
+
+        assert!(scheduler.extend_step_at(&cfg, 5, at("2026-06-10 09:10:00")));
+        assert!(scheduler.restart_cycle_at(&cfg, at("2026-06-10 09:40:00")));
+        let shift = scheduler.state.shift;
+        // 09:40 - позиция 40 - 5 = 35 минут, ее и вычитает перезапуск
+        assert_eq!(shift.cycle_offset_secs, 300 + 2100);
+
+        assert_eq!(
+            cycle_state(&cfg, "2026-06-10 09:40:00", &shift),
+            (step(0), 1500, 4800)
+        );
+        assert_eq!(
+            cycle_state(&cfg, "2026-06-10 10:05:00", &shift),
+            (step(1), 300, 3300)
+        );
+    }
+
+    #[test]
+    fn shift_resets_for_next_occurrence() {
+        let cfg = config(vec![pomodoro()]);
+        let mut scheduler = TimerScheduler::new();
+
+        assert!(scheduler.skip_step_at(&cfg, at("2026-06-10 09:10:00")));
+        assert!(scheduler.skip_step_at(&cfg, at("2026-06-11 09:10:00")));
+        let shift = scheduler.state.shift;
+        assert_eq!(
+            shift.occurrence,
+            Some(Occurrence::Interval {
+                id: 1,
+                date: date("2026-06-11")
+            })
+        );
+        assert_eq!(shift.cycle_offset_secs, -900);
+    }
+
+    fn focus_steps() -> Vec<CycleStep> {
+        vec![
+            CycleStep {
+                screen_id: 1,
+                duration_minutes: 20,
+            },
+            CycleStep {
+                screen_id: 3,
+                duration_minutes: 10,
+            },
+        ]
+    }
+
+    #[test]
+    fn adhoc_session_durations() {
+        let now = at("2026-06-10 09:00:00");
+        let single = AdhocSession::single("Звонок".into(), 3, 45, now);
+        assert_eq!(single.duration_secs, 45 * 60);
+        assert_eq!(single.mode, IntervalMode::Static { screen_id: 3 });
+
+        let cycle = AdhocSession::cycle("Фокус".into(), focus_steps(), 3, now);
+        assert_eq!(cycle.duration_secs, 3 * 30 * 60);
+        assert_eq!(cycle.started_at, now);
+    }
+
+    #[test]
+    fn adhoc_session_overrides_schedule_until_it_ends() {
+        let cfg = config(vec![pomodoro()]);
+        let shift = ScheduleShift::default();
+        let session =
+            AdhocSession::cycle("Фокус".into(), focus_steps(), 2, at("2026-06-10 09:50:00"));
+        let adhoc = Some(&session);
+        let started = Occurrence::Adhoc {
+            started_at: session.started_at,
+        };
+
+        let active = find_active_interval(&cfg, at("2026-06-10 10:15:00"), &shift, adhoc).unwrap();
+        assert_eq!(active.occurrence, started);
+        assert_eq!(active.name, "Фокус");
+        assert_eq!(active.remaining_secs(), 35 * 60);
+
+        let info = determine_active_screen(&cfg, at("2026-06-10 10:15:00"), &shift, adhoc).unwrap();
+        assert_eq!(
+            (info.screen_id, info.kind),
+            (
+                3,
+                ScreenKind::Cycle {
+                    step: CycleStepRef { index: 1, count: 2 }
                 }
+            )
+        );
+        assert_eq!(info.interval_name.as_deref(), Some("Фокус"));
+
+        // Конец сессии - переход обратно к расписанию
+        assert_eq!(
+            calculate_next_transition(&cfg, at("2026-06-10 10:45:00"), &shift, adhoc),
+            Some(at("2026-06-10 10:50:00"))
+        );
+        let active = find_active_interval(&cfg, at("2026-06-10 10:50:00"), &shift, adhoc).unwrap();
+        assert_eq!(active.occurrence, pomodoro_occurrence().unwrap());
+
+        // Вне расписания после сессии показывается экран по умолчанию
+        let info = determine_active_screen(&cfg, at("2026-06-10 11:30:00"), &shift, adhoc).unwrap();
+        assert!(info.is_default_screen());
+    }
+
+    #[test]
+    fn adhoc_session_accepts_step_controls() {
+        let cfg = config(vec![pomodoro()]);
+        let mut scheduler = TimerScheduler::new();
+        let session =
+            AdhocSession::cycle("Фокус".into(), focus_steps(), 1, at("2026-06-10 12:00:00"));
+        let started = Occurrence::Adhoc {
+            started_at: session.started_at,
+        };
+        scheduler.start_adhoc(session);
+
+        // Продление последнего шага продлевает и саму сессию
+        assert!(scheduler.extend_step_at(&cfg, 5, at("2026-06-10 12:25:00")));
+        let shift = scheduler.state.shift;
+        assert_eq!(shift.occurrence, Some(started));
+        assert_eq!(
+            (shift.cycle_offset_secs, shift.end_extension_secs),
+            (300, 300)
+        );
+        let adhoc = scheduler.state.adhoc.as_ref();
+        assert_eq!(
+            calculate_next_transition(&cfg, at("2026-06-10 12:25:00"), &shift, adhoc),
+            Some(at("2026-06-10 12:35:00"))
+        );
+
+        scheduler.stop_adhoc();
+        assert!(scheduler.state.adhoc.is_none());
+        assert!(!scheduler.skip_step_at(&cfg, at("2026-06-10 12:26:00")));
+    }
+
+    #[test]
+    fn next_start_wraps_to_next_day() {
+        let cfg = config(vec![night()]);
+        let minutes = |now| find_next_start(&cfg, at(now)).map(|(m, _)| m);
+
+        assert_eq!(minutes("2026-06-10 21:00:00"), Some(60));
+        // Внутри вхождения ближайшее начало - завтрашнее
+        assert_eq!(minutes("2026-06-10 23:30:00"), Some(22 * 60 + 30));
+        assert_eq!(minutes("2026-06-11 01:00:00"), Some(21 * 60));
+    }
+
+    #[test]
+    fn next_start_skips_days_without_occurrence() {
+        // 2026-06-12 - пятница: следующее будничное начало - в понедельник
+        let mut work = interval(1, (9, 0), (12, 0), IntervalMode::Static { screen_id: 1 });
+        work.recurrence = Recurrence::Weekdays;
+        let cfg = config(vec![work.clone()]);
+        let minutes = |now| find_next_start(&cfg, at(now)).map(|(m, _)| m);
+
+        assert_eq!(minutes("2026-06-12 08:00:00"), Some(60));
+        assert_eq!(minutes("2026-06-12 13:00:00"), Some(2 * 24 * 60 + 20 * 60));
+
+        // Даты, которые уже прошли, больше не дают начал
+        work.recurrence = Recurrence::Dates {
+            dates: vec![date("2026-06-01")],
+        };
+        let cfg = config(vec![work.clone()]);
+        assert!(find_next_start(&cfg, at("2026-06-12 08:00:00")).is_none());
+
+        // Дальние даты находятся без ограничения на число просматриваемых дней
+        work.recurrence = Recurrence::Dates {
+            dates: vec![date("2028-06-12")],
+        };
+        let cfg = config(vec![work]);
+        let minutes = |now| find_next_start(&cfg, at(now)).map(|(m, _)| m);
+        assert_eq!(minutes("2026-06-12 08:00:00"), Some(731 * 24 * 60 + 60));
+    }
+
+    #[test]
+    fn interval_stays_active_after_midnight() {
+        let cfg = config(vec![night()]);
+        let shift = ScheduleShift::default();
+
+        let info = determine_active_screen(&cfg, at("2026-06-11 01:00:00"), &shift, None).unwrap();
+        assert_eq!(info.kind, ScreenKind::Static);
+        assert_eq!(info.screen_id, 2);
+        assert_eq!(info.remaining_seconds, 3600);
+
+        let active = find_active_interval(&cfg, at("2026-06-11 01:00:00"), &shift, None).unwrap();
+        assert_eq!(
+            active.occurrence,
+            Occurrence::Interval {
+                id: 1,
+                date: date("2026-06-10")
             }
-        }
+        );
+
+        let info = determine_active_screen(&cfg, at("2026-06-11 02:00:00"), &shift, None).unwrap();
+        assert!(info.is_default_screen());
     }
 
-    // Сортируем по времени
-    transitions.sort_by_key(|t| t.0);
-    transitions
+    #[test]
+    fn next_transition_crosses_midnight() {
+        let cfg = config(vec![night()]);
+        let shift = ScheduleShift::default();
+        let next = |now| calculate_next_transition(&cfg, at(now), &shift, None);
+
+        assert_eq!(next("2026-06-10 23:30:15"), Some(at("2026-06-11 02:00:00")));
+        assert_eq!(next("2026-06-11 03:00:20"), Some(at("2026-06-11 22:00:00")));
+    }
+
+    #[test]
+    fn cycle_steps_continue_after_midnight() {
+        let steps = vec![
+            CycleStep {
+                screen_id: 1,
+                duration_minutes: 50,
+            },
+            CycleStep {
+                screen_id: 2,
+                duration_minutes: 10,
+            },
+        ];
+        let cfg = config(vec![interval(
+            1,
+            (23, 0),
+            (1, 30),
+            IntervalMode::Cycle { steps },
+        )]);
+        let shift = ScheduleShift::default();
+
+        // 00:55 - второй шаг второго круга
+        let info = determine_active_screen(&cfg, at("2026-06-11 00:55:00"), &shift, None).unwrap();
+        assert_eq!(info.screen_id, 2);
+        assert_eq!(info.remaining_seconds, 5 * 60);
+        assert_eq!(
+            calculate_next_transition(&cfg, at("2026-06-11 00:55:00"), &shift, None),
+            Some(at("2026-06-11 01:00:00"))
+        );
+        // Третий круг обрезается концом интервала
+        assert_eq!(
+            calculate_next_transition(&cfg, at("2026-06-11 01:10:00"), &shift, None),
+            Some(at("2026-06-11 01:30:00"))
+        );
+    }
+
+    #[test]
+    fn daily_transitions_split_at_midnight() {
+        let mut once = night();
+        once.recurrence = Recurrence::Dates {
+            dates: vec![date("2026-06-10")],
+        };
+        let cfg = config(vec![once]);
+        let transitions = |day| {
+            get_daily_transitions(&cfg, date(day))
+                .into_iter()
+                .map(|t| (t.minute, t.kind))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            transitions("2026-06-10"),
+            vec![(22 * 60, TransitionKind::IntervalStart)]
+        );
+        assert_eq!(
+            transitions("2026-06-11"),
+            vec![(2 * 60, TransitionKind::IntervalEnd)]
+        );
+        assert!(transitions("2026-06-12").is_empty());
+    }
 }
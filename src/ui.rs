@@ -1,11 +1,37 @@
-use std::time::Duration;
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
+};
 
+use chrono::{Datelike, Local, NaiveDate, Weekday};
 use eframe::egui;
 
 use crate::{
-    config::{AppConfig, CycleStep, IntervalMode, Rgba8, ScreenConfig, TimeInterval, TimeOfDay},
-    timer::{TimerScheduler, format_duration_hhmmss, get_daily_transitions, validate_intervals},
-    utils::{set_language, tr, tr_with_args},
+    autostart::{self, AutostartStatus},
+    calendar::{BusyImport, IcsError, export_ics, read_busy_blocks, replace_imported},
+    config::{
+        AppConfig, AutostartMethod, BrokenConfig, Chime, ConfigError, ConfigWatcher, CycleStep,
+        IntervalMode, PauseMode, Profile, Recurrence, Rgba8, ScreenConfig, TimeInterval, TimeOfDay,
+        WindowPosition,
+    },
+    history::{
+        ExportFormat, ExportGrouping, ExportOptions, History, HistoryEntry, append_entry, export,
+        history_path, week_start,
+    },
+    ipc::{IpcRequest, IpcResponse, IpcServer, StateSnapshot},
+    locale::{
+        AvailableLocale, available_locales, fallback_chain, make_bundle, reported_errors,
+        user_locales_dir,
+    },
+    notify::{DesktopNotifications, Notification},
+    sharing::{ImportAction, ImportItem, ImportPlan, ScheduleBundle},
+    sound::{Sound, SoundPlayer},
+    timer::{
+        AdhocSession, TimerEvent, TimerScheduler, TransitionKind, UpcomingTransition,
+        format_duration_hhmmss, get_daily_transitions,
+    },
+    utils::{set_language, tr, tr_with_args, weekday_key},
+    validation::{IssueField, Severity, ValidationIssue, validate_intervals, validate_profiles},
 };
 
 use fluent_bundle::{FluentBundle, FluentResource};
@@ -14,6 +40,8 @@ use fluent_bundle::{FluentBundle, FluentResource};
 pub enum SettingsTab {
     Timers,
     System,
+    Sharing,
+    Stats,
 }
 
 #[derive(Debug, Clone)]
@@ -28,10 +56,23 @@ pub struct EditingInterval {
     pub is_new: bool,
 }
 
+/// Параметры разовой сессии в диалоге запуска
+#[derive(Debug, Clone)]
+pub struct AdhocDraft {
+    /// Повторять шаги циклического интервала вместо одного экрана
+    pub use_cycle: bool,
+    pub screen_id: u32,
+    pub minutes: u32,
+    pub interval_id: Option<u32>,
+    pub repeats: u32,
+}
+
 pub struct AppState {
     pub config: AppConfig,
     pub config_path: std::path::PathBuf,
     pub bundle: FluentBundle<FluentResource>,
+    /// Языки для выбора в настройках: встроенные и из каталога пользователя
+    pub locales: Vec<AvailableLocale>,
     pub show_settings: bool,
     pub settings_tab: SettingsTab,
     pub editing_screen: Option<EditingScreen>,
@@ -39,6 +80,167 @@ pub struct AppState {
     pub next_screen_id: u32,
     pub next_interval_id: u32,
     pub timer_scheduler: TimerScheduler,
+    pub adhoc_draft: Option<AdhocDraft>,
+    pub sound_player: SoundPlayer,
+    /// Результат последней операции с автозапуском: `Ok` - запись, найденная
+    /// при запуске (исправленная устаревшая или чужая), `Err` - текст ошибки
+    pub autostart_notice: Option<Result<AutostartStatus, String>>,
+    pub window_tracking: WindowTracking,
+    pub notifications: DesktopNotifications,
+    /// Управляющий сокет (None, если его не удалось открыть)
+    pub ipc: Option<IpcServer>,
+    pub config_watcher: ConfigWatcher,
+    /// Проблема, найденная при последнем перечитывании config.json
+    pub config_issue: Option<ConfigIssue>,
+    /// config.json не прочитан при запуске: вместо таймера показывается экран восстановления
+    pub config_recovery: Option<BrokenConfig>,
+    pub sharing: SharingState,
+    pub history: HistoryState,
+}
+
+/// Журнал сессий и вкладка статистики
+pub struct HistoryState {
+    /// Журнал читается при первом открытии вкладки, затем пополняется в памяти
+    pub log: Option<History>,
+    /// День, за который показывается статистика (и неделя, в которую он входит)
+    pub date: NaiveDate,
+    /// Ошибка последнего чтения или записи журнала
+    pub error: Option<String>,
+    /// Параметры выгрузки для табеля и файл, в который она пишется
+    pub export: ExportOptions,
+    pub export_path: String,
+    /// Результат последней выгрузки: `Ok` - сообщение, `Err` - текст ошибки
+    pub export_notice: Option<Result<String, String>>,
+}
+
+impl Default for HistoryState {
+    fn default() -> Self {
+        let today = Local::now().date_naive();
+        Self {
+            log: None,
+            date: today,
+            error: None,
+            export: ExportOptions {
+                from: week_start(today),
+                to: today,
+                grouping: ExportGrouping::Sessions,
+                rounding_minutes: 0,
+            },
+            export_path: directories::UserDirs::new()
+                .map(|dirs| dirs.home_dir().join("flowtimer-history.csv"))
+                .unwrap_or_else(|| PathBuf::from("flowtimer-history.csv"))
+                .display()
+                .to_string(),
+            export_notice: None,
+        }
+    }
+}
+
+/// Вкладка обмена расписаниями
+pub struct SharingState {
+    /// Экраны и интервалы, отмеченные для экспорта
+    pub export_screens: Vec<u32>,
+    pub export_intervals: Vec<u32>,
+    pub export_path: String,
+    pub import_path: String,
+    /// Предпросмотр импорта, ожидающий подтверждения
+    pub import_plan: Option<ImportPlan>,
+    /// Выгрузка в календарь: первый день, неделя вместо дня, шаги цикла
+    pub ics_date: NaiveDate,
+    pub ics_week: bool,
+    pub ics_steps: bool,
+    pub ics_path: String,
+    /// Импорт занятого времени из календаря
+    pub busy: BusyImportDraft,
+    /// Результат последней операции: `Ok` - сообщение, `Err` - текст ошибки
+    pub notice: Option<Result<String, String>>,
+}
+
+impl Default for SharingState {
+    fn default() -> Self {
+        let home_file = |name: &str| {
+            directories::UserDirs::new()
+                .map(|dirs| dirs.home_dir().join(name))
+                .unwrap_or_else(|| PathBuf::from(name))
+                .display()
+                .to_string()
+        };
+        let path = home_file("flowtimer-schedule.json");
+        Self {
+            export_screens: Vec::new(),
+            export_intervals: Vec::new(),
+            export_path: path.clone(),
+            import_path: path,
+            import_plan: None,
+            ics_date: Local::now().date_naive(),
+            ics_week: false,
+            ics_steps: false,
+            ics_path: home_file("flowtimer.ics"),
+            busy: BusyImportDraft {
+                path: home_file("calendar.ics"),
+                date: Local::now().date_naive(),
+                week: true,
+                meeting_screen_id: None,
+                filler_interval_id: None,
+                day_start: TimeOfDay { hour: 9, minute: 0 },
+                day_end: TimeOfDay {
+                    hour: 18,
+                    minute: 0,
+                },
+                unknown_time_zones: Vec::new(),
+            },
+            notice: None,
+        }
+    }
+}
+
+/// Параметры импорта занятого времени из файла .ics
+pub struct BusyImportDraft {
+    pub path: String,
+    pub date: NaiveDate,
+    pub week: bool,
+    /// Экран встреч; `None` - создать новый экран при импорте
+    pub meeting_screen_id: Option<u32>,
+    /// Циклический интервал, шаги которого заполняют промежутки между встречами
+    pub filler_interval_id: Option<u32>,
+    /// Рабочее время, в пределах которого заполняются промежутки
+    pub day_start: TimeOfDay,
+    pub day_end: TimeOfDay,
+    /// Часовые пояса последнего импорта, время в которых прочитано как местное
+    pub unknown_time_zones: Vec<String>,
+}
+
+/// Проблема с config.json, изменённым вне программы
+#[derive(Debug, Clone)]
+pub enum ConfigIssue {
+    /// Файл не разобран: действует прежняя конфигурация, а файл не перезаписывается
+    ParseError(String),
+    /// Файл применён, но интервалы не прошли проверку
+    Invalid(Vec<ValidationIssue>),
+}
+
+/// Внешняя и внутренняя рамки основного окна и размер его монитора.
+/// Читаются из корневого viewport, поэтому верны и внутри окна настроек.
+fn main_window_geometry(
+    ctx: &egui::Context,
+) -> (Option<egui::Rect>, Option<egui::Rect>, Option<egui::Vec2>) {
+    ctx.input_for(egui::ViewportId::ROOT, |i| {
+        let viewport = i.viewport();
+        (
+            viewport.outer_rect,
+            viewport.inner_rect,
+            viewport.monitor_size,
+        )
+    })
+}
+
+/// Отслеживание положения окна для его сохранения
+#[derive(Default)]
+pub struct WindowTracking {
+    /// Положение уже проверено на выход за пределы монитора после запуска
+    pub checked: bool,
+    /// Когда окно последний раз сдвинулось и позиция ещё не сохранена
+    pub moved_at: Option<Instant>,
 }
 
 impl AppState {
@@ -52,21 +254,465 @@ impl AppState {
             {
                 self.show_settings = !self.show_settings;
             }
+            if i.key_pressed(egui::Key::Space) {
+                self.timer_scheduler.toggle_pause(&self.config);
+            }
+            // Управление шагами цикла
+            if i.key_pressed(egui::Key::N) {
+                self.timer_scheduler.skip_step(&self.config);
+            }
+            if i.key_pressed(egui::Key::E) {
+                self.timer_scheduler.extend_step(
+                    &self.config,
+                    self.config.system_settings.extend_step_minutes,
+                );
+            }
+            if i.key_pressed(egui::Key::R) && !i.modifiers.ctrl {
+                self.timer_scheduler.restart_cycle(&self.config);
+            }
         });
 
-        // Обновляем планировщик таймера
-        let screen_changed = self.timer_scheduler.update(&self.config);
+        // Команды от скриптов через управляющий сокет
+        self.handle_ipc(ctx);
+
+        // Правка config.json вне программы применяется без перезапуска
+        if self.config_watcher.poll() {
+            let _ = self.reload_config();
+        }
+
+        // Пока испорченный config.json не исправлен, таймер по настройкам
+        // по умолчанию не запускаем
+        if self.config_recovery.is_some() {
+            ctx.request_repaint_after(Duration::from_secs(1));
+            self.recovery_panel(ctx);
+            return;
+        }
+
+        // Профиль, назначенный на сегодняшний день недели, включается раз в день
+        if self.config.apply_auto_profile(Local::now().date_naive()) {
+            self.save_config();
+        }
 
-        // Если экран изменился и включены звуковые уведомления, можно добавить звук
-        if screen_changed && self.config.system_settings.sound_notifications {
-            // TODO: Добавить воспроизведение звука при смене экранов
+        // Обновляем планировщик таймера и реагируем на его события
+        self.timer_scheduler.update(&self.config);
+        for event in self.timer_scheduler.take_events() {
+            match event {
+                TimerEvent::Transition { kind, screen_id } => self.on_transition(kind, screen_id),
+                TimerEvent::Warning(upcoming) => self.on_warning(&upcoming),
+                TimerEvent::Finished(entry) => self.record_history(entry),
+            }
         }
 
+        self.track_window_position(ctx);
+
         ctx.request_repaint_after(Duration::from_secs(1));
         self.main_panel(ctx);
+        self.adhoc_window(ctx);
         self.settings_window(ctx);
     }
 
+    /// Выполняет накопившиеся команды управляющего сокета
+    fn handle_ipc(&mut self, ctx: &egui::Context) {
+        let Some(ipc) = &self.ipc else {
+            return;
+        };
+        let calls: Vec<_> = std::iter::from_fn(|| ipc.try_recv()).collect();
+
+        for call in calls {
+            let response = match self.apply_ipc_request(ctx, &call.request) {
+                Ok(()) => {
+                    // События перехода попадут в обычную обработку ниже
+                    self.timer_scheduler.update(&self.config);
+                    IpcResponse::ok(StateSnapshot::new(
+                        &self.timer_scheduler.state,
+                        &self.config,
+                    ))
+                }
+                Err(error) => IpcResponse::error(error),
+            };
+            call.reply(response);
+        }
+    }
+
+    fn apply_ipc_request(
+        &mut self,
+        ctx: &egui::Context,
+        request: &IpcRequest,
+    ) -> Result<(), String> {
+        let config = &self.config;
+        let scheduler = &mut self.timer_scheduler;
+        let in_cycle = |done: bool| {
+            if done {
+                Ok(())
+            } else {
+                Err("no active cycle".to_string())
+            }
+        };
+
+        match request {
+            IpcRequest::State => Ok(()),
+            IpcRequest::Pause => {
+                scheduler.pause();
+                Ok(())
+            }
+            IpcRequest::Resume => {
+                scheduler.resume(config);
+                Ok(())
+            }
+            IpcRequest::TogglePause => {
+                scheduler.toggle_pause(config);
+                Ok(())
+            }
+            IpcRequest::Skip => in_cycle(scheduler.skip_step(config)),
+            IpcRequest::Extend { minutes } => {
+                let minutes = minutes.unwrap_or(config.system_settings.extend_step_minutes);
+                in_cycle(scheduler.extend_step(config, minutes))
+            }
+            IpcRequest::Restart => in_cycle(scheduler.restart_cycle(config)),
+            IpcRequest::StartAdhoc {
+                screen_id,
+                minutes,
+                interval_id,
+                repeats,
+            } => {
+                let draft = AdhocDraft {
+                    use_cycle: interval_id.is_some(),
+                    screen_id: screen_id
+                        .or(config.default_screen_id())
+                        .or_else(|| config.screens.first().map(|s| s.id))
+                        .unwrap_or(1),
+                    minutes: minutes.unwrap_or(45).max(1),
+                    interval_id: *interval_id,
+                    repeats: repeats.unwrap_or(1).max(1),
+                };
+                let session = self
+                    .adhoc_session(&draft, Local::now())
+                    .ok_or_else(|| "unknown screen or cycle interval".to_string())?;
+                self.timer_scheduler.start_adhoc(session);
+                Ok(())
+            }
+            IpcRequest::StopAdhoc => {
+                scheduler.stop_adhoc();
+                Ok(())
+            }
+            IpcRequest::Reload => self.reload_config().map_err(|e| e.to_string()),
+            IpcRequest::SwitchProfile { profile } => {
+                let id = config
+                    .find_profile(profile)
+                    .map(|p| p.id)
+                    .ok_or_else(|| format!("unknown profile: {}", profile))?;
+                self.switch_profile(id);
+                Ok(())
+            }
+            IpcRequest::Focus => {
+                // Повторный запуск программы: поднимаем уже открытое окно
+                ctx.send_viewport_cmd(egui::ViewportCommand::Visible(true));
+                ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(false));
+                ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+                Ok(())
+            }
+        }
+    }
+
+    /// Перечитывает config.json с диска. При ошибке разбора остаётся прежняя
+    /// конфигурация, а ошибка показывается в интерфейсе.
+    pub fn reload_config(&mut self) -> Result<(), ConfigError> {
+        self.config_watcher.mark_seen();
+        let config = match crate::config::load_config(&self.config_path) {
+            Ok(config) => config,
+            Err(err) => {
+                self.config_issue = Some(ConfigIssue::ParseError(err.to_string()));
+                return Err(err);
+            }
+        };
+        if config.language != self.config.language {
+            self.bundle = make_bundle(&config.language, &self.locales);
+        }
+        // Новые id не должны совпасть с уже выданными
+        let max_screen_id = config.screens.iter().map(|s| s.id).max().unwrap_or(0);
+        let max_interval_id = config.all_intervals().map(|i| i.id).max().unwrap_or(0);
+        self.next_screen_id = self.next_screen_id.max(max_screen_id + 1);
+        self.next_interval_id = self.next_interval_id.max(max_interval_id + 1);
+
+        let problems = validate_profiles(&config);
+        self.config_issue = (!problems.is_empty()).then_some(ConfigIssue::Invalid(problems));
+        self.config_recovery = None;
+        self.config = config;
+        Ok(())
+    }
+
+    /// Делает профиль активным и сохраняет выбор
+    pub fn switch_profile(&mut self, id: u32) {
+        if self.config.active_profile_id != id && self.config.set_active_profile(id) {
+            self.save_config();
+            self.timer_scheduler.update(&self.config);
+        }
+    }
+
+    /// Отказывается от испорченного config.json и записывает настройки по умолчанию.
+    /// Копия испорченного файла остаётся рядом.
+    pub fn reset_config_to_defaults(&mut self) {
+        self.config = AppConfig::default();
+        self.bundle = make_bundle(&self.config.language, &self.locales);
+        self.config_issue = None;
+        self.config_recovery = None;
+        self.save_config();
+    }
+
+    /// Экран восстановления после неудачной загрузки config.json
+    fn recovery_panel(&mut self, ctx: &egui::Context) {
+        let Some(broken) = self.config_recovery.clone() else {
+            return;
+        };
+        egui::CentralPanel::default()
+            .frame(
+                egui::Frame::default()
+                    .fill(egui::Color32::from_rgb(60, 60, 60))
+                    .inner_margin(egui::Margin::same(12)),
+            )
+            .show(ctx, |ui| {
+                let drag_area = ui.allocate_response(
+                    egui::vec2(ui.available_width(), 10.0),
+                    egui::Sense::click(),
+                );
+                if drag_area.is_pointer_button_down_on() {
+                    ctx.send_viewport_cmd(egui::ViewportCommand::StartDrag);
+                }
+
+                ui.label(
+                    egui::RichText::new(tr(&self.bundle, "recovery-title"))
+                        .size(20.0)
+                        .strong()
+                        .color(egui::Color32::WHITE),
+                );
+                ui.label(tr(&self.bundle, "recovery-desc"));
+                egui::ScrollArea::vertical()
+                    .max_height(60.0)
+                    .show(ui, |ui| {
+                        ui.colored_label(egui::Color32::from_rgb(200, 80, 80), &broken.error);
+                    });
+
+                match &broken.backup {
+                    Some(backup) => {
+                        let mut args = fluent_bundle::FluentArgs::new();
+                        args.set("path", backup.display().to_string());
+                        ui.small(tr_with_args(&self.bundle, "recovery-backup", Some(&args)));
+                    }
+                    None => {
+                        ui.small(tr(&self.bundle, "recovery-no-backup"));
+                    }
+                }
+                ui.small(self.config_path.display().to_string());
+
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    if ui.button(tr(&self.bundle, "recovery-retry")).clicked() {
+                        let _ = self.reload_config();
+                        if let Some(recovery) = &mut self.config_recovery
+                            && let Some(ConfigIssue::ParseError(error)) = &self.config_issue
+                        {
+                            recovery.error = error.clone();
+                        }
+                    }
+                    if ui.button(tr(&self.bundle, "recovery-defaults")).clicked() {
+                        self.reset_config_to_defaults();
+                    }
+                    if ui.button(tr(&self.bundle, "btn-close")).clicked() {
+                        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                    }
+                });
+            });
+    }
+
+    /// Сохраняет конфигурацию. Пока config.json с ошибкой не исправлен,
+    /// файл не перезаписывается, чтобы не потерять ручную правку.
+    pub fn save_config(&mut self) {
+        if matches!(self.config_issue, Some(ConfigIssue::ParseError(_))) {
+            return;
+        }
+        let _ = crate::config::save_config(&self.config_path, &self.config);
+        // Собственная запись не должна вызывать перечитывание
+        self.config_watcher.mark_seen();
+    }
+
+    /// Меню переключения профиля в верхней панели (если профилей больше одного)
+    fn profile_menu(&mut self, ui: &mut egui::Ui) {
+        if self.config.profiles.len() < 2 {
+            return;
+        }
+        let mut selected = None;
+        let title =
+            egui::RichText::new(&self.config.active_profile().name).color(egui::Color32::WHITE);
+        ui.menu_button(title, |ui| {
+            for profile in &self.config.profiles {
+                if ui
+                    .selectable_label(profile.id == self.config.active_profile_id, &profile.name)
+                    .clicked()
+                {
+                    selected = Some(profile.id);
+                    ui.close();
+                }
+            }
+        })
+        .response
+        .on_hover_text(tr(&self.bundle, "profile-switch"));
+
+        if let Some(id) = selected {
+            self.switch_profile(id);
+        }
+    }
+
+    /// Кнопка-индикатор проблемы с config.json: открывает системные настройки
+    fn config_issue_button(&mut self, ui: &mut egui::Ui, button_color: egui::Color32) {
+        let Some(issue) = &self.config_issue else {
+            return;
+        };
+        let (color, hint) = match issue {
+            ConfigIssue::ParseError(_) => (
+                egui::Color32::from_rgb(200, 80, 80),
+                tr(&self.bundle, "config-parse-error"),
+            ),
+            ConfigIssue::Invalid(_) => (
+                egui::Color32::from_rgb(200, 150, 50),
+                tr(&self.bundle, "config-invalid"),
+            ),
+        };
+        if ui
+            .add(
+                egui::Button::new(egui::RichText::new("⚠").color(color))
+                    .fill(button_color)
+                    .stroke(egui::Stroke::NONE),
+            )
+            .on_hover_text(hint)
+            .clicked()
+        {
+            self.show_settings = true;
+            self.settings_tab = SettingsTab::System;
+        }
+    }
+
+    /// Звук и уведомление рабочего стола при смене экрана
+    fn on_transition(&mut self, kind: TransitionKind, screen_id: u32) {
+        let screen = self.config.screens.iter().find(|s| s.id == screen_id);
+
+        if self.config.system_settings.sound_notifications {
+            // Собственный звук экрана по умолчанию не заменяет звук конца интервала
+            let screen_sound = screen.filter(|_| kind != TransitionKind::IntervalEnd);
+            self.sound_player.play_transition(
+                &self.config.system_settings.sound,
+                screen_sound,
+                kind,
+            );
+        }
+
+        // Уведомление рабочего стола: окно может быть перекрыто или свёрнуто
+        if self.config.system_settings.desktop_notifications
+            && screen.is_none_or(|s| s.notify)
+            && let Some(info) = &self.timer_scheduler.state.current_screen
+        {
+            let body = match self.timer_scheduler.state.next_transition {
+                Some(next) => {
+                    let mut args = fluent_bundle::FluentArgs::new();
+                    args.set("interval", info.interval_label(&self.bundle));
+                    args.set("time", next.format("%H:%M").to_string());
+                    tr_with_args(&self.bundle, "notify-body", Some(&args))
+                }
+                None => info.interval_label(&self.bundle),
+            };
+            self.notifications.send(Notification {
+                summary: info.display_title(&self.bundle),
+                body,
+            });
+        }
+    }
+
+    /// Звук и уведомление о приближающемся переходе (плашка и пульсация - в `main_panel`)
+    fn on_warning(&mut self, upcoming: &UpcomingTransition) {
+        let warnings = &self.config.system_settings.warnings;
+
+        if warnings.sound {
+            let sound = &self.config.system_settings.sound;
+            let _ = self
+                .sound_player
+                .play(&Sound::Chime(sound.chime), sound.volume);
+        }
+
+        if warnings.notification {
+            let mut args = fluent_bundle::FluentArgs::new();
+            args.set("screen", upcoming.next_title.as_str());
+            args.set("minutes", minutes_until(upcoming.at));
+            args.set("time", upcoming.at.format("%H:%M").to_string());
+            self.notifications.send(Notification {
+                summary: tr_with_args(&self.bundle, "warning-banner", Some(&args)),
+                body: tr_with_args(&self.bundle, "warning-notify-body", Some(&args)),
+            });
+        }
+    }
+
+    /// Следит за внешней рамкой окна: возвращает окно на экран после смены
+    /// монитора и сохраняет новое положение, когда окно перестало двигаться
+    fn track_window_position(&mut self, ctx: &egui::Context) {
+        let Some(saved) = &mut self.config.system_settings.window_position else {
+            return;
+        };
+        let (outer_rect, inner_rect, monitor_size) = main_window_geometry(ctx);
+        let Some(outer_rect) = outer_rect else {
+            return;
+        };
+
+        if !self.window_tracking.checked {
+            self.window_tracking.checked = true;
+            if let Some(monitor) = monitor_size
+                && let Some((x, y)) = saved.clamped_to(outer_rect.size().into(), monitor.into())
+            {
+                ctx.send_viewport_cmd(egui::ViewportCommand::OuterPosition(egui::pos2(x, y)));
+                return;
+            }
+        }
+
+        let moved =
+            (saved.x - outer_rect.min.x).abs() >= 1.0 || (saved.y - outer_rect.min.y).abs() >= 1.0;
+        if moved {
+            saved.x = outer_rect.min.x;
+            saved.y = outer_rect.min.y;
+            saved.size = inner_rect.map(|rect| rect.size().into());
+            saved.monitor_size = monitor_size.map(Into::into);
+            self.window_tracking.moved_at = Some(Instant::now());
+        }
+
+        // Во время перетаскивания не пишем файл на каждый кадр
+        if let Some(moved_at) = self.window_tracking.moved_at
+            && moved_at.elapsed() >= Duration::from_secs(1)
+        {
+            self.save_window_position();
+        } else if self.window_tracking.moved_at.is_some() {
+            ctx.request_repaint_after(Duration::from_millis(500));
+        }
+    }
+
+    /// Дописывает отрезок в журнал сессий
+    fn record_history(&mut self, entry: HistoryEntry) {
+        let path = history_path(&self.config_path);
+        self.history.error = append_entry(&path, &entry).err().map(|err| err.to_string());
+        if let Some(log) = &mut self.history.log {
+            log.entries.push(entry);
+        }
+    }
+
+    /// Записывает в журнал отрезок, оборванный закрытием программы
+    pub fn finish_history(&mut self) {
+        if let Some(entry) = self.timer_scheduler.finish() {
+            self.record_history(entry);
+        }
+    }
+
+    /// Сохраняет отложенное положение окна (после перемещения и при выходе)
+    pub fn save_window_position(&mut self) {
+        if self.window_tracking.moved_at.take().is_some() {
+            self.save_config();
+        }
+    }
+
     fn setup_custom_style(&self, ctx: &egui::Context) {
         let mut style = (*ctx.style()).clone();
 
@@ -99,10 +745,39 @@ impl AppState {
         // Клонируем информацию о текущем экране, чтобы избежать проблем с заимствованием
         let current_screen = self.timer_scheduler.state.current_screen.clone();
         let _next_transition = self.timer_scheduler.state.next_transition;
-        let _is_running = self.timer_scheduler.state.is_running;
+        let is_running = self.timer_scheduler.state.is_running;
+        let in_cycle = self.timer_scheduler.in_cycle(&self.config);
+        let adhoc_name = self
+            .timer_scheduler
+            .state
+            .adhoc
+            .as_ref()
+            .map(|a| a.name.clone());
+
+        let warning = self.timer_scheduler.state.warning.clone();
+        let warnings = self.config.system_settings.warnings.clone();
 
         if let Some(active) = current_screen {
-            let bg = active.color.to_egui();
+            let mut bg = active.color.to_egui();
+
+            // Пульсация фона перед переходом: цвет качается в сторону следующего экрана
+            if warnings.pulse
+                && let Some(upcoming) = &warning
+            {
+                let target = self
+                    .config
+                    .screens
+                    .iter()
+                    .find(|s| s.id == upcoming.next_screen_id)
+                    .map(|s| s.color.to_egui())
+                    .filter(|color| *color != bg)
+                    .unwrap_or(egui::Color32::WHITE);
+                let time = ctx.input(|i| i.time);
+                let phase = ((time * std::f64::consts::PI).sin() * 0.5 + 0.5) as f32;
+                bg = bg.lerp_to_gamma(target, phase * 0.35);
+                ctx.request_repaint();
+            }
+
             egui::CentralPanel::default()
                 .frame(egui::Frame::default().fill(bg))
                 .show(ctx, |ui| {
@@ -110,7 +785,7 @@ impl AppState {
                     ui.horizontal(|ui| {
                         // Делаем область для перетаскивания окна
                         let drag_area = ui.allocate_response(
-                            egui::vec2(ui.available_width() - 120.0, 30.0),
+                            egui::vec2(ui.available_width() - 200.0, 30.0),
                             egui::Sense::click(),
                         );
                         if drag_area.is_pointer_button_down_on() {
@@ -160,6 +835,55 @@ impl AppState {
                             {
                                 self.show_settings = true;
                             }
+
+                            // Ошибка в config.json, изменённом вне программы
+                            self.config_issue_button(ui, button_color);
+
+                            // Быстрое переключение профиля
+                            self.profile_menu(ui);
+
+                            // Кнопка паузы
+                            let (pause_icon, pause_hint) = if is_running {
+                                ("⏸", tr(&self.bundle, "timer-pause"))
+                            } else {
+                                ("▶", tr(&self.bundle, "timer-continue"))
+                            };
+                            if ui
+                                .add(
+                                    egui::Button::new(pause_icon)
+                                        .fill(button_color)
+                                        .stroke(egui::Stroke::NONE),
+                                )
+                                .on_hover_text(pause_hint)
+                                .clicked()
+                            {
+                                self.timer_scheduler.toggle_pause(&self.config);
+                            }
+
+                            // Запуск или остановка разовой сессии
+                            if adhoc_name.is_some() {
+                                if ui
+                                    .add(
+                                        egui::Button::new("⏹")
+                                            .fill(button_color)
+                                            .stroke(egui::Stroke::NONE),
+                                    )
+                                    .on_hover_text(tr(&self.bundle, "adhoc-stop"))
+                                    .clicked()
+                                {
+                                    self.timer_scheduler.stop_adhoc();
+                                }
+                            } else if ui
+                                .add(
+                                    egui::Button::new("⏱")
+                                        .fill(button_color)
+                                        .stroke(egui::Stroke::NONE),
+                                )
+                                .on_hover_text(tr(&self.bundle, "adhoc-start"))
+                                .clicked()
+                            {
+                                self.open_adhoc_dialog();
+                            }
                         });
                     });
 
@@ -167,7 +891,31 @@ impl AppState {
                     let remaining_text = format_duration_hhmmss(active.remaining_seconds);
 
                     ui.vertical_centered(|ui| {
-                        ui.add_space(10.0);
+                        // Плашка предупреждения о скором переходе
+                        if warnings.banner
+                            && let Some(upcoming) = &warning
+                        {
+                            let mut args = fluent_bundle::FluentArgs::new();
+                            args.set("screen", upcoming.next_title.as_str());
+                            args.set("minutes", minutes_until(upcoming.at));
+                            egui::Frame::default()
+                                .fill(egui::Color32::from_rgba_unmultiplied(0, 0, 0, 60))
+                                .corner_radius(6.0)
+                                .inner_margin(egui::Margin::symmetric(10, 2))
+                                .show(ui, |ui| {
+                                    ui.label(
+                                        egui::RichText::new(tr_with_args(
+                                            &self.bundle,
+                                            "warning-banner",
+                                            Some(&args),
+                                        ))
+                                        .size(14.0)
+                                        .color(egui::Color32::WHITE),
+                                    );
+                                });
+                        } else {
+                            ui.add_space(10.0);
+                        }
 
                         // 1. ТАЙМЕР ПЕРВЫЙ - большой белый таймер по центру
                         let timer_text = egui::RichText::new(remaining_text)
@@ -179,21 +927,52 @@ impl AppState {
                         ui.add_space(15.0);
 
                         // 2. ЗАГОЛОВОК ВТОРОЙ
-                        let title_text = egui::RichText::new(&active.title)
+                        let title_text = egui::RichText::new(active.display_title(&self.bundle))
                             .size(28.0)
                             .strong()
                             .color(egui::Color32::WHITE);
                         ui.label(title_text);
 
                         // 3. ПОДЗАГОЛОВОК ТРЕТИЙ
-                        if !active.subtitle.is_empty() {
+                        let subtitle = active.display_subtitle(&self.bundle);
+                        if !subtitle.is_empty() {
                             ui.add_space(3.0);
-                            let subtitle_text = egui::RichText::new(&active.subtitle)
+                            let subtitle_text = egui::RichText::new(subtitle)
                                 .size(16.0)
                                 .color(egui::Color32::from_rgba_unmultiplied(255, 255, 255, 220));
                             ui.label(subtitle_text);
                         }
 
+                        // Название разовой сессии
+                        if let Some(name) = &adhoc_name {
+                            ui.add_space(3.0);
+                            let mut args = fluent_bundle::FluentArgs::new();
+                            args.set("name", name.as_str());
+                            let session_text = egui::RichText::new(tr_with_args(
+                                &self.bundle,
+                                "adhoc-active",
+                                Some(&args),
+                            ))
+                            .size(14.0)
+                            .color(egui::Color32::from_rgba_unmultiplied(255, 255, 255, 200));
+                            ui.label(session_text);
+                        }
+
+                        // Индикатор паузы
+                        if !is_running {
+                            ui.add_space(3.0);
+                            let paused_text = egui::RichText::new(tr(&self.bundle, "timer-paused"))
+                                .size(14.0)
+                                .color(egui::Color32::from_rgba_unmultiplied(255, 255, 255, 200));
+                            ui.label(paused_text);
+                        }
+
+                        // Управление шагами цикла
+                        if in_cycle {
+                            ui.add_space(8.0);
+                            self.cycle_controls(ui);
+                        }
+
                         ui.add_space(20.0);
                     });
                 });
@@ -256,6 +1035,12 @@ impl AppState {
                             {
                                 self.show_settings = true;
                             }
+
+                            // Ошибка в config.json, изменённом вне программы
+                            self.config_issue_button(ui, button_color);
+
+                            // Быстрое переключение профиля
+                            self.profile_menu(ui);
                         });
                     });
 
@@ -282,6 +1067,229 @@ impl AppState {
         }
     }
 
+    fn open_adhoc_dialog(&mut self) {
+        let cycle_interval = self
+            .config
+            .intervals()
+            .iter()
+            .find(|i| matches!(i.mode, IntervalMode::Cycle { .. }));
+        self.adhoc_draft = Some(AdhocDraft {
+            use_cycle: false,
+            screen_id: self
+                .config
+                .default_screen_id()
+                .or_else(|| self.config.screens.first().map(|s| s.id))
+                .unwrap_or(1),
+            minutes: 45,
+            interval_id: cycle_interval.map(|i| i.id),
+            repeats: 4,
+        });
+    }
+
+    fn adhoc_window(&mut self, ctx: &egui::Context) {
+        let Some(draft) = &mut self.adhoc_draft else {
+            return;
+        };
+
+        let mut open = true;
+        let mut should_start = false;
+        let mut should_close = false;
+
+        egui::Window::new(tr(&self.bundle, "adhoc-title"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.radio_value(
+                        &mut draft.use_cycle,
+                        false,
+                        tr(&self.bundle, "adhoc-single"),
+                    );
+                    ui.radio_value(&mut draft.use_cycle, true, tr(&self.bundle, "adhoc-cycle"));
+                });
+
+                let cycle_intervals: Vec<&TimeInterval> = self
+                    .config
+                    .intervals()
+                    .iter()
+                    .filter(|i| matches!(i.mode, IntervalMode::Cycle { .. }))
+                    .collect();
+
+                if draft.use_cycle {
+                    if cycle_intervals.is_empty() {
+                        ui.small(tr(&self.bundle, "adhoc-no-cycles"));
+                    } else {
+                        ui.horizontal(|ui| {
+                            ui.label(tr(&self.bundle, "adhoc-cycle-source"));
+                            let selected = cycle_intervals
+                                .iter()
+                                .find(|i| Some(i.id) == draft.interval_id)
+                                .map(|i| i.name.clone())
+                                .unwrap_or_else(|| tr(&self.bundle, "select"));
+                            egui::ComboBox::from_id_salt("adhoc_cycle_interval")
+                                .selected_text(selected)
+                                .show_ui(ui, |ui| {
+                                    for interval in &cycle_intervals {
+                                        ui.selectable_value(
+                                            &mut draft.interval_id,
+                                            Some(interval.id),
+                                            &interval.name,
+                                        );
+                                    }
+                                });
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label(tr(&self.bundle, "adhoc-repeats"));
+                            ui.add(
+                                egui::DragValue::new(&mut draft.repeats)
+                                    .range(1..=20)
+                                    .speed(1.0),
+                            );
+                        });
+                    }
+                } else {
+                    ui.horizontal(|ui| {
+                        ui.label(tr(&self.bundle, "interval-step-screen-label"));
+                        let selected = self
+                            .config
+                            .screens
+                            .iter()
+                            .find(|s| s.id == draft.screen_id)
+                            .map(|s| s.title.clone())
+                            .unwrap_or_else(|| tr(&self.bundle, "select"));
+                        egui::ComboBox::from_id_salt("adhoc_screen")
+                            .selected_text(selected)
+                            .show_ui(ui, |ui| {
+                                for screen in &self.config.screens {
+                                    ui.selectable_value(
+                                        &mut draft.screen_id,
+                                        screen.id,
+                                        &screen.title,
+                                    );
+                                }
+                            });
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label(tr(&self.bundle, "interval-step-duration-label"));
+                        ui.add(
+                            egui::DragValue::new(&mut draft.minutes)
+                                .range(1..=600)
+                                .speed(1.0),
+                        );
+                    });
+                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button(tr(&self.bundle, "adhoc-start-btn")).clicked() {
+                        should_start = true;
+                    }
+                    if ui.button(tr(&self.bundle, "btn-cancel")).clicked() {
+                        should_close = true;
+                    }
+                });
+            });
+
+        if should_start {
+            let draft = draft.clone();
+            let session = self.adhoc_session(&draft, chrono::Local::now());
+
+            if let Some(session) = session {
+                self.timer_scheduler.start_adhoc(session);
+                self.adhoc_draft = None;
+            }
+        } else if should_close || !open {
+            self.adhoc_draft = None;
+        }
+    }
+
+    /// Собирает разовую сессию по параметрам диалога (или команды IPC)
+    pub fn adhoc_session(
+        &self,
+        draft: &AdhocDraft,
+        now: chrono::DateTime<Local>,
+    ) -> Option<AdhocSession> {
+        if draft.use_cycle {
+            self.config
+                .intervals()
+                .iter()
+                .find(|i| Some(i.id) == draft.interval_id)
+                .and_then(|interval| match &interval.mode {
+                    IntervalMode::Cycle { steps } => {
+                        let mut args = fluent_bundle::FluentArgs::new();
+                        args.set("interval", interval.name.as_str());
+                        args.set("repeats", draft.repeats);
+                        let name = tr_with_args(&self.bundle, "adhoc-cycle-name", Some(&args));
+                        Some(AdhocSession::cycle(name, steps.clone(), draft.repeats, now))
+                    }
+                    IntervalMode::Static { .. } => None,
+                })
+        } else {
+            self.config
+                .screens
+                .iter()
+                .find(|s| s.id == draft.screen_id)
+                .map(|screen| {
+                    let mut args = fluent_bundle::FluentArgs::new();
+                    args.set("screen", screen.title.as_str());
+                    args.set("minutes", draft.minutes);
+                    let name = tr_with_args(&self.bundle, "adhoc-single-name", Some(&args));
+                    AdhocSession::single(name, screen.id, draft.minutes, now)
+                })
+        }
+    }
+
+    fn cycle_controls(&mut self, ui: &mut egui::Ui) {
+        let button_color = egui::Color32::from_rgba_unmultiplied(255, 255, 255, 40);
+        let extend_minutes = self.config.system_settings.extend_step_minutes;
+
+        let mut args = fluent_bundle::FluentArgs::new();
+        args.set("minutes", extend_minutes);
+        let extend_label = tr_with_args(&self.bundle, "timer-extend", Some(&args));
+
+        // Кнопки центрируются по ширине: считаем отступ от примерной ширины ряда
+        ui.horizontal(|ui| {
+            ui.add_space((ui.available_width() - 200.0).max(0.0) / 2.0);
+
+            if ui
+                .add(
+                    egui::Button::new("↺")
+                        .fill(button_color)
+                        .stroke(egui::Stroke::NONE),
+                )
+                .on_hover_text(tr(&self.bundle, "timer-restart"))
+                .clicked()
+            {
+                self.timer_scheduler.restart_cycle(&self.config);
+            }
+            if ui
+                .add(
+                    egui::Button::new(extend_label)
+                        .fill(button_color)
+                        .stroke(egui::Stroke::NONE),
+                )
+                .on_hover_text(tr(&self.bundle, "timer-extend-tooltip"))
+                .clicked()
+            {
+                self.timer_scheduler
+                    .extend_step(&self.config, extend_minutes);
+            }
+            if ui
+                .add(
+                    egui::Button::new("⏭")
+                        .fill(button_color)
+                        .stroke(egui::Stroke::NONE),
+                )
+                .on_hover_text(tr(&self.bundle, "timer-skip"))
+                .clicked()
+            {
+                self.timer_scheduler.skip_step(&self.config);
+            }
+        });
+    }
+
     fn settings_window(&mut self, ctx: &egui::Context) {
         if self.show_settings {
             let title = tr(&self.bundle, "settings-title");
@@ -329,18 +1337,34 @@ impl AppState {
                                     matches!(self.settings_tab, SettingsTab::System),
                                     tr(&self.bundle, "tab-system"),
                                 );
+                                let sharing_tab = ui.selectable_label(
+                                    matches!(self.settings_tab, SettingsTab::Sharing),
+                                    tr(&self.bundle, "tab-sharing"),
+                                );
                                 if timers_tab.clicked() {
                                     self.settings_tab = SettingsTab::Timers;
                                 }
                                 if system_tab.clicked() {
                                     self.settings_tab = SettingsTab::System;
                                 }
+                                let stats_tab = ui.selectable_label(
+                                    matches!(self.settings_tab, SettingsTab::Stats),
+                                    tr(&self.bundle, "tab-stats"),
+                                );
+                                if sharing_tab.clicked() {
+                                    self.settings_tab = SettingsTab::Sharing;
+                                }
+                                if stats_tab.clicked() {
+                                    self.settings_tab = SettingsTab::Stats;
+                                }
                             });
                             ui.separator();
 
                             egui::ScrollArea::vertical().show(ui, |ui| match self.settings_tab {
                                 SettingsTab::Timers => self.ui_tab_timers(ui),
                                 SettingsTab::System => self.ui_tab_system(ui),
+                                SettingsTab::Sharing => self.ui_tab_sharing(ui),
+                                SettingsTab::Stats => self.ui_tab_stats(ui),
                             });
                         });
 
@@ -415,6 +1439,8 @@ impl AppState {
                     b: 200,
                     a: 255,
                 },
+                sound: None,
+                notify: true,
             };
             self.editing_screen = Some(EditingScreen {
                 screen: new_screen,
@@ -442,6 +1468,13 @@ impl AppState {
 
         ui.separator();
 
+        // Профили: у каждого свои интервалы и экран по умолчанию
+        if self.ui_profiles(ui) {
+            self.save_config();
+        }
+
+        ui.separator();
+
         // Управление временными интервалами
         ui.heading(tr(&self.bundle, "intervals-title"));
         ui.small(tr(&self.bundle, "intervals-description"));
@@ -449,12 +1482,13 @@ impl AppState {
         let mut interval_changed = false;
         let mut to_delete_interval: Option<usize> = None;
         let mut to_edit_interval: Option<usize> = None;
+        let issues = validate_profiles(&self.config);
 
         ui.group(|ui| {
-            if self.config.intervals.is_empty() {
+            if self.config.intervals().is_empty() {
                 ui.label(tr(&self.bundle, "intervals-none"));
             } else {
-                for (idx, interval) in self.config.intervals.iter().enumerate() {
+                for (idx, interval) in self.config.intervals().iter().enumerate() {
                     ui.group(|ui| {
                         ui.horizontal(|ui| {
                             ui.strong(&interval.name);
@@ -465,20 +1499,53 @@ impl AppState {
                                 interval.end.hour,
                                 interval.end.minute
                             ));
-
-                            // Показываем режим интервала
-                            match &interval.mode {
-                                IntervalMode::Static { screen_id } => {
-                                    if let Some(screen) =
-                                        self.config.screens.iter().find(|s| s.id == *screen_id)
-                                    {
+                            if interval.crosses_midnight() {
+                                ui.small(tr(&self.bundle, "interval-crosses-midnight"));
+                            }
+                            if interval.recurrence != Recurrence::Daily {
+                                ui.small(recurrence_label(&self.bundle, &interval.recurrence));
+                            }
+                            if let Some(source) = &interval.source {
+                                let mut args = fluent_bundle::FluentArgs::new();
+                                args.set("source", source.as_str());
+                                ui.small("📅").on_hover_text(tr_with_args(
+                                    &self.bundle,
+                                    "interval-imported",
+                                    Some(&args),
+                                ));
+                            }
+                            let own_issues: Vec<&ValidationIssue> = issues
+                                .iter()
+                                .filter(|issue| issue.interval_ids().contains(&interval.id))
+                                .collect();
+                            if let Some(severity) = own_issues.iter().map(|i| i.severity()).max() {
+                                let (color, icon) = severity_style(severity);
+                                let hint: Vec<String> = own_issues
+                                    .iter()
+                                    .map(|issue| issue.message(&self.bundle))
+                                    .collect();
+                                ui.colored_label(color, icon).on_hover_text(hint.join("\n"));
+                            }
+
+                            // Показываем режим интервала
+                            match &interval.mode {
+                                IntervalMode::Static { screen_id } => {
+                                    if let Some(screen) =
+                                        self.config.screens.iter().find(|s| s.id == *screen_id)
+                                    {
                                         ui.colored_label(screen.color.to_egui(), "●");
                                         ui.label(&screen.title);
                                     }
-                                    ui.small("(статичный)");
+                                    ui.small(tr(&self.bundle, "interval-static-mode"));
                                 }
                                 IntervalMode::Cycle { steps } => {
-                                    ui.small(format!("(цикл из {} шагов)", steps.len()));
+                                    let mut args = fluent_bundle::FluentArgs::new();
+                                    args.set("steps", steps.len());
+                                    ui.small(tr_with_args(
+                                        &self.bundle,
+                                        "interval-cycle-mode",
+                                        Some(&args),
+                                    ));
                                 }
                             }
 
@@ -510,7 +1577,7 @@ impl AppState {
 
         // Кнопка добавления нового интервала
         if ui.button(tr(&self.bundle, "intervals-create")).clicked() {
-            let start_time = if let Some(last) = self.config.intervals.last() {
+            let start_time = if let Some(last) = self.config.intervals().last() {
                 last.end
             } else {
                 TimeOfDay { hour: 9, minute: 0 }
@@ -521,12 +1588,15 @@ impl AppState {
                 name: tr(&self.bundle, "interval-new-title"),
                 start: start_time,
                 end: TimeOfDay {
-                    hour: start_time.hour + 1,
+                    hour: (start_time.hour + 1) % 24,
                     minute: start_time.minute,
                 },
                 mode: IntervalMode::Static {
                     screen_id: self.config.screens.first().map(|s| s.id).unwrap_or(1),
                 },
+                recurrence: Recurrence::Daily,
+                warning_minutes: None,
+                source: None,
             };
             self.editing_interval = Some(EditingInterval {
                 interval: new_interval,
@@ -537,29 +1607,33 @@ impl AppState {
 
         // Обработка операций с интервалами
         if let Some(idx) = to_delete_interval {
-            self.config.intervals.remove(idx);
+            self.config.active_profile_mut().intervals.remove(idx);
             interval_changed = true;
         }
 
-        if let Some(idx) = to_edit_interval {
-            if let Some(interval) = self.config.intervals.get(idx).cloned() {
-                self.editing_interval = Some(EditingInterval {
-                    interval,
-                    is_new: false,
-                });
-            }
-        }
-
         // Валидация и предупреждения
         ui.separator();
         ui.heading(tr(&self.bundle, "validation-title"));
 
-        let validation_errors = validate_intervals(&self.config.intervals);
-        if !validation_errors.is_empty() {
+        if !issues.is_empty() {
             ui.group(|ui| {
                 ui.strong(tr(&self.bundle, "validation-problems-found"));
-                for error in &validation_errors {
-                    ui.small(error);
+                for issue in &issues {
+                    // Исправить можно только интервал активного профиля
+                    let editable = issue
+                        .interval_ids()
+                        .first()
+                        .and_then(|id| self.config.intervals().iter().position(|i| i.id == *id));
+                    ui.horizontal(|ui| {
+                        issue_label(ui, &self.bundle, issue);
+                        if let Some(idx) = editable
+                            && ui
+                                .small_button(tr(&self.bundle, "validation-edit"))
+                                .clicked()
+                        {
+                            to_edit_interval = Some(idx);
+                        }
+                    });
                 }
             });
         } else {
@@ -569,11 +1643,21 @@ impl AppState {
             });
         }
 
+        // Редактор открывается из списка интервалов или из списка проблем
+        if let Some(idx) = to_edit_interval
+            && let Some(interval) = self.config.intervals().get(idx).cloned()
+        {
+            self.editing_interval = Some(EditingInterval {
+                interval,
+                is_new: false,
+            });
+        }
+
         // Показываем расписание переходов на день
         ui.separator();
         ui.heading(tr(&self.bundle, "schedule-title"));
 
-        let transitions = get_daily_transitions(&self.config);
+        let transitions = get_daily_transitions(&self.config, Local::now().date_naive());
         if transitions.is_empty() {
             ui.small(tr(&self.bundle, "schedule-none"));
         } else {
@@ -581,19 +1665,18 @@ impl AppState {
                 egui::ScrollArea::vertical()
                     .max_height(150.0)
                     .show(ui, |ui| {
-                        for (time_min, description, transition_type) in transitions {
-                            let hour = time_min / 60;
-                            let minute = time_min % 60;
-                            let icon = match transition_type.as_str() {
-                                "start" => "▶",
-                                "end" => "⏸",
-                                "step" => "🔄",
-                                _ => "•",
+                        for transition in transitions {
+                            let hour = transition.minute / 60;
+                            let minute = transition.minute % 60;
+                            let icon = match transition.kind {
+                                TransitionKind::IntervalStart => "▶",
+                                TransitionKind::IntervalEnd => "⏸",
+                                TransitionKind::Step => "🔄",
                             };
                             ui.horizontal(|ui| {
                                 ui.monospace(format!("{:02}:{:02}", hour, minute));
                                 ui.label(icon);
-                                ui.small(description);
+                                ui.small(transition.description(&self.bundle));
                             });
                         }
                     });
@@ -602,7 +1685,7 @@ impl AppState {
 
         // Сохранение изменений
         if screen_changed || interval_changed {
-            let _ = crate::config::save_config(&self.config_path, &self.config);
+            self.save_config();
         }
 
         // Окна редактирования
@@ -610,8 +1693,137 @@ impl AppState {
         self.ui_interval_editor(ui.ctx());
     }
 
+    /// Выбор и настройка профиля. Возвращает `true`, если профиль изменился.
+    fn ui_profiles(&mut self, ui: &mut egui::Ui) -> bool {
+        ui.heading(tr(&self.bundle, "profiles-title"));
+        ui.small(tr(&self.bundle, "profiles-description"));
+
+        let mut changed = false;
+        let mut switch_to: Option<u32> = None;
+        let mut add_profile = false;
+        let mut duplicate_profile = false;
+        let mut delete_profile = false;
+
+        ui.group(|ui| {
+            ui.horizontal(|ui| {
+                ui.label(tr(&self.bundle, "profile-active"));
+                egui::ComboBox::from_id_salt("active_profile")
+                    .selected_text(&self.config.active_profile().name)
+                    .show_ui(ui, |ui| {
+                        for profile in &self.config.profiles {
+                            if ui
+                                .selectable_label(
+                                    profile.id == self.config.active_profile_id,
+                                    &profile.name,
+                                )
+                                .clicked()
+                            {
+                                switch_to = Some(profile.id);
+                            }
+                        }
+                    });
+
+                add_profile = ui.button(tr(&self.bundle, "profile-add")).clicked();
+                duplicate_profile = ui.button(tr(&self.bundle, "profile-duplicate")).clicked();
+                delete_profile = ui
+                    .add_enabled(
+                        self.config.profiles.len() > 1,
+                        egui::Button::new(tr(&self.bundle, "profile-delete")),
+                    )
+                    .clicked();
+            });
+
+            let screens: Vec<(u32, String)> = self
+                .config
+                .screens
+                .iter()
+                .map(|s| (s.id, s.title.clone()))
+                .collect();
+            let profile = self.config.active_profile_mut();
+
+            ui.horizontal(|ui| {
+                ui.label(tr(&self.bundle, "profile-name"));
+                changed |= ui.text_edit_singleline(&mut profile.name).changed();
+            });
+
+            ui.horizontal(|ui| {
+                ui.label(tr(&self.bundle, "profile-default-screen"));
+                let selected_title = profile
+                    .default_screen_id
+                    .and_then(|id| screens.iter().find(|(screen_id, _)| *screen_id == id))
+                    .map(|(_, title)| title.clone())
+                    .unwrap_or_else(|| tr(&self.bundle, "profile-default-screen-first"));
+                egui::ComboBox::from_id_salt("profile_default_screen")
+                    .selected_text(selected_title)
+                    .show_ui(ui, |ui| {
+                        changed |= ui
+                            .selectable_value(
+                                &mut profile.default_screen_id,
+                                None,
+                                tr(&self.bundle, "profile-default-screen-first"),
+                            )
+                            .changed();
+                        for (id, title) in &screens {
+                            changed |= ui
+                                .selectable_value(&mut profile.default_screen_id, Some(*id), title)
+                                .changed();
+                        }
+                    });
+            });
+
+            ui.label(tr(&self.bundle, "profile-auto-days"));
+            changed |= weekday_checkboxes(ui, &self.bundle, &mut profile.auto_days);
+        });
+
+        if add_profile || duplicate_profile {
+            let id = self.config.profiles.iter().map(|p| p.id).max().unwrap_or(0) + 1;
+            let mut profile = if duplicate_profile {
+                let mut copy = self.config.active_profile().clone();
+                copy.auto_days.clear();
+                // id интервалов уникальны во всём файле
+                for interval in &mut copy.intervals {
+                    interval.id = self.next_interval_id;
+                    self.next_interval_id += 1;
+                }
+                copy
+            } else {
+                Profile::new(id, String::new(), self.config.default_screen_id())
+            };
+            profile.id = id;
+            profile.name = tr(&self.bundle, "profile-new-name");
+            self.config.profiles.push(profile);
+            switch_to = Some(id);
+            changed = true;
+        }
+
+        if delete_profile && self.config.profiles.len() > 1 {
+            let id = self.config.active_profile_id;
+            self.config.profiles.retain(|p| p.id != id);
+            switch_to = self.config.profiles.first().map(|p| p.id);
+            changed = true;
+        }
+
+        if let Some(id) = switch_to {
+            changed |= self.config.set_active_profile(id);
+            self.timer_scheduler.update(&self.config);
+        }
+        changed
+    }
+
     fn ui_interval_editor(&mut self, ctx: &egui::Context) {
         if let Some(editing) = &mut self.editing_interval {
+            // Проверяем профиль так, как если бы правки уже были сохранены
+            let mut intervals = self.config.intervals().to_vec();
+            match intervals.iter_mut().find(|i| i.id == editing.interval.id) {
+                Some(interval) => *interval = editing.interval.clone(),
+                None => intervals.push(editing.interval.clone()),
+            }
+            let interval_id = editing.interval.id;
+            let issues: Vec<ValidationIssue> = validate_intervals(&intervals, &self.config.screens)
+                .into_iter()
+                .filter(|issue| issue.interval_ids().contains(&interval_id))
+                .collect();
+
             let title = if editing.is_new {
                 tr(&self.bundle, "interval-editor-new")
             } else {
@@ -637,9 +1849,15 @@ impl AppState {
                         ui.label(tr(&self.bundle, "interval-name-field"));
                         ui.text_edit_singleline(&mut editing.interval.name);
                     });
+                    if editing.interval.source.is_some() {
+                        ui.colored_label(
+                            egui::Color32::from_rgb(200, 150, 50),
+                            tr(&self.bundle, "interval-imported-edit-hint"),
+                        );
+                    }
 
                     // Время работы интервала
-                    ui.group(|ui| {
+                    let time_group = ui.group(|ui| {
                         ui.strong(tr(&self.bundle, "interval-time-work"));
                         ui.horizontal(|ui| {
                             ui.label(tr(&self.bundle, "interval-time-from"));
@@ -670,14 +1888,8 @@ impl AppState {
                             );
                         });
 
-                        // Показать длительность
-                        let duration_minutes = if editing.interval.end.to_minutes()
-                            > editing.interval.start.to_minutes()
-                        {
-                            editing.interval.end.to_minutes() - editing.interval.start.to_minutes()
-                        } else {
-                            0
-                        };
+                        // Показать длительность (конец раньше начала - интервал через полночь)
+                        let duration_minutes = editing.interval.duration_minutes();
                         let hours = duration_minutes / 60;
                         let minutes = duration_minutes % 60;
                         let mut args = fluent_bundle::FluentArgs::new();
@@ -688,6 +1900,48 @@ impl AppState {
                             "interval-duration-format",
                             Some(&args),
                         ));
+                        if editing.interval.crosses_midnight() {
+                            ui.small(tr(&self.bundle, "interval-crosses-midnight-hint"));
+                        }
+                    });
+                    mark_field(
+                        ui,
+                        time_group.response.rect,
+                        &issues,
+                        interval_id,
+                        IssueField::Time,
+                    );
+
+                    // Правило повторения по дням
+                    let recurrence_group = ui.group(|ui| {
+                        ui.strong(tr(&self.bundle, "interval-repeat"));
+                        recurrence_editor(ui, &self.bundle, &mut editing.interval.recurrence);
+                    });
+                    mark_field(
+                        ui,
+                        recurrence_group.response.rect,
+                        &issues,
+                        interval_id,
+                        IssueField::Recurrence,
+                    );
+
+                    // Собственное время предупреждения перед переходами
+                    ui.group(|ui| {
+                        let mut own_warning = editing.interval.warning_minutes.is_some();
+                        if ui
+                            .checkbox(&mut own_warning, tr(&self.bundle, "interval-warning-own"))
+                            .on_hover_text(tr(&self.bundle, "interval-warning-own-tooltip"))
+                            .changed()
+                        {
+                            editing.interval.warning_minutes = own_warning
+                                .then_some(self.config.system_settings.warnings.lead_minutes);
+                        }
+                        if let Some(minutes) = &mut editing.interval.warning_minutes {
+                            ui.horizontal(|ui| {
+                                ui.label(tr(&self.bundle, "warning-lead"));
+                                ui.add(egui::DragValue::new(minutes).range(0..=60).speed(1.0));
+                            });
+                        }
                     });
 
                     ui.separator();
@@ -734,7 +1988,7 @@ impl AppState {
                                     tr(&self.bundle, "interval-screen-choose-placeholder")
                                 });
 
-                            egui::ComboBox::from_id_salt("static_screen_combo")
+                            let combo = egui::ComboBox::from_id_salt("static_screen_combo")
                                 .selected_text(&screen_name)
                                 .width(200.0)
                                 .show_ui(ui, |ui| {
@@ -742,6 +1996,13 @@ impl AppState {
                                         ui.selectable_value(screen_id, screen.id, &screen.title);
                                     }
                                 });
+                            mark_field(
+                                ui,
+                                combo.response.rect,
+                                &issues,
+                                interval_id,
+                                IssueField::Screen,
+                            );
                         }
                         IntervalMode::Cycle { steps } => {
                             ui.label(tr(&self.bundle, "interval-steps-configure"));
@@ -749,7 +2010,7 @@ impl AppState {
                             let mut to_remove: Option<usize> = None;
 
                             for (idx, step) in steps.iter_mut().enumerate() {
-                                ui.group(|ui| {
+                                let step_group = ui.group(|ui| {
                                     ui.horizontal(|ui| {
                                         let mut args = fluent_bundle::FluentArgs::new();
                                         args.set("number", idx + 1);
@@ -804,17 +2065,29 @@ impl AppState {
                                             egui::DragValue::new(&mut step.duration_minutes)
                                                 .range(1..=480)
                                                 .speed(1.0)
-                                                .suffix(" мин"),
+                                                .suffix(format!(
+                                                    " {}",
+                                                    tr(&self.bundle, "interval-step-minutes")
+                                                )),
                                         );
                                     });
                                 });
+                                mark_field(
+                                    ui,
+                                    step_group.response.rect,
+                                    &issues,
+                                    interval_id,
+                                    IssueField::Step(idx),
+                                );
                             }
 
                             if let Some(idx) = to_remove {
                                 steps.remove(idx);
                             }
 
-                            if ui.button(tr(&self.bundle, "interval-step-add")).clicked() {
+                            let add_step = ui.button(tr(&self.bundle, "interval-step-add"));
+                            mark_field(ui, add_step.rect, &issues, interval_id, IssueField::Steps);
+                            if add_step.clicked() {
                                 steps.push(CycleStep {
                                     screen_id: self
                                         .config
@@ -842,6 +2115,11 @@ impl AppState {
 
                     ui.separator();
 
+                    // Проблемы, которые останутся после сохранения
+                    for issue in &issues {
+                        issue_label(ui, &self.bundle, issue);
+                    }
+
                     // Кнопки управления
                     ui.horizontal(|ui| {
                         if ui.button(tr(&self.bundle, "btn-save")).clicked() {
@@ -854,19 +2132,24 @@ impl AppState {
                 });
 
             if should_save {
+                // Изменённый вручную интервал больше не заменяется повторным импортом
+                editing.interval.source = None;
                 if editing.is_new {
-                    self.config.intervals.push(editing.interval.clone());
+                    self.config
+                        .active_profile_mut()
+                        .intervals
+                        .push(editing.interval.clone());
                 } else {
                     if let Some(idx) = self
                         .config
-                        .intervals
+                        .intervals()
                         .iter()
                         .position(|i| i.id == editing.interval.id)
                     {
-                        self.config.intervals[idx] = editing.interval.clone();
+                        self.config.active_profile_mut().intervals[idx] = editing.interval.clone();
                     }
                 }
-                let _ = crate::config::save_config(&self.config_path, &self.config);
+                self.save_config();
                 self.editing_interval = None;
             } else if should_close || !open {
                 self.editing_interval = None;
@@ -920,6 +2203,16 @@ impl AppState {
                         }
                     });
 
+                    // Звук при переходе на экран
+                    ui.horizontal(|ui| {
+                        ui.label(tr(&self.bundle, "field-sound"));
+                        path_edit(ui, &mut editing.screen.sound);
+                    })
+                    .response
+                    .on_hover_text(tr(&self.bundle, "field-sound-tooltip"));
+
+                    ui.checkbox(&mut editing.screen.notify, tr(&self.bundle, "field-notify"));
+
                     ui.separator();
 
                     // Предварительный просмотр
@@ -964,7 +2257,7 @@ impl AppState {
                         self.config.screens[idx] = editing.screen.clone();
                     }
                 }
-                let _ = crate::config::save_config(&self.config_path, &self.config);
+                self.save_config();
                 self.editing_screen = None;
             } else if should_close || !open {
                 self.editing_screen = None;
@@ -980,27 +2273,93 @@ impl AppState {
             ui.strong(tr(&self.bundle, "system-language"));
             ui.small(tr(&self.bundle, "system-language-desc"));
 
-            let mut selected = self.config.language.to_string();
-            let current_text = match selected.as_str() {
-                "ru-RU" | "ru" => tr(&self.bundle, "language-russian"),
-                _ => "English".to_string(),
-            };
+            // Выбранный язык может быть недоступен (например, определён по системе):
+            // в списке отмечается локаль, которой он показан
+            let shown = fallback_chain(&self.config.language, &self.locales)
+                .into_iter()
+                .next()
+                .and_then(|id| self.locales.iter().find(|locale| locale.id == id))
+                .cloned();
+            let mut selected = shown.as_ref().map(|locale| locale.id.clone());
 
             ui.horizontal(|ui| {
                 egui::ComboBox::from_id_salt("system_language")
-                    .selected_text(current_text)
+                    .selected_text(
+                        shown
+                            .as_ref()
+                            .map(|locale| locale.name.as_str())
+                            .unwrap_or(""),
+                    )
                     .show_ui(ui, |ui| {
-                        ui.selectable_value(&mut selected, "en-US".to_owned(), "English");
-                        ui.selectable_value(
-                            &mut selected,
-                            "ru-RU".to_owned(),
-                            tr(&self.bundle, "language-russian"),
-                        );
+                        for locale in &self.locales {
+                            ui.selectable_value(
+                                &mut selected,
+                                Some(locale.id.clone()),
+                                &locale.name,
+                            )
+                            .on_hover_text(locale.id.to_string());
+                        }
                     });
+                if ui
+                    .small_button("↻")
+                    .on_hover_text(tr(&self.bundle, "system-language-refresh"))
+                    .clicked()
+                {
+                    self.locales = available_locales();
+                    self.bundle = make_bundle(&self.config.language, &self.locales);
+                }
             });
 
-            if selected != self.config.language.to_string() {
-                set_language(self, &selected);
+            if let Some(shown) = &shown
+                && shown.id.language != self.config.language.language
+            {
+                let mut args = fluent_bundle::FluentArgs::new();
+                args.set("requested", self.config.language.to_string());
+                args.set("language", shown.name.as_str());
+                ui.colored_label(
+                    egui::Color32::from_rgb(200, 150, 50),
+                    tr_with_args(&self.bundle, "system-language-fallback", Some(&args)),
+                );
+            }
+
+            if let Some(dir) = user_locales_dir() {
+                let mut args = fluent_bundle::FluentArgs::new();
+                args.set("dir", dir.display().to_string());
+                ui.small(tr_with_args(
+                    &self.bundle,
+                    "system-language-dir",
+                    Some(&args),
+                ));
+            }
+
+            let errors = reported_errors();
+            if !errors.is_empty() {
+                let mut args = fluent_bundle::FluentArgs::new();
+                args.set("count", errors.len());
+                let header = egui::RichText::new(tr_with_args(
+                    &self.bundle,
+                    "system-language-errors",
+                    Some(&args),
+                ))
+                .color(egui::Color32::from_rgb(200, 150, 50));
+                // Заголовок меняется с числом ошибок, поэтому id задан явно
+                egui::CollapsingHeader::new(header)
+                    .id_salt("locale_errors")
+                    .show(ui, |ui| {
+                        egui::ScrollArea::vertical()
+                            .max_height(120.0)
+                            .show(ui, |ui| {
+                                for error in &errors {
+                                    ui.small(error.to_string());
+                                }
+                            });
+                    });
+            }
+
+            if let Some(id) = selected
+                && shown.as_ref().is_none_or(|shown| shown.id != id)
+            {
+                set_language(self, &id.to_string());
                 settings_changed = true;
             }
         });
@@ -1012,12 +2371,64 @@ impl AppState {
             ui.strong(tr(&self.bundle, "system-autostart"));
             ui.small(tr(&self.bundle, "system-autostart-desc"));
 
-            if ui
-                .checkbox(&mut self.config.system_settings.autostart, "")
-                .changed()
-            {
+            if !autostart::is_supported() {
+                ui.label(tr(&self.bundle, "autostart-unsupported"));
+                return;
+            }
+
+            let settings = &mut self.config.system_settings;
+            let mut autostart_changed = ui.checkbox(&mut settings.autostart, "").changed();
+
+            ui.add_enabled_ui(settings.autostart, |ui| {
+                for (method, key) in [
+                    (AutostartMethod::Xdg, "autostart-method-xdg"),
+                    (AutostartMethod::Systemd, "autostart-method-systemd"),
+                ] {
+                    autostart_changed |= ui
+                        .radio_value(
+                            &mut settings.autostart_method,
+                            method,
+                            tr(&self.bundle, key),
+                        )
+                        .changed();
+                }
+            });
+
+            if autostart_changed {
+                match autostart::apply(settings.autostart, settings.autostart_method) {
+                    Ok(()) => self.autostart_notice = None,
+                    Err(err) => {
+                        // Не сохраняем состояние, которое не удалось применить
+                        settings.autostart = !settings.autostart;
+                        self.autostart_notice = Some(Err(err.to_string()));
+                    }
+                }
                 settings_changed = true;
             }
+
+            match &self.autostart_notice {
+                Some(Ok(AutostartStatus::Stale { .. })) => {
+                    ui.small(tr(&self.bundle, "autostart-repaired"));
+                }
+                Some(Ok(AutostartStatus::Foreign { exec })) => {
+                    let mut args = fluent_bundle::FluentArgs::new();
+                    args.set("path", exec.display().to_string());
+                    ui.colored_label(
+                        egui::Color32::from_rgb(200, 150, 50),
+                        tr_with_args(&self.bundle, "autostart-foreign", Some(&args)),
+                    );
+                }
+                Some(Ok(_)) => {}
+                Some(Err(error)) => {
+                    let mut args = fluent_bundle::FluentArgs::new();
+                    args.set("error", error.as_str());
+                    ui.colored_label(
+                        egui::Color32::from_rgb(200, 80, 80),
+                        tr_with_args(&self.bundle, "autostart-error", Some(&args)),
+                    );
+                }
+                None => {}
+            }
         });
 
         ui.separator();
@@ -1033,6 +2444,215 @@ impl AppState {
             {
                 settings_changed = true;
             }
+
+            ui.add_enabled_ui(self.config.system_settings.sound_notifications, |ui| {
+                let sound = &mut self.config.system_settings.sound;
+
+                ui.horizontal(|ui| {
+                    ui.label(tr(&self.bundle, "sound-volume"));
+                    if ui
+                        .add(egui::Slider::new(&mut sound.volume, 0.0..=1.0).show_value(false))
+                        .changed()
+                    {
+                        settings_changed = true;
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label(tr(&self.bundle, "sound-chime"));
+                    egui::ComboBox::from_id_salt("sound_chime")
+                        .selected_text(tr(&self.bundle, chime_key(sound.chime)))
+                        .show_ui(ui, |ui| {
+                            for chime in [Chime::Soft, Chime::Bell, Chime::Rising] {
+                                if ui
+                                    .selectable_value(
+                                        &mut sound.chime,
+                                        chime,
+                                        tr(&self.bundle, chime_key(chime)),
+                                    )
+                                    .changed()
+                                {
+                                    settings_changed = true;
+                                }
+                            }
+                        });
+                    if ui.button(tr(&self.bundle, "sound-test")).clicked() {
+                        let _ = self
+                            .sound_player
+                            .play(&Sound::Chime(sound.chime), sound.volume);
+                    }
+                });
+
+                ui.small(tr(&self.bundle, "sound-files-desc"));
+                egui::Grid::new("sound_files")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        for (key, path) in [
+                            ("sound-interval-start", &mut sound.interval_start),
+                            ("sound-interval-end", &mut sound.interval_end),
+                            ("sound-step", &mut sound.step),
+                        ] {
+                            ui.label(tr(&self.bundle, key));
+                            if path_edit(ui, path) {
+                                settings_changed = true;
+                            }
+                            ui.end_row();
+                        }
+                    });
+
+                if !self.sound_player.is_audible() {
+                    ui.colored_label(
+                        egui::Color32::from_rgb(200, 150, 50),
+                        tr(&self.bundle, "sound-no-device"),
+                    );
+                }
+                if let Some(error) = &self.sound_player.last_error {
+                    let mut args = fluent_bundle::FluentArgs::new();
+                    args.set("error", error.as_str());
+                    ui.colored_label(
+                        egui::Color32::from_rgb(200, 80, 80),
+                        tr_with_args(&self.bundle, "sound-error", Some(&args)),
+                    );
+                }
+            });
+        });
+
+        ui.separator();
+
+        // Уведомления рабочего стола
+        ui.group(|ui| {
+            ui.strong(tr(&self.bundle, "system-notifications"));
+            ui.small(tr(&self.bundle, "system-notifications-desc"));
+
+            if ui
+                .checkbox(&mut self.config.system_settings.desktop_notifications, "")
+                .changed()
+            {
+                settings_changed = true;
+            }
+
+            if let Some(error) = self.notifications.last_error() {
+                let mut args = fluent_bundle::FluentArgs::new();
+                args.set("error", error);
+                ui.colored_label(
+                    egui::Color32::from_rgb(200, 80, 80),
+                    tr_with_args(&self.bundle, "notifications-error", Some(&args)),
+                );
+            }
+        });
+
+        ui.separator();
+
+        // Предупреждения перед переходами
+        ui.group(|ui| {
+            ui.strong(tr(&self.bundle, "system-warnings"));
+            ui.small(tr(&self.bundle, "system-warnings-desc"));
+
+            let warnings = &mut self.config.system_settings.warnings;
+            settings_changed |= ui.checkbox(&mut warnings.enabled, "").changed();
+
+            ui.add_enabled_ui(warnings.enabled, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(tr(&self.bundle, "warning-lead"));
+                    settings_changed |= ui
+                        .add(
+                            egui::DragValue::new(&mut warnings.lead_minutes)
+                                .range(1..=60)
+                                .speed(1.0),
+                        )
+                        .changed();
+                });
+                for (key, enabled) in [
+                    ("warning-banner-option", &mut warnings.banner),
+                    ("warning-sound-option", &mut warnings.sound),
+                    ("warning-notification-option", &mut warnings.notification),
+                    ("warning-pulse-option", &mut warnings.pulse),
+                ] {
+                    settings_changed |= ui.checkbox(enabled, tr(&self.bundle, key)).changed();
+                }
+            });
+        });
+
+        ui.separator();
+
+        // Поведение паузы
+        ui.group(|ui| {
+            ui.strong(tr(&self.bundle, "system-pause-mode"));
+            ui.small(tr(&self.bundle, "system-pause-mode-desc"));
+
+            let pause_mode = &mut self.config.system_settings.pause_mode;
+            if ui
+                .radio_value(
+                    pause_mode,
+                    PauseMode::ShiftSteps,
+                    tr(&self.bundle, "pause-mode-shift"),
+                )
+                .changed()
+            {
+                settings_changed = true;
+            }
+            if ui
+                .radio_value(
+                    pause_mode,
+                    PauseMode::ExtendInterval,
+                    tr(&self.bundle, "pause-mode-extend"),
+                )
+                .changed()
+            {
+                settings_changed = true;
+            }
+        });
+
+        ui.separator();
+
+        // Продление шага цикла
+        ui.group(|ui| {
+            ui.strong(tr(&self.bundle, "system-extend-minutes"));
+            ui.small(tr(&self.bundle, "system-extend-minutes-desc"));
+
+            if ui
+                .add(
+                    egui::DragValue::new(&mut self.config.system_settings.extend_step_minutes)
+                        .range(1..=120)
+                        .speed(1.0),
+                )
+                .changed()
+            {
+                settings_changed = true;
+            }
+        });
+
+        ui.separator();
+
+        // Файл конфигурации
+        ui.group(|ui| {
+            ui.strong(tr(&self.bundle, "system-config-file"));
+            ui.small(tr(&self.bundle, "system-config-file-desc"));
+            ui.monospace(self.config_path.display().to_string());
+
+            match &self.config_issue {
+                Some(ConfigIssue::ParseError(error)) => {
+                    ui.colored_label(
+                        egui::Color32::from_rgb(200, 80, 80),
+                        tr(&self.bundle, "config-parse-error"),
+                    );
+                    ui.small(error);
+                }
+                Some(ConfigIssue::Invalid(problems)) => {
+                    ui.colored_label(
+                        egui::Color32::from_rgb(200, 150, 50),
+                        tr(&self.bundle, "config-invalid"),
+                    );
+                    for problem in problems {
+                        ui.small(format!("• {}", problem.message(&self.bundle)));
+                    }
+                }
+                None => {}
+            }
+
+            if ui.button(tr(&self.bundle, "config-reload")).clicked() {
+                let _ = self.reload_config();
+            }
         });
 
         ui.separator();
@@ -1047,33 +2667,33 @@ impl AppState {
 
             if ui.checkbox(&mut remember_position, "").changed() {
                 if remember_position && !has_position {
-                    // Запомнить текущую позицию (пока что заглушка)
-                    self.config.system_settings.window_position =
-                        Some(crate::config::WindowPosition { x: 100.0, y: 100.0 });
+                    // Запоминаем текущее положение основного окна, а не окна настроек
+                    let (outer_rect, inner_rect, monitor_size) = main_window_geometry(ui.ctx());
+                    let origin = outer_rect.map(|rect| rect.min).unwrap_or_default();
+                    self.config.system_settings.window_position = Some(WindowPosition {
+                        x: origin.x,
+                        y: origin.y,
+                        size: inner_rect.map(|rect| rect.size().into()),
+                        monitor_size: monitor_size.map(Into::into),
+                    });
+                    self.window_tracking.checked = true;
                 } else if !remember_position && has_position {
                     // Забыть позицию
                     self.config.system_settings.window_position = None;
+                    self.window_tracking.moved_at = None;
                 }
                 settings_changed = true;
             }
 
-            if let Some(pos) = &mut self.config.system_settings.window_position {
-                ui.horizontal(|ui| {
-                    ui.label("X:");
-                    if ui
-                        .add(egui::DragValue::new(&mut pos.x).range(0.0..=2000.0))
-                        .changed()
-                    {
-                        settings_changed = true;
-                    }
-                    ui.label("Y:");
-                    if ui
-                        .add(egui::DragValue::new(&mut pos.y).range(0.0..=2000.0))
-                        .changed()
-                    {
-                        settings_changed = true;
-                    }
-                });
+            if let Some(pos) = &self.config.system_settings.window_position {
+                let mut args = fluent_bundle::FluentArgs::new();
+                args.set("x", pos.x.round() as i64);
+                args.set("y", pos.y.round() as i64);
+                ui.label(tr_with_args(
+                    &self.bundle,
+                    "window-pos-current",
+                    Some(&args),
+                ));
             }
         });
 
@@ -1088,11 +2708,969 @@ impl AppState {
             ui.separator();
             ui.strong(tr(&self.bundle, "hotkeys-title"));
             ui.small(tr(&self.bundle, "hotkey-settings"));
+            ui.small(tr(&self.bundle, "hotkey-pause"));
+            ui.small(tr(&self.bundle, "hotkey-cycle"));
         });
 
         // Автосохранение при изменениях
         if settings_changed {
-            let _ = crate::config::save_config(&self.config_path, &self.config);
+            self.save_config();
+        }
+    }
+
+    /// Импортирует встречи из файла .ics в активный профиль, заменяя интервалы,
+    /// импортированные из того же файла раньше
+    fn import_busy_time(&mut self) {
+        let draft = &self.sharing.busy;
+        let days = if draft.week { 7 } else { 1 };
+        let calendar = std::fs::read_to_string(draft.path.trim())
+            .map_err(IcsError::from)
+            .and_then(|text| read_busy_blocks(&text, draft.date, days));
+        let calendar = match calendar {
+            Ok(calendar) => calendar,
+            Err(err) => {
+                self.sharing.notice = Some(Err(err.to_string()));
+                return;
+            }
+        };
+
+        let meeting_screen_id = match draft.meeting_screen_id {
+            Some(id) if self.config.screens.iter().any(|s| s.id == id) => id,
+            _ => {
+                let screen = ScreenConfig {
+                    id: self.next_screen_id,
+                    title: tr(&self.bundle, "sharing-busy-meeting-title"),
+                    subtitle: String::new(),
+                    color: Rgba8 {
+                        r: 155,
+                        g: 89,
+                        b: 182,
+                        a: 255,
+                    },
+                    sound: None,
+                    notify: true,
+                };
+                self.next_screen_id += 1;
+                self.sharing.busy.meeting_screen_id = Some(screen.id);
+                let id = screen.id;
+                self.config.screens.push(screen);
+                id
+            }
+        };
+
+        let draft = &self.sharing.busy;
+        let source = busy_source(&draft.path);
+        let filler = draft.filler_interval_id.and_then(|id| {
+            self.config
+                .intervals()
+                .iter()
+                .find(|i| i.id == id)
+                .and_then(|i| match &i.mode {
+                    IntervalMode::Cycle { steps } => Some((i.name.clone(), steps.clone())),
+                    IntervalMode::Static { .. } => None,
+                })
+        });
+        let untitled = tr(&self.bundle, "sharing-busy-meeting-title");
+        let import = BusyImport {
+            source: &source,
+            meeting_screen_id,
+            untitled: &untitled,
+            filler: filler
+                .as_ref()
+                .map(|(name, steps)| (name.as_str(), steps.as_slice())),
+            day_start: draft.day_start,
+            day_end: draft.day_end,
+        };
+        let intervals = import.intervals(&calendar.blocks, &mut self.next_interval_id);
+        let added = intervals.len();
+        let removed = replace_imported(
+            &mut self.config.active_profile_mut().intervals,
+            &source,
+            intervals,
+        );
+        self.save_config();
+
+        let mut args = fluent_bundle::FluentArgs::new();
+        args.set("added", added);
+        args.set("meetings", calendar.blocks.len());
+        args.set("removed", removed);
+        args.set("skipped", calendar.skipped);
+        self.sharing.busy.unknown_time_zones = calendar.unknown_time_zones;
+        self.sharing.notice = Some(Ok(tr_with_args(
+            &self.bundle,
+            "sharing-busy-imported",
+            Some(&args),
+        )));
+    }
+
+    fn ui_tab_sharing(&mut self, ui: &mut egui::Ui) {
+        // Экспорт выбранных экранов и интервалов
+        ui.group(|ui| {
+            ui.strong(tr(&self.bundle, "sharing-export"));
+            ui.small(tr(&self.bundle, "sharing-export-desc"));
+
+            ui.label(tr(&self.bundle, "sharing-screens"));
+            for screen in &self.config.screens {
+                id_checkbox(
+                    ui,
+                    &mut self.sharing.export_screens,
+                    screen.id,
+                    &screen.title,
+                );
+            }
+            ui.label(tr(&self.bundle, "sharing-intervals"));
+            for interval in self.config.intervals() {
+                id_checkbox(
+                    ui,
+                    &mut self.sharing.export_intervals,
+                    interval.id,
+                    &interval.name,
+                );
+            }
+
+            ui.horizontal(|ui| {
+                ui.label(tr(&self.bundle, "sharing-file"));
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.sharing.export_path).desired_width(260.0),
+                );
+            });
+            ui.small(tr(&self.bundle, "sharing-format-hint"));
+
+            let bundle = ScheduleBundle::export(
+                &self.config,
+                &self.sharing.export_screens,
+                &self.sharing.export_intervals,
+            );
+            if ui
+                .add_enabled(
+                    !bundle.is_empty(),
+                    egui::Button::new(tr(&self.bundle, "sharing-export-button")),
+                )
+                .clicked()
+            {
+                let path = PathBuf::from(self.sharing.export_path.trim());
+                let mut args = fluent_bundle::FluentArgs::new();
+                args.set("path", path.display().to_string());
+                self.sharing.notice = Some(match bundle.write(&path) {
+                    Ok(()) => Ok(tr_with_args(&self.bundle, "sharing-exported", Some(&args))),
+                    Err(err) => Err(err.to_string()),
+                });
+            }
+        });
+
+        ui.separator();
+
+        // Импорт: сначала предпросмотр, затем слияние
+        ui.group(|ui| {
+            ui.strong(tr(&self.bundle, "sharing-import"));
+            ui.small(tr(&self.bundle, "sharing-import-desc"));
+
+            ui.horizontal(|ui| {
+                ui.label(tr(&self.bundle, "sharing-file"));
+                if ui
+                    .add(
+                        egui::TextEdit::singleline(&mut self.sharing.import_path)
+                            .desired_width(260.0),
+                    )
+                    .changed()
+                {
+                    self.sharing.import_plan = None;
+                }
+                if ui.button(tr(&self.bundle, "sharing-preview")).clicked() {
+                    let path = PathBuf::from(self.sharing.import_path.trim());
+                    match ScheduleBundle::read(&path) {
+                        Ok(bundle) => {
+                            self.sharing.import_plan = Some(ImportPlan::new(&self.config, bundle));
+                            self.sharing.notice = None;
+                        }
+                        Err(err) => {
+                            self.sharing.import_plan = None;
+                            self.sharing.notice = Some(Err(err.to_string()));
+                        }
+                    }
+                }
+            });
+
+            let Some(plan) = &self.sharing.import_plan else {
+                return;
+            };
+
+            let mut args = fluent_bundle::FluentArgs::new();
+            args.set("profile", self.config.active_profile().name.as_str());
+            ui.label(tr_with_args(
+                &self.bundle,
+                "sharing-preview-title",
+                Some(&args),
+            ));
+            for (key, items) in [
+                ("sharing-screens", &plan.screens),
+                ("sharing-intervals", &plan.intervals),
+            ] {
+                if items.is_empty() {
+                    continue;
+                }
+                ui.strong(tr(&self.bundle, key));
+                for item in items {
+                    let text = import_item_label(&self.bundle, item);
+                    if matches!(item.action, ImportAction::MissingScreen { .. }) {
+                        ui.colored_label(egui::Color32::from_rgb(200, 150, 50), text);
+                    } else {
+                        ui.label(text);
+                    }
+                }
+            }
+            if !plan.new_issues.is_empty() {
+                ui.colored_label(
+                    egui::Color32::from_rgb(200, 150, 50),
+                    tr(&self.bundle, "sharing-new-issues"),
+                );
+                for issue in &plan.new_issues {
+                    ui.small(issue.message(&self.bundle));
+                }
+            }
+
+            let can_apply = !plan.is_empty();
+            if !can_apply {
+                ui.small(tr(&self.bundle, "sharing-nothing-to-import"));
+            }
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(
+                        can_apply,
+                        egui::Button::new(tr(&self.bundle, "sharing-apply")),
+                    )
+                    .clicked()
+                    && let Some(plan) = self.sharing.import_plan.take()
+                {
+                    plan.apply(&mut self.config);
+                    // Новые id не должны совпасть с импортированными
+                    let max_screen_id = self.config.screens.iter().map(|s| s.id).max();
+                    let max_interval_id = self.config.all_intervals().map(|i| i.id).max();
+                    self.next_screen_id = self.next_screen_id.max(max_screen_id.unwrap_or(0) + 1);
+                    self.next_interval_id =
+                        self.next_interval_id.max(max_interval_id.unwrap_or(0) + 1);
+                    self.save_config();
+                    self.sharing.notice = Some(Ok(tr(&self.bundle, "sharing-imported")));
+                }
+                if ui.button(tr(&self.bundle, "sharing-cancel")).clicked() {
+                    self.sharing.import_plan = None;
+                }
+            });
+        });
+
+        ui.separator();
+
+        // Расписание активного профиля для приложений-календарей
+        ui.group(|ui| {
+            ui.strong(tr(&self.bundle, "sharing-ics"));
+            ui.small(tr(&self.bundle, "sharing-ics-desc"));
+
+            ui.horizontal(|ui| {
+                ui.label(tr(&self.bundle, "sharing-ics-from"));
+                date_edit(ui, &mut self.sharing.ics_date);
+            });
+            ui.horizontal(|ui| {
+                ui.radio_value(
+                    &mut self.sharing.ics_week,
+                    false,
+                    tr(&self.bundle, "sharing-ics-day"),
+                );
+                ui.radio_value(
+                    &mut self.sharing.ics_week,
+                    true,
+                    tr(&self.bundle, "sharing-ics-week"),
+                );
+            });
+            ui.checkbox(
+                &mut self.sharing.ics_steps,
+                tr(&self.bundle, "sharing-ics-steps"),
+            );
+            ui.horizontal(|ui| {
+                ui.label(tr(&self.bundle, "sharing-file"));
+                ui.add(egui::TextEdit::singleline(&mut self.sharing.ics_path).desired_width(260.0));
+            });
+
+            if ui
+                .button(tr(&self.bundle, "sharing-export-button"))
+                .clicked()
+            {
+                let days = if self.sharing.ics_week { 7 } else { 1 };
+                let ics = export_ics(
+                    &self.config,
+                    self.sharing.ics_date,
+                    days,
+                    self.sharing.ics_steps,
+                    chrono::Utc::now(),
+                );
+                let path = PathBuf::from(self.sharing.ics_path.trim());
+                let mut args = fluent_bundle::FluentArgs::new();
+                args.set("path", path.display().to_string());
+                self.sharing.notice = Some(match std::fs::write(&path, ics) {
+                    Ok(()) => Ok(tr_with_args(&self.bundle, "sharing-exported", Some(&args))),
+                    Err(err) => Err(err.to_string()),
+                });
+            }
+        });
+
+        ui.separator();
+
+        // Занятое время из календаря: встречи и заполнение промежутков циклом
+        ui.group(|ui| {
+            ui.strong(tr(&self.bundle, "sharing-busy"));
+            ui.small(tr(&self.bundle, "sharing-busy-desc"));
+
+            let draft = &mut self.sharing.busy;
+            ui.horizontal(|ui| {
+                ui.label(tr(&self.bundle, "sharing-file"));
+                ui.add(egui::TextEdit::singleline(&mut draft.path).desired_width(260.0));
+            });
+            ui.horizontal(|ui| {
+                ui.label(tr(&self.bundle, "sharing-ics-from"));
+                date_edit(ui, &mut draft.date);
+            });
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut draft.week, false, tr(&self.bundle, "sharing-ics-day"));
+                ui.radio_value(&mut draft.week, true, tr(&self.bundle, "sharing-ics-week"));
+            });
+
+            ui.horizontal(|ui| {
+                ui.label(tr(&self.bundle, "sharing-busy-screen"));
+                let new_screen = tr(&self.bundle, "sharing-busy-new-screen");
+                let selected = draft
+                    .meeting_screen_id
+                    .and_then(|id| self.config.screens.iter().find(|s| s.id == id))
+                    .map(|s| s.title.clone())
+                    .unwrap_or_else(|| new_screen.clone());
+                egui::ComboBox::from_id_salt("busy_meeting_screen")
+                    .selected_text(selected)
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut draft.meeting_screen_id, None, new_screen);
+                        for screen in &self.config.screens {
+                            ui.selectable_value(
+                                &mut draft.meeting_screen_id,
+                                Some(screen.id),
+                                &screen.title,
+                            );
+                        }
+                    });
+            });
+
+            // Шаблоном может быть любой циклический интервал, созданный вручную
+            let templates: Vec<&TimeInterval> = self
+                .config
+                .intervals()
+                .iter()
+                .filter(|i| i.source.is_none() && matches!(i.mode, IntervalMode::Cycle { .. }))
+                .collect();
+            ui.horizontal(|ui| {
+                ui.label(tr(&self.bundle, "sharing-busy-filler"));
+                let no_filler = tr(&self.bundle, "sharing-busy-no-filler");
+                let selected = draft
+                    .filler_interval_id
+                    .and_then(|id| templates.iter().find(|i| i.id == id))
+                    .map(|i| i.name.clone())
+                    .unwrap_or_else(|| no_filler.clone());
+                egui::ComboBox::from_id_salt("busy_filler")
+                    .selected_text(selected)
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut draft.filler_interval_id, None, no_filler);
+                        for interval in &templates {
+                            ui.selectable_value(
+                                &mut draft.filler_interval_id,
+                                Some(interval.id),
+                                &interval.name,
+                            );
+                        }
+                    });
+            });
+            ui.add_enabled_ui(draft.filler_interval_id.is_some(), |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(tr(&self.bundle, "sharing-busy-hours"));
+                    time_edit(ui, &mut draft.day_start);
+                    ui.label("–");
+                    time_edit(ui, &mut draft.day_end);
+                });
+            });
+
+            let source = busy_source(&draft.path);
+            let has_imported = self
+                .config
+                .intervals()
+                .iter()
+                .any(|i| i.source.as_deref() == Some(source.as_str()));
+            ui.horizontal(|ui| {
+                let import_key = if has_imported {
+                    "sharing-busy-refresh"
+                } else {
+                    "sharing-busy-import"
+                };
+                if ui.button(tr(&self.bundle, import_key)).clicked() {
+                    self.import_busy_time();
+                }
+                if ui
+                    .add_enabled(
+                        has_imported,
+                        egui::Button::new(tr(&self.bundle, "sharing-busy-remove")),
+                    )
+                    .clicked()
+                {
+                    let removed = replace_imported(
+                        &mut self.config.active_profile_mut().intervals,
+                        &source,
+                        Vec::new(),
+                    );
+                    self.save_config();
+                    let mut args = fluent_bundle::FluentArgs::new();
+                    args.set("removed", removed);
+                    self.sharing.notice = Some(Ok(tr_with_args(
+                        &self.bundle,
+                        "sharing-busy-removed",
+                        Some(&args),
+                    )));
+                }
+            });
+
+            let zones = &self.sharing.busy.unknown_time_zones;
+            if !zones.is_empty() {
+                let mut args = fluent_bundle::FluentArgs::new();
+                args.set("zones", zones.join(", "));
+                ui.colored_label(
+                    egui::Color32::from_rgb(200, 150, 50),
+                    tr_with_args(&self.bundle, "sharing-busy-time-zones", Some(&args)),
+                );
+            }
+        });
+
+        match &self.sharing.notice {
+            Some(Ok(message)) => {
+                ui.label(message);
+            }
+            Some(Err(error)) => {
+                let mut args = fluent_bundle::FluentArgs::new();
+                args.set("error", error.as_str());
+                ui.colored_label(
+                    egui::Color32::from_rgb(200, 80, 80),
+                    tr_with_args(&self.bundle, "sharing-error", Some(&args)),
+                );
+            }
+            None => {}
         }
     }
+
+    fn ui_tab_stats(&mut self, ui: &mut egui::Ui) {
+        let path = history_path(&self.config_path);
+        if self.history.log.is_none() {
+            match History::load(&path) {
+                Ok(log) => self.history.log = Some(log),
+                Err(err) => self.history.error = Some(err.to_string()),
+            }
+        }
+
+        ui.heading(tr(&self.bundle, "tab-stats"));
+        ui.small(tr(&self.bundle, "stats-desc"));
+        let mut args = fluent_bundle::FluentArgs::new();
+        args.set("path", path.display().to_string());
+        ui.small(tr_with_args(&self.bundle, "stats-log", Some(&args)));
+
+        if let Some(error) = &self.history.error {
+            let mut args = fluent_bundle::FluentArgs::new();
+            args.set("error", error.as_str());
+            ui.colored_label(
+                egui::Color32::from_rgb(200, 80, 80),
+                tr_with_args(&self.bundle, "sharing-error", Some(&args)),
+            );
+        }
+        let Some(log) = &self.history.log else {
+            return;
+        };
+        if log.broken_lines > 0 {
+            let mut args = fluent_bundle::FluentArgs::new();
+            args.set("count", log.broken_lines);
+            ui.colored_label(
+                egui::Color32::from_rgb(200, 150, 50),
+                tr_with_args(&self.bundle, "stats-broken-lines", Some(&args)),
+            );
+        }
+
+        let today = Local::now().date_naive();
+        ui.horizontal(|ui| {
+            if ui.button("◀").clicked() {
+                self.history.date -= chrono::Duration::days(1);
+            }
+            date_edit(ui, &mut self.history.date);
+            if ui.button("▶").clicked() {
+                self.history.date += chrono::Duration::days(1);
+            }
+            if ui
+                .add_enabled(
+                    self.history.date != today,
+                    egui::Button::new(tr(&self.bundle, "stats-today")),
+                )
+                .clicked()
+            {
+                self.history.date = today;
+            }
+        });
+
+        let date = self.history.date;
+        let week = week_start(date);
+        let mut day_args = fluent_bundle::FluentArgs::new();
+        day_args.set("date", date.format("%d.%m.%Y").to_string());
+        let mut week_args = fluent_bundle::FluentArgs::new();
+        week_args.set("from", week.format("%d.%m").to_string());
+        week_args.set(
+            "to",
+            (week + chrono::Duration::days(6))
+                .format("%d.%m.%Y")
+                .to_string(),
+        );
+
+        for (title, from, days) in [
+            (
+                tr_with_args(&self.bundle, "stats-day", Some(&day_args)),
+                date,
+                1,
+            ),
+            (
+                tr_with_args(&self.bundle, "stats-week", Some(&week_args)),
+                week,
+                7,
+            ),
+        ] {
+            ui.group(|ui| {
+                ui.strong(title);
+                let totals = log.screen_totals(from, days);
+                if totals.is_empty() {
+                    ui.small(tr(&self.bundle, "stats-empty"));
+                }
+                for total in &totals {
+                    let color = self
+                        .config
+                        .screens
+                        .iter()
+                        .find(|s| s.id == total.screen_id)
+                        .map(|s| s.color);
+                    screen_total_row(ui, color, &total.title, total.secs);
+                }
+                let mut args = fluent_bundle::FluentArgs::new();
+                args.set("count", log.completed_cycles(from, days));
+                ui.label(tr_with_args(&self.bundle, "stats-cycles", Some(&args)));
+            });
+        }
+
+        // Серии считаются до сегодняшнего дня независимо от выбранной даты
+        let streaks = log.streaks(today);
+        ui.group(|ui| {
+            ui.strong(tr(&self.bundle, "stats-streaks"));
+            ui.small(tr(&self.bundle, "stats-streaks-desc"));
+            for (key, days) in [
+                ("stats-streak-current", streaks.current),
+                ("stats-streak-longest", streaks.longest),
+            ] {
+                let mut args = fluent_bundle::FluentArgs::new();
+                args.set("days", days);
+                ui.label(tr_with_args(&self.bundle, key, Some(&args)));
+            }
+        });
+
+        // Выгрузка журнала за период
+        ui.group(|ui| {
+            ui.strong(tr(&self.bundle, "stats-export"));
+            ui.small(tr(&self.bundle, "stats-export-desc"));
+            let options = &mut self.history.export;
+            ui.horizontal(|ui| {
+                ui.label(tr(&self.bundle, "stats-export-from"));
+                date_edit(ui, &mut options.from);
+                ui.label(tr(&self.bundle, "stats-export-to"));
+                date_edit(ui, &mut options.to);
+            });
+            ui.horizontal(|ui| {
+                ui.label(tr(&self.bundle, "stats-export-group"));
+                egui::ComboBox::from_id_salt("history_export_grouping")
+                    .selected_text(tr(&self.bundle, grouping_key(options.grouping)))
+                    .show_ui(ui, |ui| {
+                        for grouping in ExportGrouping::ALL {
+                            ui.selectable_value(
+                                &mut options.grouping,
+                                grouping,
+                                tr(&self.bundle, grouping_key(grouping)),
+                            );
+                        }
+                    });
+            });
+            ui.horizontal(|ui| {
+                ui.label(tr(&self.bundle, "stats-export-round"));
+                ui.add(egui::DragValue::new(&mut options.rounding_minutes).range(0..=240));
+                ui.small(tr(&self.bundle, "stats-export-round-hint"));
+            });
+            ui.horizontal(|ui| {
+                ui.label(tr(&self.bundle, "sharing-file"));
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.history.export_path).desired_width(260.0),
+                );
+            });
+            if ui
+                .add_enabled(
+                    options.from <= options.to,
+                    egui::Button::new(tr(&self.bundle, "stats-export-button")),
+                )
+                .clicked()
+            {
+                let path = PathBuf::from(self.history.export_path.trim());
+                let text = export(log, options, ExportFormat::from_path(&path));
+                let mut args = fluent_bundle::FluentArgs::new();
+                args.set("path", path.display().to_string());
+                self.history.export_notice = Some(match std::fs::write(&path, text) {
+                    Ok(()) => Ok(tr_with_args(&self.bundle, "stats-exported", Some(&args))),
+                    Err(err) => Err(err.to_string()),
+                });
+            }
+            match &self.history.export_notice {
+                Some(Ok(message)) => {
+                    ui.label(message);
+                }
+                Some(Err(error)) => {
+                    let mut args = fluent_bundle::FluentArgs::new();
+                    args.set("error", error.as_str());
+                    ui.colored_label(
+                        egui::Color32::from_rgb(200, 80, 80),
+                        tr_with_args(&self.bundle, "sharing-error", Some(&args)),
+                    );
+                }
+                None => {}
+            }
+        });
+    }
+}
+
+/// Метка импортированных интервалов: полный путь к файлу, чтобы повторный импорт
+/// того же файла находил их независимо от того, как путь набран
+/// Цвет и значок проблемы проверки
+fn severity_style(severity: Severity) -> (egui::Color32, &'static str) {
+    match severity {
+        Severity::Error => (egui::Color32::from_rgb(200, 80, 80), "✖"),
+        Severity::Warning => (egui::Color32::from_rgb(200, 150, 50), "⚠"),
+    }
+}
+
+/// Проблема проверки с подсказкой по исправлению
+fn issue_label(ui: &mut egui::Ui, bundle: &FluentBundle<FluentResource>, issue: &ValidationIssue) {
+    let (color, icon) = severity_style(issue.severity());
+    let severity_key = match issue.severity() {
+        Severity::Error => "validation-severity-error",
+        Severity::Warning => "validation-severity-warning",
+    };
+    ui.vertical(|ui| {
+        ui.colored_label(color, format!("{} {}", icon, issue.message(bundle)))
+            .on_hover_text(tr(bundle, severity_key));
+        ui.small(format!("→ {}", issue.fix(bundle)));
+    });
+}
+
+/// Обводит поле редактора интервала, в котором проверка нашла проблему
+fn mark_field(
+    ui: &egui::Ui,
+    rect: egui::Rect,
+    issues: &[ValidationIssue],
+    interval_id: u32,
+    field: IssueField,
+) {
+    let Some(severity) = issues
+        .iter()
+        .filter(|issue| issue.field(interval_id) == Some(field))
+        .map(|issue| issue.severity())
+        .max()
+    else {
+        return;
+    };
+    let (color, _) = severity_style(severity);
+    ui.painter().rect_stroke(
+        rect.expand(2.0),
+        4.0,
+        egui::Stroke::new(2.0, color),
+        egui::StrokeKind::Outside,
+    );
+}
+
+fn grouping_key(grouping: ExportGrouping) -> &'static str {
+    match grouping {
+        ExportGrouping::Sessions => "stats-group-sessions",
+        ExportGrouping::Interval => "stats-group-interval",
+        ExportGrouping::Screen => "stats-group-screen",
+    }
+}
+
+/// Строка статистики: цвет экрана, заголовок и время
+fn screen_total_row(ui: &mut egui::Ui, color: Option<Rgba8>, title: &str, secs: u64) {
+    ui.horizontal(|ui| {
+        let (rect, _) = ui.allocate_exact_size(egui::vec2(12.0, 12.0), egui::Sense::hover());
+        if let Some(color) = color {
+            ui.painter().rect_filled(rect, 2.0, color.to_egui());
+        }
+        ui.label(title);
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            ui.monospace(format_duration_hhmmss(secs));
+        });
+    });
+}
+
+fn busy_source(path: &str) -> String {
+    let path = PathBuf::from(path.trim());
+    std::fs::canonicalize(&path)
+        .unwrap_or(path)
+        .display()
+        .to_string()
+}
+
+/// Поля часов и минут
+fn time_edit(ui: &mut egui::Ui, time: &mut TimeOfDay) {
+    ui.add(
+        egui::DragValue::new(&mut time.hour)
+            .range(0..=23)
+            .speed(1.0),
+    );
+    ui.label(":");
+    ui.add(
+        egui::DragValue::new(&mut time.minute)
+            .range(0..=59)
+            .speed(1.0),
+    );
+}
+
+/// Флажок «элемент с этим id выбран»
+fn id_checkbox(ui: &mut egui::Ui, ids: &mut Vec<u32>, id: u32, label: &str) {
+    let mut checked = ids.contains(&id);
+    if ui.checkbox(&mut checked, label).changed() {
+        if checked {
+            ids.push(id);
+        } else {
+            ids.retain(|i| *i != id);
+        }
+    }
+}
+
+/// Строка предпросмотра импорта
+fn import_item_label(bundle: &FluentBundle<FluentResource>, item: &ImportItem) -> String {
+    let mut args = fluent_bundle::FluentArgs::new();
+    args.set("name", item.name.as_str());
+    args.set("id", item.id);
+    let key = match item.action {
+        ImportAction::Add => "sharing-item-add",
+        ImportAction::Remap { from } => {
+            args.set("from", from);
+            "sharing-item-remap"
+        }
+        ImportAction::Existing { .. } => "sharing-item-existing",
+        ImportAction::MissingScreen { screen_id } => {
+            args.set("screen", screen_id);
+            "sharing-item-missing"
+        }
+    };
+    tr_with_args(bundle, key, Some(&args))
+}
+
+/// Все дни недели по порядку, начиная с понедельника
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+/// Флажки дней недели; возвращает `true`, если набор изменился
+fn weekday_checkboxes(
+    ui: &mut egui::Ui,
+    bundle: &FluentBundle<FluentResource>,
+    days: &mut Vec<Weekday>,
+) -> bool {
+    let mut changed = false;
+    ui.horizontal_wrapped(|ui| {
+        for day in WEEKDAYS {
+            let mut selected = days.contains(&day);
+            if ui
+                .checkbox(&mut selected, tr(bundle, weekday_key(day)))
+                .changed()
+            {
+                if selected {
+                    days.push(day);
+                } else {
+                    days.retain(|d| *d != day);
+                }
+                days.sort_by_key(|d| d.num_days_from_monday());
+                changed = true;
+            }
+        }
+    });
+    changed
+}
+
+/// Краткое описание правила повторения для списка интервалов
+fn recurrence_label(bundle: &FluentBundle<FluentResource>, recurrence: &Recurrence) -> String {
+    match recurrence {
+        Recurrence::Daily => tr(bundle, "recurrence-daily"),
+        Recurrence::Weekdays => tr(bundle, "recurrence-weekdays"),
+        Recurrence::Weekends => tr(bundle, "recurrence-weekends"),
+        Recurrence::Days { days } => WEEKDAYS
+            .iter()
+            .filter(|day| days.contains(day))
+            .map(|day| tr(bundle, weekday_key(*day)))
+            .collect::<Vec<_>>()
+            .join(", "),
+        Recurrence::DateRange { from, to } => format!("{} — {}", from, to),
+        Recurrence::Dates { dates } => dates
+            .iter()
+            .map(|d| d.to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+    }
+}
+
+/// Редактор правила повторения интервала
+fn recurrence_editor(
+    ui: &mut egui::Ui,
+    bundle: &FluentBundle<FluentResource>,
+    recurrence: &mut Recurrence,
+) {
+    let today = Local::now().date_naive();
+    let kinds = [
+        (Recurrence::Daily, "recurrence-daily"),
+        (Recurrence::Weekdays, "recurrence-weekdays"),
+        (Recurrence::Weekends, "recurrence-weekends"),
+        (
+            Recurrence::Days {
+                days: vec![today.weekday()],
+            },
+            "recurrence-days",
+        ),
+        (
+            Recurrence::DateRange {
+                from: today,
+                to: today + chrono::Duration::days(6),
+            },
+            "recurrence-date-range",
+        ),
+        (Recurrence::Dates { dates: vec![today] }, "recurrence-dates"),
+    ];
+
+    let current_kind = kinds
+        .iter()
+        .position(|(kind, _)| std::mem::discriminant(kind) == std::mem::discriminant(recurrence))
+        .unwrap_or(0);
+
+    egui::ComboBox::from_id_salt("interval_recurrence_kind")
+        .selected_text(tr(bundle, kinds[current_kind].1))
+        .width(220.0)
+        .show_ui(ui, |ui| {
+            for (idx, (kind, key)) in kinds.iter().enumerate() {
+                if ui
+                    .selectable_label(idx == current_kind, tr(bundle, key))
+                    .clicked()
+                    && idx != current_kind
+                {
+                    *recurrence = kind.clone();
+                }
+            }
+        });
+
+    match recurrence {
+        Recurrence::Daily | Recurrence::Weekdays | Recurrence::Weekends => {}
+        Recurrence::Days { days } => {
+            weekday_checkboxes(ui, bundle, days);
+        }
+        Recurrence::DateRange { from, to } => {
+            ui.horizontal(|ui| {
+                ui.label(tr(bundle, "recurrence-from"));
+                date_edit(ui, from);
+                ui.label(tr(bundle, "recurrence-to"));
+                date_edit(ui, to);
+            });
+        }
+        Recurrence::Dates { dates } => {
+            let mut to_remove: Option<usize> = None;
+            for (idx, date) in dates.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    date_edit(ui, date);
+                    if ui.small_button("🗑").clicked() {
+                        to_remove = Some(idx);
+                    }
+                });
+            }
+            if let Some(idx) = to_remove {
+                dates.remove(idx);
+            }
+            if ui.button(tr(bundle, "recurrence-add-date")).clicked() {
+                let next = dates
+                    .last()
+                    .map(|d| *d + chrono::Duration::days(1))
+                    .unwrap_or(today);
+                dates.push(next);
+            }
+        }
+    }
+}
+
+/// Поле ввода даты: год, месяц и день; день ограничивается длиной месяца
+fn date_edit(ui: &mut egui::Ui, date: &mut NaiveDate) {
+    let mut year = date.year();
+    let mut month = date.month();
+    let mut day = date.day();
+
+    let mut changed = false;
+    changed |= ui
+        .add(
+            egui::DragValue::new(&mut year)
+                .range(2000..=2100)
+                .speed(1.0),
+        )
+        .changed();
+    ui.label("-");
+    changed |= ui
+        .add(egui::DragValue::new(&mut month).range(1..=12).speed(1.0))
+        .changed();
+    ui.label("-");
+    changed |= ui
+        .add(egui::DragValue::new(&mut day).range(1..=31).speed(1.0))
+        .changed();
+
+    if changed {
+        while day > 1 && NaiveDate::from_ymd_opt(year, month, day).is_none() {
+            day -= 1;
+        }
+        if let Some(new_date) = NaiveDate::from_ymd_opt(year, month, day) {
+            *date = new_date;
+        }
+    }
+}
+
+fn chime_key(chime: Chime) -> &'static str {
+    match chime {
+        Chime::Soft => "chime-soft",
+        Chime::Bell => "chime-bell",
+        Chime::Rising => "chime-rising",
+    }
+}
+
+/// Поле для необязательного пути к файлу; пустая строка означает «не задан»
+fn path_edit(ui: &mut egui::Ui, path: &mut Option<PathBuf>) -> bool {
+    let mut text = path
+        .as_ref()
+        .map(|p| p.display().to_string())
+        .unwrap_or_default();
+    let changed = ui
+        .add(egui::TextEdit::singleline(&mut text).desired_width(220.0))
+        .changed();
+    if changed {
+        let trimmed = text.trim();
+        *path = (!trimmed.is_empty()).then(|| PathBuf::from(trimmed));
+    }
+    changed
+}
+
+/// Сколько минут (с округлением вверх) осталось до момента `at`
+fn minutes_until(at: chrono::DateTime<Local>) -> i64 {
+    let secs = (at - Local::now()).num_seconds().max(0);
+    (secs + 59) / 60
 }
@@ -1,44 +1,47 @@
-use fluent_bundle::{FluentBundle, FluentResource, FluentArgs};
-use unic_langid::LanguageIdentifier;
+use crate::locale::{FALLBACK_LOCALE, LocaleError, make_bundle, report};
 use crate::ui::AppState;
+use chrono::Weekday;
+use fluent_bundle::{FluentArgs, FluentBundle, FluentResource};
+use unic_langid::LanguageIdentifier;
 
-pub fn make_bundle(lang: &LanguageIdentifier) -> FluentBundle<FluentResource> {
-    let ftl: &str = match lang.to_string().as_str() {
-        "ru-RU" | "ru" => include_str!("../locales/ru-RU.ftl"),
-        _ => include_str!("../locales/en-US.ftl"),
-    };
-
-    let resource = FluentResource::try_new(ftl.to_string())
-        .expect("Некорректный формат FTL ресурса");
-
-    let mut bundle = FluentBundle::new(vec![lang.clone()]);
-    bundle
-        .add_resource(resource)
-        .expect("Не удалось добавить FTL ресурс в bundle");
-    bundle
+pub fn tr(bundle: &FluentBundle<FluentResource>, id: &str) -> String {
+    tr_with_args(bundle, id, None)
 }
 
-pub fn tr(bundle: &FluentBundle<FluentResource>, id: &str) -> String {
-    if let Some(msg) = bundle.get_message(id) {
-        if let Some(pattern) = msg.value() {
-            let mut errors = vec![];
-            let value = bundle.format_pattern(pattern, None, &mut errors);
-            return value.into_owned();
-        }
+/// Локализация с параметрами. Отсутствующее сообщение заменяется своим ключом,
+/// об ошибках сообщается через `locale::report`.
+pub fn tr_with_args(bundle: &FluentBundle<FluentResource>, id: &str, args: Option<&FluentArgs>) -> String {
+    let locale = || bundle.locales.first().cloned().unwrap_or(FALLBACK_LOCALE);
+    let Some(pattern) = bundle.get_message(id).and_then(|msg| msg.value()) else {
+        report(LocaleError::MissingMessage {
+            locale: locale(),
+            id: id.to_string(),
+        });
+        return id.to_string();
+    };
+    let mut errors = vec![];
+    let value = bundle.format_pattern(pattern, args, &mut errors);
+    for error in errors {
+        report(LocaleError::Format {
+            locale: locale(),
+            id: id.to_string(),
+            error: error.to_string(),
+        });
     }
-    id.to_string()
+    value.into_owned()
 }
 
-/// Локализация с параметрами
-pub fn tr_with_args(bundle: &FluentBundle<FluentResource>, id: &str, args: Option<&FluentArgs>) -> String {
-    if let Some(msg) = bundle.get_message(id) {
-        if let Some(pattern) = msg.value() {
-            let mut errors = vec![];
-            let value = bundle.format_pattern(pattern, args, &mut errors);
-            return value.into_owned();
-        }
+/// Ключ локализации для короткого названия дня недели
+pub fn weekday_key(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "weekday-mon",
+        Weekday::Tue => "weekday-tue",
+        Weekday::Wed => "weekday-wed",
+        Weekday::Thu => "weekday-thu",
+        Weekday::Fri => "weekday-fri",
+        Weekday::Sat => "weekday-sat",
+        Weekday::Sun => "weekday-sun",
     }
-    id.to_string()
 }
 
 /// Создает FluentArgs для одного параметра
@@ -67,8 +70,8 @@ pub fn set_language(app: &mut AppState, lang_tag: &str) {
     if let Ok(parsed) = lang_tag.parse::<LanguageIdentifier>() {
         if parsed != app.config.language {
             app.config.language = parsed;
-            app.bundle = make_bundle(&app.config.language);
-            let _ = crate::config::save_config(&app.config_path, &app.config);
+            app.bundle = make_bundle(&app.config.language, &app.locales);
+            app.save_config();
         }
     }
 }
@@ -0,0 +1,605 @@
+//! Проверка расписания.
+//!
+//! Проверки возвращают [`ValidationIssue`] - проблему с серьёзностью, id
+//! затронутых интервалов и полями, которые редактор подсвечивает. Текст
+//! проблемы и подсказка по исправлению собираются из ключей `validation-*`
+//! в `locales/*.ftl` при выводе.
+
+use chrono::{NaiveDate, Weekday};
+use fluent_bundle::{FluentArgs, FluentBundle, FluentResource};
+use serde::Serialize;
+
+use crate::{
+    config::{AppConfig, IntervalMode, Recurrence, ScreenConfig, TimeInterval, TimeOfDay},
+    utils::{tr, tr_with_args, weekday_key},
+};
+
+/// Серьёзность проблемы
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Расписание работает, но не так, как, вероятно, задумано
+    /// (из пересекающихся интервалов действует первый)
+    Warning,
+    /// Интервал не работает или показывает не тот экран
+    Error,
+}
+
+/// Интервал, к которому относится проблема
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntervalRef {
+    pub id: u32,
+    pub name: String,
+    /// Профиль интервала; указывается, только если профилей несколько
+    pub profile: Option<String>,
+}
+
+/// Поле редактора интервала, которое нужно исправить
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IssueField {
+    /// Время начала и окончания
+    Time,
+    Recurrence,
+    /// Экран статичного режима
+    Screen,
+    /// Список шагов цикла целиком
+    Steps,
+    /// Отдельный шаг цикла (индекс с нуля)
+    Step(usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationIssue {
+    /// Начало и конец совпадают: интервал нулевой длины
+    ZeroLength {
+        interval: IntervalRef,
+        time: TimeOfDay,
+    },
+    /// Интервалы пересекаются в общие дни
+    Overlap {
+        first: IntervalRef,
+        second: IntervalRef,
+    },
+    /// Интервал, импортированный из календаря, пересекается с созданным вручную
+    CalendarOverlap {
+        imported: IntervalRef,
+        source: String,
+        other: IntervalRef,
+    },
+    /// Дата начала повторения позже даты окончания
+    InvertedDateRange {
+        interval: IntervalRef,
+        from: NaiveDate,
+        to: NaiveDate,
+    },
+    NoWeekdays {
+        interval: IntervalRef,
+    },
+    NoDates {
+        interval: IntervalRef,
+    },
+    EmptyCycle {
+        interval: IntervalRef,
+    },
+    ZeroCycleDuration {
+        interval: IntervalRef,
+    },
+    /// Интервал ссылается на несуществующий экран; `step` - шаг цикла
+    MissingScreen {
+        interval: IntervalRef,
+        screen_id: u32,
+        step: Option<usize>,
+    },
+    /// Экран по умолчанию профиля не существует
+    MissingDefaultScreen {
+        profile: String,
+        screen_id: u32,
+    },
+    /// Два профиля назначены на один день недели
+    ProfileDayConflict {
+        first: String,
+        second: String,
+        day: Weekday,
+    },
+}
+
+impl ValidationIssue {
+    pub fn severity(&self) -> Severity {
+        match self {
+            ValidationIssue::Overlap { .. }
+            | ValidationIssue::CalendarOverlap { .. }
+            | ValidationIssue::MissingDefaultScreen { .. }
+            | ValidationIssue::ProfileDayConflict { .. } => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+
+    /// Постоянный идентификатор вида проблемы (для `validate --json`)
+    pub fn code(&self) -> &'static str {
+        match self {
+            ValidationIssue::ZeroLength { .. } => "zero-length",
+            ValidationIssue::Overlap { .. } => "overlap",
+            ValidationIssue::CalendarOverlap { .. } => "calendar-overlap",
+            ValidationIssue::InvertedDateRange { .. } => "date-range",
+            ValidationIssue::NoWeekdays { .. } => "no-weekdays",
+            ValidationIssue::NoDates { .. } => "no-dates",
+            ValidationIssue::EmptyCycle { .. } => "empty-cycle",
+            ValidationIssue::ZeroCycleDuration { .. } => "zero-duration",
+            ValidationIssue::MissingScreen { .. } => "missing-screen",
+            ValidationIssue::MissingDefaultScreen { .. } => "missing-default-screen",
+            ValidationIssue::ProfileDayConflict { .. } => "profile-day",
+        }
+    }
+
+    /// Затронутые интервалы; первым идёт тот, который стоит исправить
+    pub fn intervals(&self) -> Vec<&IntervalRef> {
+        match self {
+            ValidationIssue::Overlap { first, second } => vec![first, second],
+            ValidationIssue::CalendarOverlap {
+                imported, other, ..
+            } => vec![imported, other],
+            ValidationIssue::ZeroLength { interval, .. }
+            | ValidationIssue::InvertedDateRange { interval, .. }
+            | ValidationIssue::NoWeekdays { interval }
+            | ValidationIssue::NoDates { interval }
+            | ValidationIssue::EmptyCycle { interval }
+            | ValidationIssue::ZeroCycleDuration { interval }
+            | ValidationIssue::MissingScreen { interval, .. } => vec![interval],
+            ValidationIssue::MissingDefaultScreen { .. }
+            | ValidationIssue::ProfileDayConflict { .. } => Vec::new(),
+        }
+    }
+
+    pub fn interval_ids(&self) -> Vec<u32> {
+        self.intervals().iter().map(|i| i.id).collect()
+    }
+
+    /// Поле редактора, которое нужно исправить у интервала `id`
+    pub fn field(&self, id: u32) -> Option<IssueField> {
+        if !self.interval_ids().contains(&id) {
+            return None;
+        }
+        Some(match self {
+            ValidationIssue::ZeroLength { .. }
+            | ValidationIssue::Overlap { .. }
+            | ValidationIssue::CalendarOverlap { .. } => IssueField::Time,
+            ValidationIssue::InvertedDateRange { .. }
+            | ValidationIssue::NoWeekdays { .. }
+            | ValidationIssue::NoDates { .. } => IssueField::Recurrence,
+            ValidationIssue::EmptyCycle { .. } | ValidationIssue::ZeroCycleDuration { .. } => {
+                IssueField::Steps
+            }
+            ValidationIssue::MissingScreen { step: None, .. } => IssueField::Screen,
+            ValidationIssue::MissingScreen {
+                step: Some(step), ..
+            } => IssueField::Step(*step),
+            ValidationIssue::MissingDefaultScreen { .. }
+            | ValidationIssue::ProfileDayConflict { .. } => return None,
+        })
+    }
+
+    /// Текст проблемы
+    pub fn message(&self, bundle: &FluentBundle<FluentResource>) -> String {
+        let mut args = FluentArgs::new();
+        let key = match self {
+            ValidationIssue::ZeroLength { interval, time } => {
+                args.set("name", interval.name.as_str());
+                let time = format!("{:02}:{:02}", time.hour, time.minute);
+                args.set("start", time.clone());
+                args.set("end", time);
+                "validation-time-order"
+            }
+            ValidationIssue::Overlap { first, second } => {
+                args.set("first", first.name.as_str());
+                args.set("second", second.name.as_str());
+                "validation-overlap"
+            }
+            ValidationIssue::CalendarOverlap {
+                imported,
+                source,
+                other,
+            } => {
+                args.set("name", imported.name.as_str());
+                args.set("source", source.as_str());
+                args.set("other", other.name.as_str());
+                "validation-calendar-overlap"
+            }
+            ValidationIssue::InvertedDateRange { interval, from, to } => {
+                args.set("name", interval.name.as_str());
+                args.set("from", from.to_string());
+                args.set("to", to.to_string());
+                "validation-date-range"
+            }
+            ValidationIssue::NoWeekdays { interval } => {
+                args.set("name", interval.name.as_str());
+                "validation-no-weekdays"
+            }
+            ValidationIssue::NoDates { interval } => {
+                args.set("name", interval.name.as_str());
+                "validation-no-dates"
+            }
+            ValidationIssue::EmptyCycle { interval } => {
+                args.set("name", interval.name.as_str());
+                "validation-empty-cycle"
+            }
+            ValidationIssue::ZeroCycleDuration { interval } => {
+                args.set("name", interval.name.as_str());
+                "validation-zero-duration"
+            }
+            ValidationIssue::MissingScreen {
+                interval,
+                screen_id,
+                step,
+            } => {
+                args.set("name", interval.name.as_str());
+                args.set("screen", *screen_id);
+                match step {
+                    Some(step) => {
+                        args.set("step", step + 1);
+                        "validation-missing-step-screen"
+                    }
+                    None => "validation-missing-screen",
+                }
+            }
+            ValidationIssue::MissingDefaultScreen { profile, screen_id } => {
+                args.set("profile", profile.as_str());
+                args.set("screen", *screen_id);
+                "validation-missing-default-screen"
+            }
+            ValidationIssue::ProfileDayConflict { first, second, day } => {
+                args.set("first", first.as_str());
+                args.set("second", second.as_str());
+                args.set("day", tr(bundle, weekday_key(*day)));
+                "validation-profile-day"
+            }
+        };
+        let message = tr_with_args(bundle, key, Some(&args));
+
+        match self.intervals().first().and_then(|i| i.profile.as_deref()) {
+            Some(profile) => {
+                let mut args = FluentArgs::new();
+                args.set("profile", profile);
+                args.set("issue", message);
+                tr_with_args(bundle, "validation-in-profile", Some(&args))
+            }
+            None => message,
+        }
+    }
+
+    /// Подсказка, как исправить проблему
+    pub fn fix(&self, bundle: &FluentBundle<FluentResource>) -> String {
+        let mut args = FluentArgs::new();
+        let key = match self {
+            ValidationIssue::ZeroLength { .. } => "validation-fix-time",
+            ValidationIssue::Overlap { second, .. } => {
+                args.set("name", second.name.as_str());
+                "validation-fix-overlap"
+            }
+            ValidationIssue::CalendarOverlap { other, .. } => {
+                args.set("name", other.name.as_str());
+                "validation-fix-calendar-overlap"
+            }
+            ValidationIssue::InvertedDateRange { .. } => "validation-fix-date-range",
+            ValidationIssue::NoWeekdays { .. } => "validation-fix-weekdays",
+            ValidationIssue::NoDates { .. } => "validation-fix-dates",
+            ValidationIssue::EmptyCycle { .. } => "validation-fix-empty-cycle",
+            ValidationIssue::ZeroCycleDuration { .. } => "validation-fix-zero-duration",
+            ValidationIssue::MissingScreen { .. } => "validation-fix-screen",
+            ValidationIssue::MissingDefaultScreen { .. } => "validation-fix-default-screen",
+            ValidationIssue::ProfileDayConflict { day, .. } => {
+                args.set("day", tr(bundle, weekday_key(*day)));
+                "validation-fix-profile-day"
+            }
+        };
+        tr_with_args(bundle, key, Some(&args))
+    }
+}
+
+/// Проверяет интервалы одного профиля и ссылки на экраны
+pub fn validate_intervals(
+    intervals: &[TimeInterval],
+    screens: &[ScreenConfig],
+) -> Vec<ValidationIssue> {
+    check_intervals(intervals, screens, None)
+}
+
+fn check_intervals(
+    intervals: &[TimeInterval],
+    screens: &[ScreenConfig],
+    profile: Option<&str>,
+) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    let interval_ref = |interval: &TimeInterval| IntervalRef {
+        id: interval.id,
+        name: interval.name.clone(),
+        profile: profile.map(str::to_string),
+    };
+
+    for (idx, interval) in intervals.iter().enumerate() {
+        // Конец раньше начала означает переход через полночь, но нулевая длительность недопустима
+        if interval.duration_minutes() == 0 {
+            issues.push(ValidationIssue::ZeroLength {
+                interval: interval_ref(interval),
+                time: interval.start,
+            });
+        }
+
+        // Каждая пара пересекающихся интервалов - одна проблема
+        for other in &intervals[idx + 1..] {
+            if !interval.overlaps(other) {
+                continue;
+            }
+            // Импортированный из календаря интервал называем первым
+            issues.push(match (&interval.source, &other.source) {
+                (Some(source), None) => ValidationIssue::CalendarOverlap {
+                    imported: interval_ref(interval),
+                    source: source.clone(),
+                    other: interval_ref(other),
+                },
+                (None, Some(source)) => ValidationIssue::CalendarOverlap {
+                    imported: interval_ref(other),
+                    source: source.clone(),
+                    other: interval_ref(interval),
+                },
+                _ => ValidationIssue::Overlap {
+                    first: interval_ref(interval),
+                    second: interval_ref(other),
+                },
+            });
+        }
+
+        match &interval.recurrence {
+            Recurrence::DateRange { from, to } if from > to => {
+                issues.push(ValidationIssue::InvertedDateRange {
+                    interval: interval_ref(interval),
+                    from: *from,
+                    to: *to,
+                });
+            }
+            Recurrence::Days { days } if days.is_empty() => {
+                issues.push(ValidationIssue::NoWeekdays {
+                    interval: interval_ref(interval),
+                });
+            }
+            Recurrence::Dates { dates } if dates.is_empty() => {
+                issues.push(ValidationIssue::NoDates {
+                    interval: interval_ref(interval),
+                });
+            }
+            _ => {}
+        }
+
+        let screen_exists = |id: u32| screens.iter().any(|s| s.id == id);
+        match &interval.mode {
+            IntervalMode::Static { screen_id } => {
+                if !screen_exists(*screen_id) {
+                    issues.push(ValidationIssue::MissingScreen {
+                        interval: interval_ref(interval),
+                        screen_id: *screen_id,
+                        step: None,
+                    });
+                }
+            }
+            IntervalMode::Cycle { steps } if steps.is_empty() => {
+                issues.push(ValidationIssue::EmptyCycle {
+                    interval: interval_ref(interval),
+                });
+            }
+            IntervalMode::Cycle { steps } => {
+                if steps.iter().all(|s| s.duration_minutes == 0) {
+                    issues.push(ValidationIssue::ZeroCycleDuration {
+                        interval: interval_ref(interval),
+                    });
+                }
+                for (step_idx, step) in steps.iter().enumerate() {
+                    if !screen_exists(step.screen_id) {
+                        issues.push(ValidationIssue::MissingScreen {
+                            interval: interval_ref(interval),
+                            screen_id: step.screen_id,
+                            step: Some(step_idx),
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    issues
+}
+
+/// Проверяет интервалы всех профилей. Интервалы разных профилей друг с другом
+/// не сравниваются: одновременно действует только один профиль.
+pub fn validate_profiles(cfg: &AppConfig) -> Vec<ValidationIssue> {
+    let named = cfg.profiles.len() > 1;
+    let mut issues: Vec<ValidationIssue> = cfg
+        .profiles
+        .iter()
+        .flat_map(|profile| {
+            let name = named.then_some(profile.name.as_str());
+            check_intervals(&profile.intervals, &cfg.screens, name)
+        })
+        .collect();
+
+    for profile in &cfg.profiles {
+        if let Some(screen_id) = profile.default_screen_id
+            && !cfg.screens.iter().any(|s| s.id == screen_id)
+        {
+            issues.push(ValidationIssue::MissingDefaultScreen {
+                profile: profile.name.clone(),
+                screen_id,
+            });
+        }
+    }
+
+    // Один день недели может включать только один профиль
+    for (idx, profile) in cfg.profiles.iter().enumerate() {
+        for other in &cfg.profiles[idx + 1..] {
+            if let Some(day) = profile
+                .auto_days
+                .iter()
+                .find(|d| other.auto_days.contains(d))
+            {
+                issues.push(ValidationIssue::ProfileDayConflict {
+                    first: profile.name.clone(),
+                    second: other.name.clone(),
+                    day: *day,
+                });
+            }
+        }
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Rgba8;
+
+    fn screens() -> Vec<ScreenConfig> {
+        vec![ScreenConfig {
+            id: 1,
+            title: "Работа".into(),
+            subtitle: String::new(),
+            color: Rgba8 {
+                r: 0,
+                g: 0,
+                b: 0,
+                a: 255,
+            },
+            sound: None,
+            notify: true,
+        }]
+    }
+
+    fn interval(id: u32, start: u8, end: u8, recurrence: Recurrence) -> TimeInterval {
+        TimeInterval {
+            id,
+            name: format!("Интервал {}", id),
+            start: TimeOfDay {
+                hour: start,
+                minute: 0,
+            },
+            end: TimeOfDay {
+                hour: end,
+                minute: 0,
+            },
+            mode: IntervalMode::Static { screen_id: 1 },
+            recurrence,
+            warning_minutes: None,
+            source: None,
+        }
+    }
+
+    fn codes(intervals: &[TimeInterval]) -> Vec<(&'static str, Vec<u32>)> {
+        validate_intervals(intervals, &screens())
+            .iter()
+            .map(|issue| (issue.code(), issue.interval_ids()))
+            .collect()
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn overlap_is_reported_once_per_pair() {
+        let intervals = [
+            interval(1, 9, 12, Recurrence::Daily),
+            interval(2, 11, 13, Recurrence::Daily),
+            interval(3, 12, 14, Recurrence::Daily),
+        ];
+        assert_eq!(
+            codes(&intervals),
+            vec![("overlap", vec![1, 2]), ("overlap", vec![2, 3])]
+        );
+    }
+
+    #[test]
+    fn intervals_on_different_days_do_not_overlap() {
+        let intervals = [
+            interval(1, 9, 12, Recurrence::Weekdays),
+            interval(2, 9, 12, Recurrence::Weekends),
+            interval(
+                3,
+                9,
+                12,
+                Recurrence::Dates {
+                    dates: vec![date("2026-06-13")],
+                },
+            ),
+        ];
+        // 2026-06-13 - суббота: пересекается только с выходными
+        assert_eq!(codes(&intervals), vec![("overlap", vec![2, 3])]);
+
+        let intervals = [
+            interval(
+                1,
+                9,
+                12,
+                Recurrence::DateRange {
+                    from: date("2026-06-01"),
+                    to: date("2026-06-30"),
+                },
+            ),
+            interval(
+                2,
+                10,
+                11,
+                Recurrence::Days {
+                    days: vec![Weekday::Mon],
+                },
+            ),
+            interval(
+                3,
+                10,
+                11,
+                Recurrence::Dates {
+                    dates: vec![date("2026-07-07")],
+                },
+            ),
+        ];
+        assert_eq!(codes(&intervals), vec![("overlap", vec![1, 2])]);
+    }
+
+    #[test]
+    fn night_interval_overlaps_next_morning() {
+        let intervals = [
+            interval(
+                1,
+                22,
+                2,
+                Recurrence::Days {
+                    days: vec![Weekday::Fri],
+                },
+            ),
+            interval(2, 1, 3, Recurrence::Weekends),
+            interval(3, 1, 3, Recurrence::Weekdays),
+        ];
+        // Пятничная ночь заходит в субботу, но не в пятничное утро
+        assert_eq!(codes(&intervals), vec![("overlap", vec![1, 2])]);
+    }
+
+    #[test]
+    fn broken_recurrences_are_errors() {
+        let intervals = [
+            interval(
+                1,
+                9,
+                10,
+                Recurrence::DateRange {
+                    from: date("2026-06-30"),
+                    to: date("2026-06-01"),
+                },
+            ),
+            interval(2, 11, 12, Recurrence::Days { days: Vec::new() }),
+            interval(3, 13, 14, Recurrence::Dates { dates: Vec::new() }),
+        ];
+        let issues = validate_intervals(&intervals, &screens());
+        assert_eq!(
+            issues.iter().map(|i| i.code()).collect::<Vec<_>>(),
+            vec!["date-range", "no-weekdays", "no-dates"]
+        );
+        assert!(issues.iter().all(|i| i.severity() == Severity::Error));
+    }
+}
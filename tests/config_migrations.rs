@@ -0,0 +1,257 @@
+//! Миграции config.json: каждый прежний формат из `tests/fixtures/config/`
+//! должен обновляться до текущей версии без потери данных.
+
+use chrono::{NaiveDate, Weekday};
+use flow_timer::{
+    config::{
+        AutostartMethod, Chime, ConfigError, IntervalMode, PauseMode, Recurrence, parse_config,
+    },
+    migrations::{CURRENT_SCHEMA_VERSION, MigrationError, migrate, migrate_to, schema_version},
+};
+use serde_json::{Value, json};
+
+const V0_BASELINE: &str = include_str!("fixtures/config/v0_baseline.json");
+const V0_UNVERSIONED: &str = include_str!("fixtures/config/v0_unversioned.json");
+const V1: &str = include_str!("fixtures/config/v1.json");
+const V2: &str = include_str!("fixtures/config/v2.json");
+
+/// Все прежние форматы и их версии
+const FIXTURES: [(&str, &str, u32); 4] = [
+    ("v0_baseline", V0_BASELINE, 0),
+    ("v0_unversioned", V0_UNVERSIONED, 0),
+    ("v1", V1, 1),
+    ("v2", V2, 2),
+];
+
+fn value(fixture: &str) -> Value {
+    serde_json::from_str(fixture).expect("fixture is valid JSON")
+}
+
+#[test]
+fn every_fixture_reaches_current_version() {
+    for (name, fixture, version) in FIXTURES {
+        let mut config = value(fixture);
+        assert_eq!(schema_version(&config), Ok(version), "{}", name);
+
+        assert_eq!(migrate(&mut config), Ok(version), "{}", name);
+        assert_eq!(
+            schema_version(&config),
+            Ok(CURRENT_SCHEMA_VERSION),
+            "{}",
+            name
+        );
+
+        let parsed = parse_config(fixture.as_bytes())
+            .unwrap_or_else(|err| panic!("{} does not parse: {}", name, err));
+        assert_eq!(parsed.schema_version, CURRENT_SCHEMA_VERSION, "{}", name);
+    }
+}
+
+#[test]
+fn migrating_current_version_changes_nothing() {
+    let mut config = value(V2);
+    migrate(&mut config).unwrap();
+    assert_eq!(config, value(V2));
+}
+
+#[test]
+fn v0_to_v1_only_stamps_the_version() {
+    for fixture in [V0_BASELINE, V0_UNVERSIONED] {
+        let mut config = value(fixture);
+        migrate_to(&mut config, 1).unwrap();
+
+        let mut expected = value(fixture);
+        expected["schema_version"] = json!(1);
+        assert_eq!(config, expected);
+    }
+}
+
+#[test]
+fn v0_baseline_keeps_data_and_gets_defaults_for_new_fields() {
+    let config = parse_config(V0_BASELINE.as_bytes()).unwrap();
+
+    assert_eq!(config.language.to_string(), "ru-RU");
+    assert_eq!(config.default_screen_id(), Some(1));
+    let ids: Vec<u32> = config.screens.iter().map(|s| s.id).collect();
+    assert_eq!(ids, [1, 2, 3]);
+    assert_eq!(config.screens[1].title, "Перерыв");
+    assert!(config.screens.iter().all(|s| s.sound.is_none() && s.notify));
+
+    assert_eq!(config.intervals().len(), 2);
+    assert!(
+        config
+            .intervals()
+            .iter()
+            .all(|i| i.recurrence == Recurrence::Daily && i.warning_minutes.is_none())
+    );
+    let IntervalMode::Cycle { steps } = &config.intervals()[1].mode else {
+        panic!("second interval is a cycle");
+    };
+    assert_eq!(steps.len(), 2);
+    assert_eq!(steps[0].duration_minutes, 25);
+
+    let settings = &config.system_settings;
+    assert_eq!(settings.autostart_method, AutostartMethod::Xdg);
+    assert_eq!(settings.pause_mode, PauseMode::ShiftSteps);
+    assert_eq!(settings.extend_step_minutes, 5);
+    assert!(!settings.warnings.enabled);
+    let position = settings.window_position.as_ref().unwrap();
+    assert_eq!((position.x, position.y), (120.0, 80.0));
+    assert_eq!(position.size, None);
+}
+
+#[test]
+fn v0_unversioned_keeps_every_later_field() {
+    let config = parse_config(V0_UNVERSIONED.as_bytes()).unwrap();
+
+    assert_eq!(
+        config.screens[1].sound.as_deref(),
+        Some(std::path::Path::new("/home/user/sounds/break.ogg"))
+    );
+    assert!(!config.screens[2].notify);
+    let intervals = config.intervals();
+    assert_eq!(intervals[0].recurrence, Recurrence::Weekdays);
+    assert_eq!(intervals[0].warning_minutes, Some(5));
+    assert!(intervals[2].crosses_midnight());
+    assert!(matches!(
+        intervals[2].recurrence,
+        Recurrence::DateRange { .. }
+    ));
+
+    let settings = &config.system_settings;
+    assert_eq!(settings.autostart_method, AutostartMethod::Systemd);
+    assert!(settings.desktop_notifications);
+    assert_eq!(settings.pause_mode, PauseMode::ExtendInterval);
+    assert_eq!(settings.extend_step_minutes, 10);
+    assert_eq!(settings.sound.chime, Chime::Bell);
+    assert!(settings.warnings.enabled);
+    assert_eq!(settings.warnings.lead_minutes, 3);
+}
+
+#[test]
+fn saved_config_is_written_in_current_version() {
+    let config = parse_config(V0_BASELINE.as_bytes()).unwrap();
+    let saved = serde_json::to_value(&config).unwrap();
+    assert_eq!(saved["schema_version"], json!(CURRENT_SCHEMA_VERSION));
+}
+
+#[test]
+fn newer_version_is_rejected() {
+    let mut config = value(V2);
+    config["schema_version"] = json!(CURRENT_SCHEMA_VERSION + 1);
+
+    let expected = MigrationError::UnsupportedVersion(CURRENT_SCHEMA_VERSION as u64 + 1);
+    assert_eq!(migrate(&mut config.clone()), Err(expected));
+    let bytes = serde_json::to_vec(&config).unwrap();
+    assert!(matches!(
+        parse_config(&bytes),
+        Err(ConfigError::Migration(MigrationError::UnsupportedVersion(
+            _
+        )))
+    ));
+}
+
+#[test]
+fn malformed_input_is_reported() {
+    assert_eq!(
+        migrate(&mut json!([1, 2])),
+        Err(MigrationError::NotAnObject)
+    );
+    assert_eq!(
+        migrate(&mut json!({ "schema_version": "1" })),
+        Err(MigrationError::InvalidVersion(json!("1")))
+    );
+
+    let mut config = value(V0_BASELINE);
+    config.as_object_mut().unwrap().remove("intervals");
+    assert_eq!(
+        migrate(&mut config),
+        Err(MigrationError::InvalidField {
+            path: "intervals".into(),
+            expected: "массив",
+        })
+    );
+}
+
+#[test]
+fn v1_to_v2_moves_intervals_into_the_first_profile() {
+    let v1 = value(V1);
+    let mut config = v1.clone();
+    assert_eq!(migrate_to(&mut config, 2), Ok(1));
+
+    assert_eq!(config["schema_version"], json!(2));
+    assert!(config.get("intervals").is_none());
+    assert!(config.get("default_screen_id").is_none());
+    assert_eq!(config["active_profile_id"], json!(1));
+    assert_eq!(
+        config["profiles"],
+        json!([{
+            "id": 1,
+            "name": "Default",
+            "intervals": v1["intervals"],
+            "default_screen_id": v1["default_screen_id"],
+            "auto_days": [],
+        }])
+    );
+    // Остальные разделы не меняются
+    assert_eq!(config["screens"], v1["screens"]);
+    assert_eq!(config["system_settings"], v1["system_settings"]);
+}
+
+#[test]
+fn v1_to_v2_names_the_profile_in_the_config_language() {
+    let mut config = value(V0_BASELINE);
+    migrate(&mut config).unwrap();
+    assert_eq!(config["profiles"][0]["name"], json!("Основной"));
+    assert_eq!(config["profiles"][0]["default_screen_id"], json!(1));
+}
+
+#[test]
+fn v2_profiles_keep_their_own_intervals() {
+    let mut config = parse_config(V2.as_bytes()).unwrap();
+
+    let names: Vec<&str> = config.profiles.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["Office day", "Deep work", "Conference"]);
+    assert_eq!(config.active_profile().name, "Deep work");
+    assert_eq!(config.intervals().len(), 1);
+    assert_eq!(config.default_screen_id(), Some(3));
+    assert_eq!(config.all_intervals().count(), 4);
+
+    assert_eq!(config.find_profile("office DAY").map(|p| p.id), Some(1));
+    assert_eq!(config.find_profile("3").map(|p| p.id), Some(3));
+    assert!(config.set_active_profile(3));
+    assert!(config.intervals().is_empty());
+    assert!(!config.set_active_profile(42));
+}
+
+#[test]
+fn auto_profile_is_applied_once_a_day() {
+    let mut config = parse_config(V2.as_bytes()).unwrap();
+    let monday = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+    assert_eq!(monday.format("%a").to_string(), "Mon");
+
+    assert!(config.apply_auto_profile(monday));
+    assert_eq!(config.active_profile().name, "Office day");
+
+    // Ручное переключение действует до конца дня
+    config.set_active_profile(3);
+    assert!(!config.apply_auto_profile(monday));
+    assert_eq!(config.active_profile_id, 3);
+
+    // В день без назначенного профиля остаётся выбранный
+    let sunday = NaiveDate::from_ymd_opt(2026, 10, 25).unwrap();
+    assert!(config.apply_auto_profile(sunday));
+    assert_eq!(config.active_profile_id, 3);
+    assert_eq!(config.profiles[1].auto_days, [Weekday::Wed, Weekday::Fri]);
+}
+
+#[test]
+fn empty_profile_list_gets_a_default_profile() {
+    let mut config = value(V2);
+    config["profiles"] = json!([]);
+    let bytes = serde_json::to_vec(&config).unwrap();
+
+    let config = parse_config(&bytes).unwrap();
+    assert_eq!(config.profiles.len(), 1);
+    assert_eq!(config.active_profile_id, config.profiles[0].id);
+}